@@ -6,3 +6,5 @@ pub mod queue;
 pub use queue::*;
 pub mod oracle;
 pub use oracle::*;
+pub mod randomness;
+pub use randomness::*;
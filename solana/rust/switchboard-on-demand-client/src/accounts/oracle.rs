@@ -85,6 +85,22 @@ impl OracleAccountData {
         .0
     }
 
+    /// The uncompressed secp256k1 key the oracle's enclave signs with, without
+    /// the `0x04` tag, or `None` if the oracle has not registered one.
+    pub fn secp256k1_signer(&self) -> Option<[u8; 64]> {
+        let key = self.enclave.secp256k1_signer;
+        if key == [0u8; 64] {
+            return None;
+        }
+        Some(key)
+    }
+
+    pub fn libsecp256k1_signer(&self) -> Option<libsecp256k1::PublicKey> {
+        let mut bytes = [4u8; 65];
+        bytes[1..].copy_from_slice(&self.secp256k1_signer()?);
+        libsecp256k1::PublicKey::parse(&bytes).ok()
+    }
+
     pub fn gateway_uri(&self) -> Option<String> {
         let uri = self.gateway_uri;
         let uri = String::from_utf8_lossy(&uri);
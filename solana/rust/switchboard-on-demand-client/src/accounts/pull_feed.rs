@@ -2,6 +2,7 @@ use crate::*;
 use anyhow_ext::Context;
 use bytemuck;
 use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
 use solana_sdk::pubkey::Pubkey;
 
 pub const PRECISION: u32 = 18;
@@ -162,6 +163,66 @@ impl PullFeedAccountData {
         hex::encode(self.feed_hash)
     }
 
+    /// Generate a checksum for the given feed hash, result, slothash, max_variance and min_responses
+    /// This is signed by the oracle and used to verify that the data submitted by the oracles is valid.
+    pub fn generate_checksum(&self, result: i128, slothash: [u8; 32]) -> [u8; 32] {
+        Self::generate_checksum_inner(
+            self.queue,
+            self.feed_hash,
+            result,
+            slothash,
+            self.max_variance,
+            self.min_responses,
+        )
+    }
+
+    /// Generate a checksum for the given feed hash, result, slothash, max_variance and min_responses
+    /// This is signed by the oracle and used to verify that the data submitted by the oracles is valid.
+    pub fn generate_checksum_inner(
+        queue: Pubkey,
+        feed_hash: [u8; 32],
+        result: i128,
+        slothash: [u8; 32],
+        max_variance: u64,
+        min_responses: u32,
+    ) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(queue.to_bytes());
+        hasher.update(feed_hash);
+        hasher.update(result.to_le_bytes());
+        hasher.update(slothash);
+        hasher.update(max_variance.to_le_bytes());
+        hasher.update(min_responses.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Generate a checksum for the given feed hash, result, slothash, max_variance, min_responses
+    /// and timestamp, for oracles signing with the timestamp.
+    pub fn generate_checksum_with_timestamp(
+        queue: Pubkey,
+        feed_hash: [u8; 32],
+        result: i128,
+        slothash: [u8; 32],
+        max_variance: u64,
+        min_responses: u32,
+        timestamp: u64,
+    ) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(queue.to_bytes());
+        hasher.update(feed_hash);
+        hasher.update(result.to_le_bytes());
+        hasher.update(slothash);
+        hasher.update(max_variance.to_le_bytes());
+        hasher.update(min_responses.to_le_bytes());
+        hasher.update(timestamp.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Whether the feed's `feed_hash` matches `jobs` on the feed's queue.
+    pub fn matches_jobs(&self, jobs: &[OracleJob]) -> bool {
+        compute_feed_hash(&self.queue, jobs) == self.feed_hash
+    }
+
     pub async fn fetch_jobs(
         &self,
         crossbar: &CrossbarClient,
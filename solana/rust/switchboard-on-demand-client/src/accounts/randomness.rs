@@ -0,0 +1,58 @@
+use crate::*;
+use anyhow_ext::anyhow;
+use anyhow_ext::Error as AnyhowError;
+use bytemuck;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct RandomnessAccountData {
+    pub authority: Pubkey,
+    pub queue: Pubkey,
+
+    pub seed_slothash: [u8; 32],
+    pub seed_slot: u64,
+    pub oracle: Pubkey,
+
+    pub reveal_slot: u64,
+    pub value: [u8; 32],
+
+    _ebuf2: [u8; 96],
+    _ebuf1: [u8; 128],
+}
+
+impl RandomnessAccountData {
+    pub fn size() -> usize {
+        8 + std::mem::size_of::<RandomnessAccountData>()
+    }
+
+    /// Loads the RandomnessAccountData from the given key.
+    pub async fn load(
+        client: &RpcClient,
+        key: &Pubkey,
+    ) -> Result<RandomnessAccountData, AnyhowError> {
+        let account = client.get_account_data(key).await?;
+        let buf = account[8..].to_vec();
+        let parsed: &RandomnessAccountData = bytemuck::try_from_bytes(&buf)
+            .map_err(|e| anyhow!("Failed to parse RandomnessAccountData: {:?}", e))?;
+        Ok(*parsed)
+    }
+
+    /// Returns true once the randomness has been revealed for its latest commit.
+    pub fn is_revealed(&self) -> bool {
+        self.reveal_slot != 0 && self.reveal_slot >= self.seed_slot
+    }
+
+    pub fn reward_escrow(key: &Pubkey) -> Pubkey {
+        get_associated_token_address(key, &NATIVE_MINT)
+    }
+
+    pub fn stats_key(oracle: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[ORACLE_RANDOMNESS_STATS_SEED, &oracle.to_bytes()],
+            &SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+        )
+        .0
+    }
+}
@@ -0,0 +1,529 @@
+use crate::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::fmt;
+
+/// The errors the on-demand program raises when aggregating a submission.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AggregationError {
+    /// Fewer oracles responded successfully than the feed's `min_responses`.
+    InsufficientResponses { required: u32, received: usize },
+    /// The successful responses vary by more than the feed's `max_variance`.
+    /// Both are in percent of the median response, scaled by 1e9 as in the
+    /// feed account.
+    ExcessiveVariance { variance: u64, max_variance: u64 },
+    /// Fewer submissions are within `max_staleness` than the feed's
+    /// `min_sample_size`.
+    NotEnoughSamples { required: u8, valid: usize },
+    /// The responses were signed after `landed_at`.
+    FutureSlot { signed_slot: u64, landed_at: u64 },
+    /// A statistic of the submissions does not fit the result.
+    IntegerOverflow,
+}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AggregationError::InsufficientResponses { required, received } => write!(
+                f,
+                "{} successful responses, {} required",
+                received, required
+            ),
+            AggregationError::ExcessiveVariance {
+                variance,
+                max_variance,
+            } => write!(
+                f,
+                "Response variance {} exceeds max variance {}",
+                variance, max_variance
+            ),
+            AggregationError::NotEnoughSamples { required, valid } => {
+                write!(f, "{} valid samples, {} required", valid, required)
+            }
+            AggregationError::FutureSlot {
+                signed_slot,
+                landed_at,
+            } => write!(
+                f,
+                "Responses signed at slot {} land at earlier slot {}",
+                signed_slot, landed_at
+            ),
+            AggregationError::IntegerOverflow => write!(f, "Integer overflow"),
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+/// Predicts the result the on-demand program computes when `responses`,
+/// signed at `signed_slot`, land on `feed` at slot `landed_at`.
+///
+/// Failed responses are skipped, as the program skips their placeholder
+/// value. Each successful response replaces the submission of the same
+/// oracle, or takes the first empty or else the oldest submission. The
+/// result is aggregated over the submissions signed within `max_staleness`
+/// slots of `landed_at`. Its median is the program's lower bound median, the
+/// value at index `(len - 1) / 2` of the sorted samples.
+///
+/// The mean, variance and standard deviation are computed in 18 decimal
+/// fixed point integer math, rounding down, as the program does.
+/// # Returns
+/// * `Result<CurrentResult, AggregationError>` - The feed's new result, or the
+///   error the program would fail the submission with
+pub fn predict_result(
+    feed: &PullFeedAccountData,
+    responses: &[OracleResponse],
+    signed_slot: u64,
+    landed_at: u64,
+) -> Result<CurrentResult, AggregationError> {
+    if signed_slot > landed_at {
+        return Err(AggregationError::FutureSlot {
+            signed_slot,
+            landed_at,
+        });
+    }
+    let values = responses
+        .iter()
+        .filter_map(|response| Some((response.oracle, response.value?)))
+        .map(|(oracle, mut value)| {
+            value.rescale(PRECISION);
+            (oracle, value.mantissa())
+        })
+        .collect::<Vec<_>>();
+    if values.len() < feed.min_responses as usize {
+        return Err(AggregationError::InsufficientResponses {
+            required: feed.min_responses,
+            received: values.len(),
+        });
+    }
+    check_variance(
+        &values.iter().map(|(_, value)| *value).collect::<Vec<_>>(),
+        feed.max_variance,
+    )?;
+
+    let mut submissions = feed.submissions;
+    for (oracle, value) in values {
+        insert_submission(
+            &mut submissions,
+            OracleSubmission {
+                oracle,
+                slot: signed_slot,
+                landed_at,
+                value,
+            },
+        );
+    }
+
+    let min_slot = landed_at.saturating_sub(feed.max_staleness as u64);
+    let mut samples: Vec<OracleSubmission> = submissions
+        .iter()
+        .take_while(|submission| !submission.is_empty())
+        .filter(|submission| submission.slot >= min_slot)
+        .copied()
+        .collect();
+    if samples.is_empty() || samples.len() < feed.min_sample_size as usize {
+        return Err(AggregationError::NotEnoughSamples {
+            required: feed.min_sample_size,
+            valid: samples.len(),
+        });
+    }
+    aggregate(&mut samples)
+}
+
+/// Inserts `submission` the way the program does, keeping the empty
+/// submissions at the end.
+fn insert_submission(submissions: &mut [OracleSubmission], submission: OracleSubmission) {
+    let idx = submissions
+        .iter()
+        .position(|x| !x.is_empty() && x.oracle == submission.oracle)
+        .or_else(|| submissions.iter().position(|x| x.is_empty()))
+        .or_else(|| {
+            submissions
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, x)| x.slot)
+                .map(|(idx, _)| idx)
+        });
+    if let Some(idx) = idx {
+        submissions[idx] = submission;
+    }
+}
+
+/// Fails if the range of `values` exceeds `max_variance` percent of their
+/// median, both scaled by 1e9. A `max_variance` of 0 is not checked.
+fn check_variance(values: &[i128], max_variance: u64) -> Result<(), AggregationError> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let (Some(min), Some(max)) = (sorted.first(), sorted.last()) else {
+        return Ok(());
+    };
+    if max_variance == 0 || min == max {
+        return Ok(());
+    }
+    let median = sorted[lower_bound_median(sorted.len())];
+    let variance = if median == 0 {
+        u64::MAX
+    } else {
+        let decimal = |value: Option<i128>| {
+            value
+                .and_then(|value| Decimal::try_from_i128_with_scale(value, PRECISION).ok())
+                .ok_or(AggregationError::IntegerOverflow)
+        };
+        let range = decimal(max.checked_sub(*min))?;
+        let median = decimal(Some(median))?.abs();
+        range
+            .checked_div(median)
+            .and_then(|ratio| ratio.checked_mul(Decimal::from(100_000_000_000u64)))
+            .and_then(|variance| variance.to_u64())
+            .unwrap_or(u64::MAX)
+    };
+    if variance > max_variance {
+        return Err(AggregationError::ExcessiveVariance {
+            variance,
+            max_variance,
+        });
+    }
+    Ok(())
+}
+
+/// The result of the non-empty `samples`.
+fn aggregate(samples: &mut [OracleSubmission]) -> Result<CurrentResult, AggregationError> {
+    samples.sort_by_key(|x| x.value);
+    let median = samples[lower_bound_median(samples.len())];
+    let min_value = samples[0].value;
+    let max_value = samples[samples.len() - 1].value;
+    let num_samples = samples.len() as i128;
+
+    let sum = samples
+        .iter()
+        .try_fold(0i128, |sum, x| sum.checked_add(x.value))
+        .ok_or(AggregationError::IntegerOverflow)?;
+    let mean = sum / num_samples;
+    let squared_deviations = samples
+        .iter()
+        .map(|x| {
+            x.value
+                .checked_sub(mean)
+                .and_then(|d| checked_mul_div(d, d, scale()))
+        })
+        .try_fold(0i128, |sum, x| sum.checked_add(x?))
+        .ok_or(AggregationError::IntegerOverflow)?;
+    let variance = squared_deviations / num_samples;
+    let std_dev = checked_sqrt(variance).ok_or(AggregationError::IntegerOverflow)?;
+
+    let mut result: CurrentResult = bytemuck::Zeroable::zeroed();
+    result.value = median.value;
+    result.std_dev = std_dev;
+    result.mean = mean;
+    result.range = max_value
+        .checked_sub(min_value)
+        .ok_or(AggregationError::IntegerOverflow)?;
+    result.min_value = min_value;
+    result.max_value = max_value;
+    result.num_samples = samples.len() as u8;
+    result.slot = median.slot;
+    result.min_slot = samples.iter().map(|x| x.slot).min().unwrap_or_default();
+    result.max_slot = samples.iter().map(|x| x.slot).max().unwrap_or_default();
+    Ok(result)
+}
+
+/// The index of the program's median of `len` sorted values: the lower of
+/// the two middle values when `len` is even.
+fn lower_bound_median(len: usize) -> usize {
+    len.saturating_sub(1) / 2
+}
+
+fn scale() -> i128 {
+    10i128.pow(PRECISION)
+}
+
+/// The square root of the 18 decimal `value`, rounded down, or `None` if it
+/// is negative.
+fn checked_sqrt(value: i128) -> Option<i128> {
+    if value < 0 {
+        return None;
+    }
+    // sqrt(value / 1e18) * 1e18 == sqrt(value * 1e18)
+    let (hi, lo) = widening_mul(value as u128, scale() as u128);
+    i128::try_from(isqrt_wide(hi, lo)).ok()
+}
+
+/// Computes `a * b / denominator` rounded toward zero with a 256 bit
+/// intermediate product. Returns `None` if the denominator is zero or the
+/// result does not fit in an `i128`.
+fn checked_mul_div(a: i128, b: i128, denominator: i128) -> Option<i128> {
+    if denominator == 0 {
+        return None;
+    }
+    let negative = (a < 0) ^ (b < 0) ^ (denominator < 0);
+    let (hi, lo) = widening_mul(a.unsigned_abs(), b.unsigned_abs());
+    let quotient = div_wide(hi, lo, denominator.unsigned_abs())?;
+    if negative {
+        0i128.checked_sub_unsigned(quotient)
+    } else {
+        i128::try_from(quotient).ok()
+    }
+}
+
+// full 256 bit product of two u128s as (high, low) halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+// binary long division of a 256 bit value by a u128, None if the quotient overflows
+fn div_wide(hi: u128, lo: u128, denominator: u128) -> Option<u128> {
+    if hi >= denominator {
+        return None;
+    }
+    let mut rem = hi;
+    let mut quotient = 0u128;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || rem >= denominator {
+            rem = rem.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+// floor of the square root of a 256 bit value using Newton's method
+fn isqrt_wide(hi: u128, lo: u128) -> u128 {
+    if hi == 0 && lo < 2 {
+        return lo;
+    }
+    let bits = if hi == 0 {
+        128 - lo.leading_zeros()
+    } else {
+        256 - hi.leading_zeros()
+    };
+    // start above the root so the iteration decreases monotonically
+    let mut root = if bits >= 255 {
+        u128::MAX
+    } else {
+        1u128 << ((bits + 1) / 2)
+    };
+    loop {
+        let next = match div_wide(hi, lo, root) {
+            Some(quotient) => (root >> 1) + (quotient >> 1) + (root & quotient & 1),
+            None => return root,
+        };
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn feed() -> PullFeedAccountData {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.min_responses = 1;
+        feed.min_sample_size = 1;
+        feed.max_staleness = 100;
+        feed
+    }
+
+    fn response(oracle: Pubkey, value: Option<i64>) -> OracleResponse {
+        OracleResponse {
+            value: value.map(Decimal::from),
+            error: String::new(),
+            oracle,
+            signature: [0; 64],
+            recovery_id: 0,
+            timestamp: None,
+        }
+    }
+
+    fn scaled(value: i128) -> i128 {
+        value * 10i128.pow(PRECISION)
+    }
+
+    #[test]
+    fn test_predict_result_aggregates_new_and_valid_samples() {
+        let mut feed = feed();
+        let (stale, kept, replaced) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        feed.submissions[0] = OracleSubmission {
+            oracle: stale,
+            slot: 800,
+            landed_at: 800,
+            value: scaled(1_000),
+        };
+        feed.submissions[1] = OracleSubmission {
+            oracle: kept,
+            slot: 950,
+            landed_at: 950,
+            value: scaled(10),
+        };
+        feed.submissions[2] = OracleSubmission {
+            oracle: replaced,
+            slot: 950,
+            landed_at: 950,
+            value: scaled(500),
+        };
+        let responses = [
+            response(replaced, Some(12)),
+            response(Pubkey::new_unique(), Some(14)),
+            response(Pubkey::new_unique(), None),
+        ];
+
+        let result = predict_result(&feed, &responses, 1_000, 1_001).unwrap();
+
+        // The submission at slot 800 is stale and the failed response skipped.
+        assert_eq!(result.num_samples, 3);
+        assert_eq!(result.value, scaled(12));
+        assert_eq!(result.mean, scaled(12));
+        assert_eq!(result.range, scaled(4));
+        assert_eq!(
+            (result.min_value, result.max_value),
+            (scaled(10), scaled(14))
+        );
+        assert_eq!(result.slot, 1_000);
+        assert_eq!((result.min_slot, result.max_slot), (950, 1_000));
+        // sqrt(8 / 3)
+        assert_eq!(result.std_dev, 1_632_993_161_855_452_065);
+    }
+
+    #[test]
+    fn test_predict_result_takes_lower_bound_median_of_even_samples() {
+        let mut feed = feed();
+        let kept = Pubkey::new_unique();
+        feed.submissions[0] = OracleSubmission {
+            oracle: kept,
+            slot: 990,
+            landed_at: 990,
+            value: scaled(20),
+        };
+        let responses = [
+            response(Pubkey::new_unique(), Some(14)),
+            response(Pubkey::new_unique(), Some(10)),
+            response(Pubkey::new_unique(), Some(12)),
+        ];
+
+        let result = predict_result(&feed, &responses, 1_000, 1_000).unwrap();
+
+        assert_eq!(result.num_samples, 4);
+        // The lower of the middle values 12 and 14, and its slot.
+        assert_eq!(result.value, scaled(12));
+        assert_eq!(result.slot, 1_000);
+        assert_eq!(result.mean, scaled(14));
+        assert_eq!(result.range, scaled(10));
+        // sqrt((16 + 4 + 0 + 36) / 4)
+        assert_eq!(result.std_dev, 3_741_657_386_773_941_385);
+
+        // The median of two samples is the lower one.
+        let mut feed = self::feed();
+        feed.submissions[0] = OracleSubmission {
+            oracle: kept,
+            slot: 990,
+            landed_at: 990,
+            value: scaled(20),
+        };
+        let result =
+            predict_result(&feed, &[response(Pubkey::new_unique(), Some(10))], 995, 995).unwrap();
+        assert_eq!((result.value, result.slot), (scaled(10), 995));
+    }
+
+    #[test]
+    fn test_check_variance_uses_lower_bound_median() {
+        // A range of 10 is 100% of the lower median 10, but only 50% of the
+        // upper median 20.
+        let values = [scaled(10), scaled(20)];
+        assert_eq!(
+            check_variance(&values, 60_000_000_000),
+            Err(AggregationError::ExcessiveVariance {
+                variance: 100_000_000_000,
+                max_variance: 60_000_000_000
+            })
+        );
+        assert_eq!(check_variance(&values, 100_000_000_000), Ok(()));
+    }
+
+    #[test]
+    fn test_predict_result_replaces_oldest_submission_when_full() {
+        let mut feed = feed();
+        for (i, submission) in feed.submissions.iter_mut().enumerate() {
+            *submission = OracleSubmission {
+                oracle: Pubkey::new_unique(),
+                slot: 1_000 - i as u64,
+                landed_at: 1_000,
+                value: scaled(1),
+            };
+        }
+        let result = predict_result(
+            &feed,
+            &[response(Pubkey::new_unique(), Some(33))],
+            1_000,
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(result.num_samples, 32);
+        assert_eq!(result.max_value, scaled(33));
+        assert_eq!(result.min_slot, 970);
+    }
+
+    #[test]
+    fn test_predict_result_errors() {
+        let mut feed = feed();
+        feed.min_responses = 2;
+        let oracles = [Pubkey::new_unique(), Pubkey::new_unique()];
+        assert_eq!(
+            predict_result(&feed, &[response(oracles[0], Some(1))], 10, 10).unwrap_err(),
+            AggregationError::InsufficientResponses {
+                required: 2,
+                received: 1
+            }
+        );
+
+        // A 10% spread with a max variance of 5%.
+        feed.max_variance = 5_000_000_000;
+        let responses = [
+            response(oracles[0], Some(100)),
+            response(oracles[1], Some(110)),
+        ];
+        assert_eq!(
+            predict_result(&feed, &responses, 10, 10).unwrap_err(),
+            AggregationError::ExcessiveVariance {
+                variance: 10_000_000_000,
+                max_variance: 5_000_000_000
+            }
+        );
+
+        feed.max_variance = 0;
+        feed.min_sample_size = 3;
+        assert_eq!(
+            predict_result(&feed, &responses, 10, 10).unwrap_err(),
+            AggregationError::NotEnoughSamples {
+                required: 3,
+                valid: 2
+            }
+        );
+        assert_eq!(
+            predict_result(&feed, &responses, 11, 10).unwrap_err(),
+            AggregationError::FutureSlot {
+                signed_slot: 11,
+                landed_at: 10
+            }
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use dashmap::DashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+struct Entry<V> {
+    value: V,
+    created: Instant,
+}
+
+/// A map of lazily loaded values that expire `ttl` after they were loaded.
+///
+/// Concurrent loads of the same key share a single call of the loader. A
+/// failed load caches nothing, so the next call loads again.
+pub struct TtlCache<K, V> {
+    entries: DashMap<K, Arc<OnceCell<Entry<V>>>>,
+    ttl: Option<Duration>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    /// Creates an empty cache. With a `ttl` of `None` values never expire
+    /// and are only dropped by `invalidate` or `clear`.
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+        }
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// The cached value of `key`, unless it is missing, still loading or
+    /// expired.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let cell = self.entries.get(key)?;
+        let entry = cell.get()?;
+        (!self.is_expired(entry)).then(|| entry.value.clone())
+    }
+
+    /// Caches `value` for `key`, replacing any cached or loading value.
+    pub fn insert(&self, key: K, value: V) {
+        let entry = Entry {
+            value,
+            created: Instant::now(),
+        };
+        self.entries
+            .insert(key, Arc::new(OnceCell::new_with(Some(entry))));
+    }
+
+    /// The cached value of `key`, loading it with `load` if it is missing or
+    /// expired.
+    pub async fn get_or_try_init<F, Fut, E>(&self, key: K, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        let cell = self.cell(key);
+        let entry = cell
+            .get_or_try_init(|| async {
+                Ok(Entry {
+                    value: load().await?,
+                    created: Instant::now(),
+                })
+            })
+            .await?;
+        Ok(entry.value.clone())
+    }
+
+    /// Drops the value of `key`, so the next lookup loads it again.
+    /// Returns whether a value was cached or loading.
+    pub fn invalidate(&self, key: &K) -> bool {
+        self.entries.remove(key).is_some()
+    }
+
+    /// Drops every cached value.
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// The keys and values of the cached values that have not expired.
+    pub fn entries(&self) -> Vec<(K, V)> {
+        self.entries
+            .iter()
+            .filter_map(|item| {
+                let entry = item.value().get()?;
+                (!self.is_expired(entry)).then(|| (item.key().clone(), entry.value.clone()))
+            })
+            .collect()
+    }
+
+    /// The cell of `key`, replacing it if its value has expired. The map is
+    /// not locked while the cell is loaded.
+    fn cell(&self, key: K) -> Arc<OnceCell<Entry<V>>> {
+        let mut cell = self.entries.entry(key).or_default();
+        if matches!(cell.get(), Some(entry) if self.is_expired(entry)) {
+            *cell = Arc::default();
+        }
+        Arc::clone(cell.value())
+    }
+
+    fn is_expired(&self, entry: &Entry<V>) -> bool {
+        matches!(self.ttl, Some(ttl) if entry.created.elapsed() >= ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn load(cache: &TtlCache<u8, usize>, key: u8, loads: &AtomicUsize) -> usize {
+        cache
+            .get_or_try_init(key, || async {
+                Ok::<_, ()>(loads.fetch_add(1, Ordering::SeqCst) + 1)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_or_try_init_caches_until_invalidated() {
+        let cache = TtlCache::new(None);
+        let loads = AtomicUsize::new(0);
+        let (a, b) = tokio::join!(load(&cache, 1, &loads), load(&cache, 1, &loads));
+        assert_eq!((a, b), (1, 1));
+        assert_eq!(cache.get(&1), Some(1));
+
+        assert!(cache.invalidate(&1));
+        assert!(!cache.invalidate(&1));
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(load(&cache, 1, &loads).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_values_are_reloaded() {
+        let cache = TtlCache::new(Some(Duration::ZERO));
+        let loads = AtomicUsize::new(0);
+        assert_eq!(load(&cache, 1, &loads).await, 1);
+        assert_eq!(cache.get(&1), None);
+        assert!(cache.entries().is_empty());
+        assert_eq!(load(&cache, 1, &loads).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_load_is_not_cached() {
+        let cache: TtlCache<u8, usize> = TtlCache::new(None);
+        let err = cache
+            .get_or_try_init(1, || async { Err("unavailable") })
+            .await
+            .unwrap_err();
+        assert_eq!(err, "unavailable");
+        assert_eq!(cache.get(&1), None);
+
+        cache.insert(1, 7);
+        assert_eq!(cache.entries(), vec![(1, 7)]);
+    }
+}
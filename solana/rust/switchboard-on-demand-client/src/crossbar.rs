@@ -1,9 +1,9 @@
 #![allow(non_snake_case)]
-use anyhow_ext::anyhow;
-use anyhow_ext::Context;
-use anyhow_ext::Error as AnyhowError;
+use crate::ClientError;
 use base58::ToBase58;
 use reqwest::Client;
+use reqwest::Response as HttpResponse;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use solana_sdk::genesis_config::ClusterType;
 use solana_sdk::pubkey::Pubkey;
@@ -82,24 +82,16 @@ impl CrossbarClient {
     /// # Returns
     /// * `Result<serde_json::Value>` - The response from the crossbar gateway,
     ///   containing the json formatted oracle jobs
-    pub async fn fetch(&self, feed_hash: &str) -> Result<serde_json::Value, AnyhowError> {
+    pub async fn fetch(&self, feed_hash: &str) -> Result<serde_json::Value, ClientError> {
         let url = format!("{}/fetch/{}", self.crossbar_url, feed_hash);
         let resp = self
             .client
             .get(&url)
             .send()
             .await
-            .context("Failed to send fetch request")?;
+            .map_err(ClientError::crossbar_http)?;
 
-        let status = resp.status();
-        if !status.is_success() {
-            if self.verbose {
-                eprintln!("{}", resp.text().await.context("Failed to fetch response")?);
-            }
-            return Err(anyhow!("Bad status code {}", status.as_u16()));
-        }
-
-        resp.json().await.context("Failed to parse response")
+        self.parse_response(resp).await
     }
 
     /// Store feed jobs in the crossbar gateway to a pinned IPFS address
@@ -107,10 +99,12 @@ impl CrossbarClient {
         &self,
         queue_address: Pubkey,
         jobs: &[serde_json::Value],
-    ) -> Result<StoreResponse, AnyhowError> {
+    ) -> Result<StoreResponse, ClientError> {
         let queue = bs58::decode(queue_address.to_string())
             .into_vec()
-            .context("Failed to decode queue address")?;
+            .map_err(|e| {
+                ClientError::InvalidInput(format!("Failed to decode queue address: {}", e))
+            })?;
         let queue_hex = queue.to_base58();
         let payload = serde_json::json!({ "queue": queue_hex, "jobs": jobs });
 
@@ -122,21 +116,9 @@ impl CrossbarClient {
             .header("Content-Type", "application/json")
             .send()
             .await
-            .context("Failed to send store request")?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            if self.verbose {
-                eprintln!(
-                    "{}: {}",
-                    status,
-                    resp.text().await.context("Failed to fetch response")?
-                );
-            }
-            return Err(anyhow!("Bad status code {}", status.as_u16()));
-        }
+            .map_err(ClientError::crossbar_http)?;
 
-        resp.json().await.context("Failed to parse response")
+        self.parse_response(resp).await
     }
 
     pub async fn fetch_solana_updates(
@@ -144,9 +126,9 @@ impl CrossbarClient {
         network: ClusterType,
         feed_pubkeys: &[Pubkey],
         num_signatures: Option<usize>,
-    ) -> Result<Vec<FetchSolanaUpdatesResponse>, AnyhowError> {
+    ) -> Result<Vec<FetchSolanaUpdatesResponse>, ClientError> {
         if feed_pubkeys.is_empty() {
-            return Err(anyhow!("Feed pubkeys are empty"));
+            return Err(ClientError::InvalidInput("Feed pubkeys are empty".to_string()));
         }
 
         let feeds_param: Vec<_> = feed_pubkeys.iter().map(|x| x.to_string()).collect();
@@ -160,21 +142,14 @@ impl CrossbarClient {
             url.push_str(&format!("?numSignatures={}", num_signatures));
         }
 
-        let resp = self.client.get(&url).send().await?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            if self.verbose {
-                eprintln!(
-                    "{}: {}",
-                    status,
-                    resp.text().await.context("Failed to fetch response")?
-                );
-            }
-            return Err(anyhow!("Bad status code {}", status.as_u16()));
-        }
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(ClientError::crossbar_http)?;
 
-        resp.json().await.context("Failed to parse response")
+        self.parse_response(resp).await
     }
 
     /// Simulate feed responses from the crossbar gateway
@@ -182,9 +157,9 @@ impl CrossbarClient {
         &self,
         network: ClusterType,
         feed_pubkeys: &[Pubkey],
-    ) -> Result<Vec<SimulateSolanaFeedsResponse>, AnyhowError> {
+    ) -> Result<Vec<SimulateSolanaFeedsResponse>, ClientError> {
         if feed_pubkeys.is_empty() {
-            return Err(anyhow!("Feed pubkeys are empty"));
+            return Err(ClientError::InvalidInput("Feed pubkeys are empty".to_string()));
         }
 
         let feeds_param: Vec<_> = feed_pubkeys.iter().map(|x| x.to_string()).collect();
@@ -194,18 +169,14 @@ impl CrossbarClient {
             "{}/simulate/solana/{}/{}",
             self.crossbar_url, network, feeds_param
         );
-        let resp = self.client.get(&url).send().await?;
-
-        let status = resp.status();
-        let raw = resp.text().await.context("Failed to fetch response")?;
-        if !status.is_success() {
-            if self.verbose {
-                eprintln!("{}: {}", status, raw);
-            }
-            return Err(anyhow!("Bad status code {}", status.as_u16()));
-        }
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(ClientError::crossbar_http)?;
 
-        Ok(serde_json::from_str(&raw)?)
+        self.parse_response(resp).await
     }
 
     /// Simulate feed responses from the crossbar gateway
@@ -214,9 +185,9 @@ impl CrossbarClient {
     pub async fn simulate_feeds(
         &self,
         feed_hashes: &[&str],
-    ) -> Result<Vec<SimulateFeedsResponse>, AnyhowError> {
+    ) -> Result<Vec<SimulateFeedsResponse>, ClientError> {
         if feed_hashes.is_empty() {
-            return Err(anyhow!("Feed hashes are empty"));
+            return Err(ClientError::InvalidInput("Feed hashes are empty".to_string()));
         }
 
         let feeds_param = feed_hashes.join(",");
@@ -226,21 +197,30 @@ impl CrossbarClient {
             .get(&url)
             .send()
             .await
-            .context("Failed to send simulate feeds request")?;
+            .map_err(ClientError::crossbar_http)?;
 
+        self.parse_response(resp).await
+    }
+
+    /// Decode a crossbar response, surfacing non-success statuses as
+    /// `ClientError::CrossbarHttp` with the response body attached
+    async fn parse_response<T: DeserializeOwned>(
+        &self,
+        resp: HttpResponse,
+    ) -> Result<T, ClientError> {
         let status = resp.status();
+        let raw = resp.text().await.map_err(ClientError::crossbar_http)?;
         if !status.is_success() {
             if self.verbose {
-                eprintln!(
-                    "{}: {}",
-                    status,
-                    resp.text().await.context("Failed to fetch response")?
-                );
+                eprintln!("{}: {}", status, raw);
             }
-            return Err(anyhow!("Bad status code {}", status.as_u16()));
+            return Err(ClientError::CrossbarHttp {
+                status: Some(status.as_u16()),
+                body: raw,
+            });
         }
 
-        resp.json().await.context("Failed to parse response")
+        serde_json::from_str(&raw).map_err(|e| ClientError::CrossbarDecode(e.to_string()))
     }
 }
 
@@ -0,0 +1,264 @@
+use solana_client::client_error::ClientError as RpcClientError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+use std::fmt;
+
+/// The on-demand program errors a submission fails with when the oracles
+/// signed a checksum of a feed config that no longer matches the account,
+/// e.g. after its `feed_hash`, `max_variance` or `min_responses` changed:
+/// `InvalidSigner` and `InvalidSecpSignature`.
+pub const STALE_CONFIG_ERROR_CODES: [u32; 2] = [6005, 6016];
+
+/// A failed oracle response, identified by the oracle's pubkey as reported
+/// by the gateway.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OracleError {
+    pub oracle: String,
+    pub error: String,
+}
+
+/// The errors returned by the client's RPC, gateway and crossbar calls.
+///
+/// Use `is_transient` to decide whether a call is worth retrying, and
+/// `is_stale_config` whether to invalidate the `SbContext` caches first.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The RPC request failed.
+    RpcError(Box<RpcClientError>),
+    /// The account does not exist.
+    AccountNotFound(Pubkey),
+    /// The account exists but could not be parsed as the expected type.
+    InvalidAccountData { account: Pubkey, reason: String },
+    /// The gateway request failed. `status` is `None` if no response was
+    /// received, e.g. on a connection error or timeout.
+    GatewayHttp { status: Option<u16>, body: String },
+    /// The gateway responded with a body that could not be decoded.
+    GatewayDecode(String),
+    /// The crossbar request failed. `status` is `None` if no response was
+    /// received.
+    CrossbarHttp { status: Option<u16>, body: String },
+    /// The crossbar responded with a body that could not be decoded.
+    CrossbarDecode(String),
+    /// No oracle returned a usable value.
+    NoSuccessfulResponses { per_oracle_errors: Vec<OracleError> },
+    /// The address lookup table of the account is missing or invalid.
+    LutMissing(Pubkey),
+    /// An oracle signature, address or checksum could not be decoded.
+    SignatureDecode(String),
+    /// The arguments passed by the caller are invalid.
+    InvalidInput(String),
+    /// The transaction failed in simulation. `transaction_error` is the
+    /// error of the simulated transaction, if it failed, and `logs` are the
+    /// program logs of the simulation.
+    SimulationFailed {
+        error: String,
+        transaction_error: Option<TransactionError>,
+        logs: Vec<String>,
+    },
+    /// The transaction could not be compiled or signed.
+    TransactionBuild(String),
+    /// A file could not be read or written.
+    Io(std::io::Error),
+}
+
+impl ClientError {
+    /// Whether the error may resolve itself on retry, as opposed to errors
+    /// caused by bad account data or malformed responses.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::RpcError(_) | ClientError::NoSuccessfulResponses { .. } => true,
+            ClientError::GatewayHttp { status, .. } | ClientError::CrossbarHttp { status, .. } => {
+                match status {
+                    None => true,
+                    Some(status) => *status == 429 || *status >= 500,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the transaction failed in a way stale cached feed data or
+    /// lookup tables can cause: a missing or outdated lookup table, or one of
+    /// the `STALE_CONFIG_ERROR_CODES` raised by a Switchboard instruction.
+    /// `submit_ixs` are the positions of the Switchboard instructions in the
+    /// transaction, so errors of other programs are not mistaken for them.
+    pub fn is_stale_config(&self, submit_ixs: &[u8]) -> bool {
+        let error = match self {
+            ClientError::RpcError(err) => err.get_transaction_error(),
+            ClientError::SimulationFailed {
+                transaction_error, ..
+            } => transaction_error.clone(),
+            ClientError::LutMissing(_) => return true,
+            _ => None,
+        };
+        match error {
+            Some(
+                TransactionError::AddressLookupTableNotFound
+                | TransactionError::InvalidAddressLookupTableOwner
+                | TransactionError::InvalidAddressLookupTableData
+                | TransactionError::InvalidAddressLookupTableIndex,
+            ) => true,
+            Some(TransactionError::InstructionError(idx, InstructionError::Custom(code))) => {
+                submit_ixs.contains(&idx) && STALE_CONFIG_ERROR_CODES.contains(&code)
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn gateway_http(err: reqwest::Error) -> Self {
+        ClientError::GatewayHttp {
+            status: err.status().map(|status| status.as_u16()),
+            body: err.to_string(),
+        }
+    }
+
+    pub(crate) fn crossbar_http(err: reqwest::Error) -> Self {
+        ClientError::CrossbarHttp {
+            status: err.status().map(|status| status.as_u16()),
+            body: err.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::RpcError(err) => write!(f, "RPC error: {}", err),
+            ClientError::AccountNotFound(account) => write!(f, "Account not found: {}", account),
+            ClientError::InvalidAccountData { account, reason } => {
+                write!(f, "Invalid account data for {}: {}", account, reason)
+            }
+            ClientError::GatewayHttp { status, body } => match status {
+                Some(status) => write!(f, "Gateway returned status {}: {}", status, body),
+                None => write!(f, "Gateway request failed: {}", body),
+            },
+            ClientError::GatewayDecode(err) => write!(f, "Failed to decode gateway response: {}", err),
+            ClientError::CrossbarHttp { status, body } => match status {
+                Some(status) => write!(f, "Crossbar returned status {}: {}", status, body),
+                None => write!(f, "Crossbar request failed: {}", body),
+            },
+            ClientError::CrossbarDecode(err) => {
+                write!(f, "Failed to decode crossbar response: {}", err)
+            }
+            ClientError::NoSuccessfulResponses { per_oracle_errors } => {
+                write!(f, "No successful oracle responses")?;
+                for err in per_oracle_errors {
+                    write!(f, "; {}: {}", err.oracle, err.error)?;
+                }
+                Ok(())
+            }
+            ClientError::LutMissing(account) => {
+                write!(f, "Address lookup table missing for {}", account)
+            }
+            ClientError::SignatureDecode(err) => write!(f, "Failed to decode signature: {}", err),
+            ClientError::InvalidInput(err) => write!(f, "Invalid input: {}", err),
+            ClientError::SimulationFailed { error, .. } => {
+                write!(f, "Transaction simulation failed: {}", error)
+            }
+            ClientError::TransactionBuild(err) => {
+                write!(f, "Failed to build transaction: {}", err)
+            }
+            ClientError::Io(err) => write!(f, "IO error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::RpcError(err) => Some(err.as_ref()),
+            ClientError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<RpcClientError> for ClientError {
+    fn from(err: RpcClientError) -> Self {
+        ClientError::RpcError(Box::new(err))
+    }
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(err: std::io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        let http = |status| ClientError::GatewayHttp {
+            status,
+            body: String::new(),
+        };
+        assert!(http(None).is_transient());
+        assert!(http(Some(429)).is_transient());
+        assert!(http(Some(503)).is_transient());
+        assert!(!http(Some(400)).is_transient());
+        assert!(!ClientError::AccountNotFound(Pubkey::default()).is_transient());
+        assert!(!ClientError::GatewayDecode("bad json".to_string()).is_transient());
+    }
+
+    #[test]
+    fn test_is_stale_config() {
+        let simulation = |transaction_error| ClientError::SimulationFailed {
+            error: String::new(),
+            transaction_error,
+            logs: vec![],
+        };
+        let custom = |idx, code| {
+            simulation(Some(TransactionError::InstructionError(
+                idx,
+                InstructionError::Custom(code),
+            )))
+        };
+        let submit_ixs = [1, 3];
+        let is_stale = |err: ClientError| err.is_stale_config(&submit_ixs);
+        assert!(is_stale(simulation(Some(
+            TransactionError::AddressLookupTableNotFound
+        ))));
+        for code in STALE_CONFIG_ERROR_CODES {
+            assert!(is_stale(custom(3, code)));
+        }
+        let expired = simulation(Some(TransactionError::BlockhashNotFound));
+        assert!(!is_stale(expired));
+        assert!(!is_stale(simulation(None)));
+        assert!(is_stale(ClientError::LutMissing(Pubkey::default())));
+        assert!(!is_stale(ClientError::AccountNotFound(Pubkey::default())));
+    }
+
+    #[test]
+    fn test_other_custom_errors_are_not_stale_config() {
+        let custom = |idx, code| ClientError::SimulationFailed {
+            error: String::new(),
+            transaction_error: Some(TransactionError::InstructionError(
+                idx,
+                InstructionError::Custom(code),
+            )),
+            logs: vec![],
+        };
+        // An SPL token `InsufficientFunds` error of an instruction appended
+        // after the update.
+        assert!(!custom(2, 1).is_stale_config(&[1]));
+        // The same code as a stale config, raised by another program.
+        assert!(!custom(2, STALE_CONFIG_ERROR_CODES[0]).is_stale_config(&[1]));
+        // Another error of the Switchboard instruction.
+        assert!(!custom(1, 6000).is_stale_config(&[1]));
+    }
+
+    #[test]
+    fn test_no_successful_responses_display() {
+        let err = ClientError::NoSuccessfulResponses {
+            per_oracle_errors: vec![OracleError {
+                oracle: "abc".to_string(),
+                error: "timeout".to_string(),
+            }],
+        };
+        assert_eq!(err.to_string(), "No successful oracle responses; abc: timeout");
+    }
+}
@@ -0,0 +1,110 @@
+use crate::oracle_job::OracleJob;
+use crate::PullFeedAccountData;
+use anyhow_ext::anyhow;
+use anyhow_ext::Error as AnyhowError;
+use prost::Message;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+/// Computes the feed hash of `jobs` on `queue`, as returned by
+/// `CrossbarClient::store` and stored in `PullFeedAccountData.feed_hash`.
+///
+/// The hash is `sha256(queue || job_0 || job_1 || ...)`, where every job is
+/// its length delimited protobuf encoding. Job order matters, and so do
+/// optional fields that are set to their default value.
+pub fn compute_feed_hash(queue: &Pubkey, jobs: &[OracleJob]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(queue.to_bytes());
+    for job in jobs {
+        hasher.update(job.encode_length_delimited_to_vec());
+    }
+    hasher.finalize().into()
+}
+
+/// Checks that `feed` was configured with exactly `jobs` on its queue.
+pub fn verify_feed_hash(feed: &PullFeedAccountData, jobs: &[OracleJob]) -> Result<(), AnyhowError> {
+    let computed = compute_feed_hash(&feed.queue, jobs);
+    if computed != feed.feed_hash {
+        return Err(anyhow!(
+            "verify_feed_hash: Feed hash mismatch: on-chain {} computed {}",
+            hex::encode(feed.feed_hash),
+            hex::encode(computed)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle_job::oracle_job::*;
+    use std::str::FromStr;
+
+    // Vectors computed outside of prost, by a standalone Python script that
+    // writes the protobuf wire format of these jobs by hand (field tags from
+    // oracle_job.rs) and hashes them with hashlib.
+    const QUEUE: &str = "A43DyUGA7s8eXPxqEjJY6EBu1KKbNgfxF8h17VAHn13w";
+
+    fn http_job() -> OracleJob {
+        OracleJob {
+            tasks: vec![
+                HttpTask {
+                    url: Some("https://api.test/price".to_string()),
+                    ..Default::default()
+                }
+                .into(),
+                JsonParseTask {
+                    path: Some("$.price".to_string()),
+                    aggregation_method: None,
+                }
+                .into(),
+            ],
+            weight: None,
+        }
+    }
+
+    fn value_job() -> OracleJob {
+        OracleJob {
+            tasks: vec![ValueTask {
+                value: Some(value_task::Value::Value(1.5)),
+            }
+            .into()],
+            weight: None,
+        }
+    }
+
+    #[test]
+    fn matches_golden_vectors() {
+        let queue = Pubkey::from_str(QUEUE).unwrap();
+        let cases = [
+            (
+                vec![],
+                "67e58870a73d43724369eba15b8b948118a1f8496c1db116c51a9d6547ee9952",
+            ),
+            (
+                vec![http_job()],
+                "92e5cefe32691d9bea0aac8fdb219df206d13e49af6e65387202212b5b4f0221",
+            ),
+            (
+                vec![http_job(), value_job()],
+                "06aabbc069994e1fd676a0fb316d354190633b73e307e5226bbf9ec2ace056c1",
+            ),
+            (
+                vec![value_job(), http_job()],
+                "7354013f24fc0a5a33a4c01260e003110ad9e81e91c23382374aae4a93fe0927",
+            ),
+        ];
+        for (jobs, expected) in cases {
+            assert_eq!(hex::encode(compute_feed_hash(&queue, &jobs)), expected);
+        }
+    }
+
+    #[test]
+    fn builder_jobs_hash_like_hand_built_jobs() {
+        let job = OracleJob::builder()
+            .http("https://api.test/price")
+            .json_parse("$.price")
+            .build();
+        assert_eq!(job, http_job());
+    }
+}
@@ -1,8 +1,11 @@
 use crate::oracle_job::OracleJob;
+use crate::ClientError;
 use base64::prelude::*;
 use prost::Message;
 use reqwest::header::CONTENT_TYPE;
 use reqwest::Client;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -104,21 +107,21 @@ pub struct FetchSignaturesBatchResponse {
     pub errors: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RandomnessRevealResponse {
     pub signature: String,
     pub recovery_id: i32,
     pub value: Vec<u8>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AttestEnclaveResponse {
     pub guardian: String,
     pub signature: String,
     pub recovery_id: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PingResponse {
     pub oracle_pubkey: String,
     pub oracle_authority: String,
@@ -132,7 +135,7 @@ pub struct PingResponse {
     pub is_guardian: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FetchQuoteResponse {
     pub oracle_pubkey: String,
     pub queue: String,
@@ -143,7 +146,7 @@ pub struct FetchQuoteResponse {
     pub quote: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BridgeEnclaveResponse {
     pub guardian: String,
     pub oracle: String,
@@ -179,6 +182,10 @@ impl Gateway {
         }
     }
 
+    pub fn gateway_url(&self) -> &str {
+        &self.gateway_url
+    }
+
     /// Fetches signatures from the gateway
     /// # Arguments
     /// * `params` - FetchSignaturesParams
@@ -189,11 +196,11 @@ impl Gateway {
     /// * `params.min_responses` - The minimum number of responses
     /// * `params.use_timestamp` - Whether to use the timestamp
     /// # Returns
-    /// * `Result<FeedEvalResponseSingle, reqwest::Error>`
+    /// * `Result<FeedEvalResponseSingle, ClientError>`
     pub async fn fetch_signatures_from_encoded(
         &self,
         params: FetchSignaturesParams,
-    ) -> Result<FeedEvalResponseSingle, reqwest::Error> {
+    ) -> Result<FeedEvalResponseSingle, ClientError> {
         let url = format!("{}/gateway/api/v1/fetch_signatures", self.gateway_url);
         let body = serde_json::json!({
             "api_version": "1.0.0",
@@ -207,18 +214,7 @@ impl Gateway {
             "use_timestamp": params.use_timestamp.unwrap_or(false),
         });
 
-        let res = self
-            .client
-            .post(&url)
-            .header(CONTENT_TYPE, "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        let raw = res.text().await?;
-        let res = serde_json::from_str::<FeedEvalResponseSingle>(&raw).unwrap();
-
-        Ok(res)
+        self.post_json(&url, &body).await
     }
 
     /// Fetches signatures from the gateway using the multi-feed method
@@ -229,11 +225,11 @@ impl Gateway {
     /// * `params.num_signatures` - The number of signatures to fetch
     /// * `params.use_timestamp` - Whether to use the timestamp
     /// # Returns
-    /// * `Result<FetchSignaturesMultiResponse, reqwest::Error>`
+    /// * `Result<FetchSignaturesMultiResponse, ClientError>`
     pub async fn fetch_signatures_multi(
         &self,
         params: FetchSignaturesMultiParams,
-    ) -> Result<FetchSignaturesMultiResponse, reqwest::Error> {
+    ) -> Result<FetchSignaturesMultiResponse, ClientError> {
         let url = format!("{}/gateway/api/v1/fetch_signatures_multi", self.gateway_url);
         let mut feed_requests = vec![];
 
@@ -256,22 +252,13 @@ impl Gateway {
             "feed_requests": feed_requests,
         });
 
-        let res = self
-            .client
-            .post(&url)
-            .header(CONTENT_TYPE, "application/json")
-            .json(&body)
-            .send()
-            .await?;
-        let res = res.json::<FetchSignaturesMultiResponse>().await?;
-
-        Ok(res)
+        self.post_json(&url, &body).await
     }
 
     pub async fn fetch_signatures_batch(
         &self,
         params: FetchSignaturesBatchParams,
-    ) -> Result<FetchSignaturesBatchResponse, reqwest::Error> {
+    ) -> Result<FetchSignaturesBatchResponse, ClientError> {
         let url = format!("{}/gateway/api/v1/fetch_signatures_batch", self.gateway_url);
         let req = FetchSignaturesBatchRequest {
             api_version: "1.0.0".to_string(),
@@ -286,22 +273,13 @@ impl Gateway {
             use_timestamp: params.use_timestamp.unwrap_or(false),
         };
 
-        let res = self
-            .client
-            .post(&url)
-            .header(CONTENT_TYPE, "application/json")
-            .json(&req)
-            .send()
-            .await?;
-
-        let response = res.json::<FetchSignaturesBatchResponse>().await?;
-        Ok(response)
+        self.post_json(&url, &req).await
     }
 
     pub async fn fetch_signatures_consensus(
         &self,
         params: FetchSignaturesConsensusParams,
-    ) -> Result<FetchSignaturesConsensusResponse, reqwest::Error> {
+    ) -> Result<FetchSignaturesConsensusResponse, ClientError> {
         let url = format!("{}/gateway/api/v1/fetch_signatures_consensus", self.gateway_url);
         // Build feed_requests array from feed_configs
         let feed_requests: Vec<serde_json::Value> = params.feed_configs.iter().map(|config| {
@@ -325,15 +303,161 @@ impl Gateway {
             "num_oracles": params.num_signatures.unwrap_or(1)
         });
         
-        let res = self.client
-            .post(&url)
+        self.post_json(&url, &body).await
+    }
+
+    /// Fetches the signed randomness value for a committed randomness account
+    /// # Arguments
+    /// * `params` - FetchRandomnessRevealParams
+    /// * `params.randomness_key` - The randomness account
+    /// * `params.slot` - The seed slot the randomness was committed at
+    /// * `params.slothash` - The seed slothash the randomness was committed with
+    /// # Returns
+    /// * `Result<RandomnessRevealResponse, ClientError>`
+    pub async fn fetch_randomness_reveal(
+        &self,
+        params: FetchRandomnessRevealParams,
+    ) -> Result<RandomnessRevealResponse, ClientError> {
+        let url = format!("{}/gateway/api/v1/randomness_reveal", self.gateway_url);
+        let body = serde_json::json!({
+            "slothash": params.slothash.to_vec(),
+            "randomness_key": hex::encode(params.randomness_key),
+            "slot": params.slot,
+        });
+
+        self.post_json(&url, &body).await
+    }
+
+    /// Fetches the signed randomness value for a cross-chain randomness request
+    /// # Arguments
+    /// * `params` - FetchCrossChainRandomnessRevealParams
+    /// * `params.randomness_id` - The id of the randomness request
+    /// * `params.timestamp` - The timestamp the randomness was requested at
+    /// * `params.min_staleness_seconds` - The minimum age of the request before it is revealed
+    /// # Returns
+    /// * `Result<RandomnessRevealResponse, ClientError>`
+    pub async fn fetch_cross_chain_randomness_reveal(
+        &self,
+        params: FetchCrossChainRandomnessRevealParams,
+    ) -> Result<RandomnessRevealResponse, ClientError> {
+        let url = format!("{}/gateway/api/v1/randomness_reveal", self.gateway_url);
+        let body = serde_json::json!({
+            "timestamp": params.timestamp,
+            "min_staleness_seconds": params.min_staleness_seconds,
+            "randomness_key": params.randomness_id,
+        });
+
+        self.post_json(&url, &body).await
+    }
+
+    /// Pings the gateway's oracle
+    /// # Returns
+    /// * `Result<PingResponse, ClientError>` - The oracle's identity, version,
+    ///   enclave measurement, rate limit and roles
+    pub async fn ping(&self) -> Result<PingResponse, ClientError> {
+        let url = format!("{}/gateway/api/v1/ping", self.gateway_url);
+        let body = serde_json::json!({ "api_version": "1.0.0" });
+
+        self.post_json(&url, &body).await
+    }
+
+    /// Fetches the enclave quotes of the gateway's oracle and guardian
+    /// # Arguments
+    /// * `params` - FetchQuoteParams
+    /// * `params.blockhash` - The blockhash to bind the quotes to
+    /// * `params.get_for_oracle` - Whether to fetch the oracle's quote
+    /// * `params.get_for_guardian` - Whether to fetch the guardian's quote
+    /// # Returns
+    /// * `Result<Vec<FetchQuoteResponse>, ClientError>`
+    pub async fn fetch_quote(
+        &self,
+        params: FetchQuoteParams,
+    ) -> Result<Vec<FetchQuoteResponse>, ClientError> {
+        let url = format!("{}/gateway/api/v1/gateway_fetch_quote", self.gateway_url);
+        let body = serde_json::json!({
+            "api_version": "1.0.0",
+            "blockhash": params.blockhash,
+            "get_for_oracle": params.get_for_oracle,
+            "get_for_guardian": params.get_for_guardian,
+        });
+
+        self.post_json(&url, &body).await
+    }
+
+    /// Asks the gateway's guardian to attest an oracle's enclave quote
+    /// # Arguments
+    /// * `params` - AttestEnclaveParams
+    /// * `params.timestamp` - The timestamp of the attestation
+    /// * `params.quote` - The oracle's enclave quote
+    /// * `params.oracle_pubkey` - The oracle's public key
+    /// * `params.oracle_reward_wallet` - The oracle's reward wallet
+    /// * `params.oracle_ed25519_enclave_signer` - The oracle's ed25519 enclave signer
+    /// * `params.oracle_secp256k1_enclave_signer` - The oracle's secp256k1 enclave signer
+    /// * `params.recent_hash` - The chain hash to sign with
+    /// # Returns
+    /// * `Result<AttestEnclaveResponse, ClientError>`
+    pub async fn attest_enclave(
+        &self,
+        params: AttestEnclaveParams,
+    ) -> Result<AttestEnclaveResponse, ClientError> {
+        let url = format!("{}/gateway/api/v1/gateway_attest_enclave", self.gateway_url);
+        let body = serde_json::json!({
+            "api_version": "1.0.0",
+            "timestamp": params.timestamp,
+            "quote": params.quote,
+            "oracle_pubkey": params.oracle_pubkey,
+            "oracle_reward_wallet": params.oracle_reward_wallet,
+            "oracle_ed25519_enclave_signer": params.oracle_ed25519_enclave_signer,
+            "oracle_secp256k1_enclave_signer": params.oracle_secp256k1_enclave_signer,
+            "chain_hash": params.recent_hash,
+        });
+
+        self.post_json(&url, &body).await
+    }
+
+    /// Fetches the guardian-signed message bridging an oracle's enclave to
+    /// another chain
+    /// # Arguments
+    /// * `params` - BridgeEnclaveParams
+    /// * `params.chain_hash` - The chain hash to include in the message
+    /// * `params.oracle_pubkey` - The oracle's public key
+    /// * `params.queue_pubkey` - The oracle's queue
+    /// # Returns
+    /// * `Result<BridgeEnclaveResponse, ClientError>`
+    pub async fn bridge_enclave(
+        &self,
+        params: BridgeEnclaveParams,
+    ) -> Result<BridgeEnclaveResponse, ClientError> {
+        let url = format!("{}/gateway/api/v1/gateway_bridge_enclave", self.gateway_url);
+        let body = serde_json::json!({
+            "api_version": "1.0.0",
+            "chain_hash": params.chain_hash,
+            "oracle_pubkey": params.oracle_pubkey,
+            "queue_pubkey": params.queue_pubkey,
+        });
+
+        self.post_json(&url, &body).await
+    }
+
+    /// Posts `body` to `url` and decodes the JSON response, separating
+    /// transport and status failures from undecodable bodies.
+    async fn post_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &impl Serialize,
+    ) -> Result<T, ClientError> {
+        let res = self
+            .client
+            .post(url)
             .header(CONTENT_TYPE, "application/json")
-            .json(&body)
+            .json(body)
             .send()
-            .await?;
-        
-        let response = res.json::<FetchSignaturesConsensusResponse>().await?;
-        Ok(response)
+            .await
+            .map_err(ClientError::gateway_http)?;
+
+        let status = res.status();
+        let raw = res.text().await.map_err(ClientError::gateway_http)?;
+        decode_response(status, raw)
     }
 
     pub async fn test_gateway(&self) -> bool {
@@ -355,7 +479,7 @@ impl Gateway {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FetchSignaturesParams {
     pub recent_hash: Option<String>,
     pub encoded_jobs: Vec<String>,
@@ -365,6 +489,45 @@ pub struct FetchSignaturesParams {
     pub use_timestamp: Option<bool>,
 }
 
+#[derive(Debug, Clone)]
+pub struct FetchRandomnessRevealParams {
+    pub randomness_key: [u8; 32],
+    pub slot: u64,
+    pub slothash: [u8; 32],
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchCrossChainRandomnessRevealParams {
+    pub randomness_id: String,
+    pub timestamp: i64,
+    pub min_staleness_seconds: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FetchQuoteParams {
+    pub blockhash: String,
+    pub get_for_oracle: bool,
+    pub get_for_guardian: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AttestEnclaveParams {
+    pub timestamp: i64,
+    pub quote: String,
+    pub oracle_pubkey: String,
+    pub oracle_reward_wallet: String,
+    pub oracle_ed25519_enclave_signer: String,
+    pub oracle_secp256k1_enclave_signer: String,
+    pub recent_hash: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BridgeEnclaveParams {
+    pub chain_hash: String,
+    pub oracle_pubkey: String,
+    pub queue_pubkey: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct FeedConfig {
     pub encoded_jobs: Vec<String>,
@@ -382,7 +545,7 @@ pub struct BatchFeedRequest {
     pub min_responses: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FetchSignaturesMultiParams {
     pub recent_hash: Option<String>,
     pub feed_configs: Vec<FeedConfig>,
@@ -398,6 +561,20 @@ pub struct FetchSignaturesBatchParams {
     pub use_timestamp: Option<bool>,
 }
 
+pub(crate) fn decode_response<T: DeserializeOwned>(
+    status: StatusCode,
+    raw: String,
+) -> Result<T, ClientError> {
+    if !status.is_success() {
+        return Err(ClientError::GatewayHttp {
+            status: Some(status.as_u16()),
+            body: raw,
+        });
+    }
+
+    serde_json::from_str(&raw).map_err(|e| ClientError::GatewayDecode(e.to_string()))
+}
+
 pub fn encode_jobs(job_array: &[OracleJob]) -> Vec<String> {
     job_array
         .iter()
@@ -0,0 +1,391 @@
+use crate::ClientError;
+use crate::*;
+use futures::future::join_all;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Weight of the newest sample in the latency moving average.
+const LATENCY_EMA_ALPHA: f64 = 0.3;
+/// Latency assumed for gateways that have not been measured yet.
+const DEFAULT_LATENCY_MS: f64 = 1_000.0;
+/// Latency recorded for a failed request, so that failing gateways sink
+/// even if they fail fast.
+const FAILURE_LATENCY_MS: f64 = 10_000.0;
+
+/// Latency and error statistics of a single gateway.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GatewayStats {
+    /// Exponential moving average of the request latency, in milliseconds.
+    pub latency_ms: Option<f64>,
+    pub successes: u64,
+    pub failures: u64,
+}
+
+impl GatewayStats {
+    /// The fraction of failed requests, smoothed so that a gateway with no
+    /// history starts at 0.5 rather than 0 or 1.
+    pub fn error_rate(&self) -> f64 {
+        (self.failures as f64 + 1.0) / ((self.successes + self.failures) as f64 + 2.0)
+    }
+
+    /// The ranking score of the gateway. Lower is better.
+    pub fn score(&self) -> f64 {
+        self.latency_ms.unwrap_or(DEFAULT_LATENCY_MS) * (1.0 + 10.0 * self.error_rate())
+    }
+
+    fn record(&mut self, latency_ms: f64, success: bool) {
+        if success {
+            self.successes += 1;
+        } else {
+            self.failures += 1;
+        }
+        self.latency_ms = Some(match self.latency_ms {
+            Some(ema) => ema + LATENCY_EMA_ALPHA * (latency_ms - ema),
+            None => latency_ms,
+        });
+    }
+}
+
+#[derive(Debug)]
+struct PooledGateway {
+    gateway: Gateway,
+    stats: Mutex<GatewayStats>,
+}
+
+/// A set of gateways that requests are spread over, best scoring first.
+///
+/// Gateways are ranked by `GatewayStats::score`, which combines latency and
+/// error rate. A request that fails transiently or with a malformed response
+/// is retried on the next gateway in the ranking, up to `max_attempts`
+/// gateways per request.
+#[derive(Debug)]
+pub struct GatewayPool {
+    gateways: Vec<PooledGateway>,
+    max_attempts: usize,
+}
+
+impl GatewayPool {
+    pub fn new(gateways: Vec<Gateway>) -> Self {
+        let max_attempts = gateways.len().clamp(1, 3);
+        Self {
+            gateways: gateways
+                .into_iter()
+                .map(|gateway| PooledGateway {
+                    gateway,
+                    stats: Mutex::new(GatewayStats::default()),
+                })
+                .collect(),
+            max_attempts,
+        }
+    }
+
+    /// Sets the maximum number of gateways a single request is tried on.
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Discovers the gateways of the oracles on `queue` and health-checks them.
+    /// Oracles sharing a gateway are deduplicated.
+    /// # Arguments
+    /// * `client` - The RPC client to use for fetching the queue and oracle accounts.
+    /// * `queue` - The queue whose oracle set to discover gateways from.
+    /// # Returns
+    /// A pool of the discovered gateways, ranked by the health check.
+    pub async fn discover(client: &RpcClient, queue: &Pubkey) -> Result<Self, ClientError> {
+        let queue_data = client
+            .get_account_with_commitment(queue, client.commitment())
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound(*queue))?;
+        let queue_data = queue_data
+            .data
+            .get(8..)
+            .and_then(|data| bytemuck::try_from_bytes::<QueueAccountData>(data).ok())
+            .ok_or_else(|| ClientError::InvalidAccountData {
+                account: *queue,
+                reason: "Failed to parse queue data".to_string(),
+            })?;
+
+        let mut urls: Vec<String> = Vec::new();
+        let oracles = client.get_multiple_accounts(&queue_data.oracle_keys()).await?;
+        for account in oracles.into_iter().flatten() {
+            let uri = account
+                .data
+                .get(8..)
+                .and_then(|data| bytemuck::try_from_bytes::<OracleAccountData>(data).ok())
+                .and_then(|oracle| oracle.gateway_uri());
+            if let Some(uri) = uri {
+                if !urls.contains(&uri) {
+                    urls.push(uri);
+                }
+            }
+        }
+
+        let pool = Self::new(urls.into_iter().map(Gateway::new).collect());
+        pool.health_check().await;
+        Ok(pool)
+    }
+
+    /// Tests every gateway concurrently and records the outcome and latency.
+    pub async fn health_check(&self) {
+        let checks = self.gateways.iter().map(|pooled| async move {
+            let start = Instant::now();
+            let healthy = pooled.gateway.test_gateway().await;
+            self.record(pooled, start.elapsed(), healthy);
+        });
+        join_all(checks).await;
+    }
+
+    /// The gateways in the pool, best scoring first.
+    pub fn ranked(&self) -> Vec<Gateway> {
+        self.ranked_indices()
+            .into_iter()
+            .map(|idx| self.gateways[idx].gateway.clone())
+            .collect()
+    }
+
+    /// The statistics of each gateway, keyed by gateway URL, in pool order.
+    pub fn stats(&self) -> Vec<(String, GatewayStats)> {
+        self.gateways
+            .iter()
+            .map(|pooled| {
+                let stats = pooled.stats.lock().unwrap().clone();
+                (pooled.gateway.gateway_url().to_string(), stats)
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.gateways.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gateways.is_empty()
+    }
+
+    /// Runs `request` against the best scoring gateway, retrying on the next
+    /// one when it fails, and returns the first success or the last error.
+    ///
+    /// Only transient errors and malformed responses count against the
+    /// gateway and are retried. Any other error, e.g. invalid input, is
+    /// returned right away.
+    pub async fn with_failover<T, F, Fut>(&self, request: F) -> Result<T, ClientError>
+    where
+        F: Fn(Gateway) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut last_err = ClientError::InvalidInput("Gateway pool is empty".to_string());
+        for idx in self.ranked_indices().into_iter().take(self.max_attempts) {
+            let pooled = &self.gateways[idx];
+            let start = Instant::now();
+            match request(pooled.gateway.clone()).await {
+                Ok(response) => {
+                    self.record(pooled, start.elapsed(), true);
+                    return Ok(response);
+                }
+                Err(err) if err.is_transient() || matches!(err, ClientError::GatewayDecode(_)) => {
+                    self.record(pooled, start.elapsed(), false);
+                    last_err = err;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_err)
+    }
+
+    /// `Gateway::fetch_signatures_from_encoded` with failover.
+    pub async fn fetch_signatures_from_encoded(
+        &self,
+        params: FetchSignaturesParams,
+    ) -> Result<FeedEvalResponseSingle, ClientError> {
+        self.with_failover(|gateway| {
+            let params = params.clone();
+            async move { gateway.fetch_signatures_from_encoded(params).await }
+        })
+        .await
+    }
+
+    /// `Gateway::fetch_signatures_multi` with failover.
+    pub async fn fetch_signatures_multi(
+        &self,
+        params: FetchSignaturesMultiParams,
+    ) -> Result<FetchSignaturesMultiResponse, ClientError> {
+        self.with_failover(|gateway| {
+            let params = params.clone();
+            async move { gateway.fetch_signatures_multi(params).await }
+        })
+        .await
+    }
+
+    /// `Gateway::fetch_signatures_batch` with failover.
+    pub async fn fetch_signatures_batch(
+        &self,
+        params: FetchSignaturesBatchParams,
+    ) -> Result<FetchSignaturesBatchResponse, ClientError> {
+        self.with_failover(|gateway| {
+            let params = params.clone();
+            async move { gateway.fetch_signatures_batch(params).await }
+        })
+        .await
+    }
+
+    /// `Gateway::fetch_signatures_consensus` with failover.
+    pub async fn fetch_signatures_consensus(
+        &self,
+        params: FetchSignaturesConsensusParams,
+    ) -> Result<FetchSignaturesConsensusResponse, ClientError> {
+        self.with_failover(|gateway| {
+            let params = params.clone();
+            async move { gateway.fetch_signatures_consensus(params).await }
+        })
+        .await
+    }
+
+    fn ranked_indices(&self) -> Vec<usize> {
+        let scores: Vec<f64> = self
+            .gateways
+            .iter()
+            .map(|pooled| pooled.stats.lock().unwrap().score())
+            .collect();
+        let mut indices: Vec<usize> = (0..self.gateways.len()).collect();
+        indices.sort_by(|a, b| scores[*a].total_cmp(&scores[*b]));
+        indices
+    }
+
+    fn record(&self, pooled: &PooledGateway, elapsed: Duration, success: bool) {
+        let latency_ms = if success {
+            elapsed.as_secs_f64() * 1_000.0
+        } else {
+            FAILURE_LATENCY_MS.max(elapsed.as_secs_f64() * 1_000.0)
+        };
+        pooled.stats.lock().unwrap().record(latency_ms, success);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockOracle, MockServer};
+
+    fn pool() -> GatewayPool {
+        GatewayPool::new(vec![
+            Gateway::new("https://a.example".to_string()),
+            Gateway::new("https://b.example".to_string()),
+            Gateway::new("https://c.example".to_string()),
+        ])
+    }
+
+    fn urls(gateways: &[Gateway]) -> Vec<&str> {
+        gateways.iter().map(|gateway| gateway.gateway_url()).collect()
+    }
+
+    #[test]
+    fn test_ranks_by_latency_and_errors() {
+        let pool = pool();
+        pool.record(&pool.gateways[0], Duration::from_millis(300), true);
+        pool.record(&pool.gateways[1], Duration::from_millis(50), true);
+        pool.record(&pool.gateways[2], Duration::from_millis(10), false);
+        assert_eq!(
+            urls(&pool.ranked()),
+            ["https://b.example", "https://a.example", "https://c.example"]
+        );
+    }
+
+    fn oracle_with_gateway(oracle: &MockOracle, uri: &str) -> OracleAccountData {
+        let mut data = oracle.account_data();
+        data.gateway_uri[..uri.len()].copy_from_slice(uri.as_bytes());
+        data
+    }
+
+    #[tokio::test]
+    async fn test_discover_dedupes_and_ranks_queue_gateways() {
+        let queue = Pubkey::new_unique();
+        let oracles = MockOracle::generate(3);
+        let server = MockServer::start(queue, oracles.clone()).await;
+        let unreachable = "http://127.0.0.1:1";
+        let url = server.url();
+        for (oracle, uri) in oracles.iter().zip([unreachable, url.as_str(), url.as_str()]) {
+            server.set_oracle(oracle.pubkey, &oracle_with_gateway(oracle, uri));
+        }
+        let mut queue_data: QueueAccountData = bytemuck::Zeroable::zeroed();
+        for (key, oracle) in queue_data.oracle_keys.iter_mut().zip(&oracles) {
+            *key = oracle.pubkey;
+        }
+        // An oracle without an account is skipped.
+        queue_data.oracle_keys[3] = Pubkey::new_unique();
+        queue_data.oracle_keys_len = 4;
+        server.set_queue(queue, &queue_data);
+
+        let pool = GatewayPool::discover(&server.rpc_client(), &queue)
+            .await
+            .unwrap();
+
+        assert_eq!(pool.len(), 2);
+        assert_eq!(urls(&pool.ranked()), [url.as_str(), unreachable]);
+        let stats = pool.stats();
+        assert_eq!(stats[0].0, unreachable);
+        assert_eq!((stats[0].1.failures, stats[1].1.successes), (1, 1));
+
+        let err = GatewayPool::discover(&server.rpc_client(), &Pubkey::new_unique())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::AccountNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_next_gateway() {
+        let pool = pool();
+        pool.record(&pool.gateways[1], Duration::from_millis(50), true);
+        let response = pool
+            .with_failover(|gateway| async move {
+                if gateway.gateway_url() == "https://b.example" {
+                    Err(ClientError::GatewayHttp {
+                        status: None,
+                        body: "timed out".to_string(),
+                    })
+                } else {
+                    Ok(gateway.gateway_url().to_string())
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(response, "https://a.example");
+        assert_eq!(urls(&pool.ranked())[2], "https://b.example");
+    }
+
+    #[tokio::test]
+    async fn test_failover_returns_last_error() {
+        let pool = pool().with_max_attempts(2);
+        let attempts = Mutex::new(0);
+        let err = pool
+            .with_failover(|_| {
+                *attempts.lock().unwrap() += 1;
+                async { Err::<(), _>(ClientError::GatewayDecode("bad json".to_string())) }
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::GatewayDecode(_)));
+        assert_eq!(*attempts.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_returns_other_errors_right_away() {
+        let pool = pool();
+        let attempts = Mutex::new(0);
+        let err = pool
+            .with_failover(|_| {
+                *attempts.lock().unwrap() += 1;
+                async { Err::<(), _>(ClientError::InvalidInput("no feeds".to_string())) }
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::InvalidInput(_)));
+        assert_eq!(*attempts.lock().unwrap(), 1);
+        assert!(pool.stats().iter().all(|(_, stats)| stats.failures == 0));
+    }
+}
@@ -4,6 +4,12 @@ pub mod pull_feed_submit_response_many_ix;
 pub use pull_feed_submit_response_many_ix::*;
 pub mod pull_feed_submit_response_consensus;
 pub use pull_feed_submit_response_consensus::*;
+pub mod randomness_init_ix;
+pub use randomness_init_ix::*;
+pub mod randomness_commit_ix;
+pub use randomness_commit_ix::*;
+pub mod randomness_reveal_ix;
+pub use randomness_reveal_ix::*;
 use sha2::{Digest, Sha256};
 
 pub fn get_discriminator(name: &str) -> Vec<u8> {
@@ -0,0 +1,74 @@
+use crate::get_discriminator;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Clone, Debug)]
+pub struct RandomnessCommit {
+    // mut
+    pub randomness: Pubkey,
+    pub queue: Pubkey,
+    // mut
+    pub oracle: Pubkey,
+    pub recent_slothashes: Pubkey,
+    pub authority: Pubkey,
+}
+
+impl RandomnessCommit {
+    pub fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.randomness, false),
+            AccountMeta::new_readonly(self.queue, false),
+            AccountMeta::new(self.oracle, false),
+            AccountMeta::new_readonly(self.recent_slothashes, false),
+            AccountMeta::new_readonly(self.authority, true),
+        ]
+    }
+}
+
+#[derive(Clone)]
+pub struct RandomnessCommitParams;
+
+impl RandomnessCommitParams {
+    pub fn data(&self) -> Vec<u8> {
+        get_discriminator("randomness_commit")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_randomness_commit_data() {
+        assert_eq!(
+            RandomnessCommitParams.data(),
+            vec![52, 170, 152, 201, 179, 133, 242, 141]
+        );
+    }
+
+    #[test]
+    fn test_randomness_commit_account_metas() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let metas = RandomnessCommit {
+            randomness: keys[0],
+            queue: keys[1],
+            oracle: keys[2],
+            recent_slothashes: keys[3],
+            authority: keys[4],
+        }
+        .to_account_metas(None);
+        let pubkeys: Vec<Pubkey> = metas.iter().map(|x| x.pubkey).collect();
+        assert_eq!(pubkeys, keys);
+        let flags: Vec<(bool, bool)> = metas.iter().map(|x| (x.is_signer, x.is_writable)).collect();
+        assert_eq!(
+            flags,
+            vec![
+                (false, true),
+                (false, false),
+                (false, true),
+                (false, false),
+                (true, false)
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::get_discriminator;
+use borsh::{BorshDeserialize, BorshSerialize};
+use lazy_static::lazy_static;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+lazy_static! {
+    pub static ref ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+        Pubkey::from_str("AddressLookupTab1e1111111111111111111111111").unwrap();
+}
+
+#[derive(Clone, Debug)]
+pub struct RandomnessInit {
+    // mut, signer
+    pub randomness: Pubkey,
+    // mut
+    pub reward_escrow: Pubkey,
+    pub authority: Pubkey,
+    // mut
+    pub queue: Pubkey,
+    // mut
+    pub payer: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub wrapped_sol_mint: Pubkey,
+    pub program_state: Pubkey,
+    pub lut_signer: Pubkey,
+    // mut
+    pub lut: Pubkey,
+}
+
+impl RandomnessInit {
+    pub fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.randomness, true),
+            AccountMeta::new(self.reward_escrow, false),
+            AccountMeta::new_readonly(self.authority, true),
+            AccountMeta::new(self.queue, false),
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(self.system_program, false),
+            AccountMeta::new_readonly(self.token_program, false),
+            AccountMeta::new_readonly(self.associated_token_program, false),
+            AccountMeta::new_readonly(self.wrapped_sol_mint, false),
+            AccountMeta::new_readonly(self.program_state, false),
+            AccountMeta::new_readonly(self.lut_signer, false),
+            AccountMeta::new(self.lut, false),
+            AccountMeta::new_readonly(*ADDRESS_LOOKUP_TABLE_PROGRAM_ID, false),
+        ]
+    }
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct RandomnessInitParams {
+    pub recent_slot: u64,
+}
+
+impl RandomnessInitParams {
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut buffer: Vec<u8> = Vec::new();
+        self.serialize(&mut buffer).unwrap();
+        buffer
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        let mut res = get_discriminator("randomness_init").to_vec();
+        res.extend_from_slice(&self.to_vec());
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_randomness_init_data() {
+        let data = RandomnessInitParams { recent_slot: 42 }.data();
+        assert_eq!(data[..8], [9, 9, 204, 33, 50, 116, 113, 15]);
+        assert_eq!(data[8..], 42u64.to_le_bytes());
+        let params = RandomnessInitParams::try_from_slice(&data[8..]).unwrap();
+        assert_eq!(params.recent_slot, 42);
+    }
+
+    #[test]
+    fn test_randomness_init_account_metas() {
+        let keys: Vec<Pubkey> = (0..12).map(|_| Pubkey::new_unique()).collect();
+        let metas = RandomnessInit {
+            randomness: keys[0],
+            reward_escrow: keys[1],
+            authority: keys[2],
+            queue: keys[3],
+            payer: keys[4],
+            system_program: keys[5],
+            token_program: keys[6],
+            associated_token_program: keys[7],
+            wrapped_sol_mint: keys[8],
+            program_state: keys[9],
+            lut_signer: keys[10],
+            lut: keys[11],
+        }
+        .to_account_metas(None);
+        let mut expected = keys.clone();
+        expected.push(*ADDRESS_LOOKUP_TABLE_PROGRAM_ID);
+        let pubkeys: Vec<Pubkey> = metas.iter().map(|x| x.pubkey).collect();
+        assert_eq!(pubkeys, expected);
+        let signers: Vec<usize> = (0..metas.len()).filter(|&i| metas[i].is_signer).collect();
+        assert_eq!(signers, vec![0, 2, 4]);
+        let writable: Vec<usize> = (0..metas.len()).filter(|&i| metas[i].is_writable).collect();
+        assert_eq!(writable, vec![0, 1, 3, 4, 11]);
+    }
+}
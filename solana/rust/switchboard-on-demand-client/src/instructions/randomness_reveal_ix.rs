@@ -0,0 +1,119 @@
+use crate::get_discriminator;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Clone, Debug)]
+pub struct RandomnessReveal {
+    // mut
+    pub randomness: Pubkey,
+    pub oracle: Pubkey,
+    pub queue: Pubkey,
+    // mut
+    pub stats: Pubkey,
+    pub authority: Pubkey,
+    // mut
+    pub payer: Pubkey,
+    pub recent_slothashes: Pubkey,
+    pub system_program: Pubkey,
+    // mut
+    pub reward_escrow: Pubkey,
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub wrapped_sol_mint: Pubkey,
+    pub program_state: Pubkey,
+}
+
+impl RandomnessReveal {
+    pub fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.randomness, false),
+            AccountMeta::new_readonly(self.oracle, false),
+            AccountMeta::new_readonly(self.queue, false),
+            AccountMeta::new(self.stats, false),
+            AccountMeta::new_readonly(self.authority, true),
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(self.recent_slothashes, false),
+            AccountMeta::new_readonly(self.system_program, false),
+            AccountMeta::new(self.reward_escrow, false),
+            AccountMeta::new_readonly(self.token_program, false),
+            AccountMeta::new_readonly(self.associated_token_program, false),
+            AccountMeta::new_readonly(self.wrapped_sol_mint, false),
+            AccountMeta::new_readonly(self.program_state, false),
+        ]
+    }
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize)]
+pub struct RandomnessRevealParams {
+    pub signature: [u8; 64],
+    pub recovery_id: u8,
+    pub value: [u8; 32],
+}
+
+impl RandomnessRevealParams {
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut buffer: Vec<u8> = Vec::new();
+        self.serialize(&mut buffer).unwrap();
+        buffer
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        let mut res = get_discriminator("randomness_reveal").to_vec();
+        res.extend_from_slice(&self.to_vec());
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_randomness_reveal_data() {
+        let params = RandomnessRevealParams {
+            signature: [1; 64],
+            recovery_id: 2,
+            value: [3; 32],
+        };
+        let data = params.data();
+        assert_eq!(data[..8], [197, 181, 187, 10, 30, 58, 20, 73]);
+        // Borsh writes the fields in declaration order without length
+        // prefixes for the fixed size arrays.
+        assert_eq!(data.len(), 8 + 64 + 1 + 32);
+        assert_eq!(data[8..72], [1; 64]);
+        assert_eq!(data[72], 2);
+        assert_eq!(data[73..], [3; 32]);
+        let decoded = RandomnessRevealParams::try_from_slice(&data[8..]).unwrap();
+        assert_eq!(decoded.signature, params.signature);
+        assert_eq!(decoded.recovery_id, params.recovery_id);
+        assert_eq!(decoded.value, params.value);
+    }
+
+    #[test]
+    fn test_randomness_reveal_account_metas() {
+        let keys: Vec<Pubkey> = (0..13).map(|_| Pubkey::new_unique()).collect();
+        let metas = RandomnessReveal {
+            randomness: keys[0],
+            oracle: keys[1],
+            queue: keys[2],
+            stats: keys[3],
+            authority: keys[4],
+            payer: keys[5],
+            recent_slothashes: keys[6],
+            system_program: keys[7],
+            reward_escrow: keys[8],
+            token_program: keys[9],
+            associated_token_program: keys[10],
+            wrapped_sol_mint: keys[11],
+            program_state: keys[12],
+        }
+        .to_account_metas(None);
+        let pubkeys: Vec<Pubkey> = metas.iter().map(|x| x.pubkey).collect();
+        assert_eq!(pubkeys, keys);
+        let signers: Vec<usize> = (0..metas.len()).filter(|&i| metas[i].is_signer).collect();
+        assert_eq!(signers, vec![4, 5]);
+        let writable: Vec<usize> = (0..metas.len()).filter(|&i| metas[i].is_writable).collect();
+        assert_eq!(writable, vec![0, 3, 5, 8]);
+    }
+}
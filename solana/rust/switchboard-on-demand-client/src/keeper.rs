@@ -0,0 +1,605 @@
+use crate::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::genesis_config::ClusterType;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::keypair::Keypair;
+use solana_sdk::signer::Signer;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// When a single feed is due for an update. Unset conditions are not checked.
+#[derive(Clone, Debug, Default)]
+pub struct KeeperFeed {
+    pub feed: Pubkey,
+    /// Updates the feed once its last update is this old.
+    pub heartbeat: Option<Duration>,
+    /// Updates the feed once the simulated value deviates from the on-chain
+    /// value by this many basis points.
+    pub deviation_bps: Option<u64>,
+    /// Updates the feed once its result is this many slots old. Defaults to
+    /// 3/4 of the feed's `max_staleness`, if set.
+    pub stale_after_slots: Option<u64>,
+}
+
+/// Why a feed is due for an update.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpdateReason {
+    /// The feed has no result yet.
+    NeverUpdated,
+    /// The last update is older than the heartbeat, in seconds.
+    Heartbeat { elapsed_secs: i64 },
+    /// The simulated value deviates from the on-chain value.
+    Deviation { bps: u64 },
+    /// The result is this many slots old.
+    Stale { slots: u64 },
+}
+
+#[derive(Clone)]
+pub struct KeeperConfig {
+    pub feeds: Vec<KeeperFeed>,
+    pub payer: Arc<Keypair>,
+    pub gateway: Gateway,
+    /// Fetches the signatures from this pool, with failover, instead of from
+    /// `gateway`, e.g. a pool from `GatewayPool::discover`.
+    pub gateway_pool: Option<Arc<GatewayPool>>,
+    pub crossbar: CrossbarClient,
+    /// The network `simulate_solana_feeds` is queried for.
+    pub network: ClusterType,
+    /// The time between two checks of the feeds in `Keeper::run`.
+    pub poll_interval: Duration,
+    /// The most feeds updated by a single transaction.
+    pub max_batch_size: usize,
+    /// The most attempts at sending a single batch.
+    pub max_attempts: u32,
+    /// The time to wait between two attempts.
+    pub retry_delay: Duration,
+    pub num_signatures: Option<u32>,
+    pub transaction: TransactionConfig,
+    /// The TTLs of the keeper's feed, job and lookup table caches.
+    pub cache: CacheConfig,
+    /// Where `Keeper::run` loads the job cache from on start and saves it to
+    /// after each check, if set.
+    pub job_cache_path: Option<PathBuf>,
+}
+
+impl KeeperConfig {
+    pub fn new(
+        feeds: Vec<KeeperFeed>,
+        payer: Arc<Keypair>,
+        gateway: Gateway,
+        crossbar: CrossbarClient,
+    ) -> Self {
+        Self {
+            feeds,
+            payer,
+            gateway,
+            gateway_pool: None,
+            crossbar,
+            network: ClusterType::MainnetBeta,
+            poll_interval: Duration::from_secs(5),
+            max_batch_size: 5,
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(500),
+            num_signatures: None,
+            transaction: TransactionConfig::default(),
+            cache: CacheConfig::default(),
+            job_cache_path: None,
+        }
+    }
+}
+
+/// Hooks called by the keeper, e.g. to export metrics. All hooks default to
+/// doing nothing.
+pub trait KeeperMetrics: Send + Sync {
+    /// Called for each feed that is due for an update.
+    fn on_update_due(&self, _feed: &Pubkey, _reason: &UpdateReason) {}
+    /// Called when the transaction updating `feeds` is confirmed, after
+    /// `attempts` attempts.
+    fn on_update_sent(&self, _feeds: &[Pubkey], _signature: &Signature, _attempts: u32) {}
+    /// Called when an attempt at updating `feeds` fails.
+    fn on_update_failed(&self, _feeds: &[Pubkey], _error: &ClientError, _attempt: u32) {}
+    /// Called when the feeds could not be checked, or could only be checked
+    /// partially, e.g. when the simulation failed.
+    fn on_error(&self, _error: &ClientError) {}
+}
+
+/// The `KeeperMetrics` that ignores every event.
+pub struct NoopMetrics;
+impl KeeperMetrics for NoopMetrics {}
+
+/// Keeps a set of pull feeds fresh by updating them when they are due.
+///
+/// # Examples
+///
+/// ```ignore
+/// let config = KeeperConfig::new(
+///     vec![KeeperFeed {
+///         feed,
+///         heartbeat: Some(Duration::from_secs(60)),
+///         deviation_bps: Some(50),
+///         ..Default::default()
+///     }],
+///     payer,
+///     Gateway::new(gateway_url),
+///     CrossbarClient::new("https://crossbar.switchboard.xyz", false),
+/// );
+/// Keeper::new(Arc::new(client), config).run().await;
+/// ```
+pub struct Keeper {
+    client: Arc<RpcClient>,
+    context: Arc<SbContext>,
+    config: KeeperConfig,
+    metrics: Arc<dyn KeeperMetrics>,
+}
+
+impl Keeper {
+    pub fn new(client: Arc<RpcClient>, config: KeeperConfig) -> Self {
+        Self {
+            client,
+            context: SbContext::with_config(config.cache.clone()),
+            config,
+            metrics: Arc::new(NoopMetrics),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<dyn KeeperMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// The caches of feed data, jobs and lookup tables used for the updates.
+    pub fn context(&self) -> &Arc<SbContext> {
+        &self.context
+    }
+
+    /// Checks and updates the feeds every `poll_interval`, forever.
+    pub async fn run(&self) {
+        if let Some(path) = &self.config.job_cache_path {
+            if let Err(err) = self.context.load_job_cache(path) {
+                self.metrics.on_error(&err);
+            }
+        }
+        loop {
+            if let Err(err) = self.tick().await {
+                self.metrics.on_error(&err);
+            }
+            if let Some(path) = &self.config.job_cache_path {
+                if let Err(err) = self.context.save_job_cache(path) {
+                    self.metrics.on_error(&err);
+                }
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// Checks the feeds once and updates the ones that are due.
+    /// # Returns
+    /// The signatures of the confirmed update transactions. Failed batches are
+    /// reported to the metrics hooks.
+    pub async fn tick(&self) -> Result<Vec<Signature>, ClientError> {
+        let due: Vec<Pubkey> = self
+            .due_feeds()
+            .await?
+            .into_iter()
+            .map(|(feed, reason)| {
+                self.metrics.on_update_due(&feed, &reason);
+                feed
+            })
+            .collect();
+
+        let mut signatures = Vec::new();
+        for batch in due.chunks(self.config.max_batch_size.max(1)) {
+            if let Some(signature) = self.send_update(batch).await {
+                signatures.push(signature);
+            }
+        }
+        Ok(signatures)
+    }
+
+    /// The feeds that are due for an update, with the first reason found.
+    /// The loaded feed data replaces the cached data, so config changes are
+    /// picked up by the next update. A feed that is missing or cannot be
+    /// parsed is reported and skipped.
+    pub async fn due_feeds(&self) -> Result<Vec<(Pubkey, UpdateReason)>, ClientError> {
+        let keys: Vec<Pubkey> = self.config.feeds.iter().map(|x| x.feed).collect();
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+        let slot = self.client.get_slot().await?;
+        let accounts = self.client.get_multiple_accounts(&keys).await?;
+        let simulated = self.simulated_values().await;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+
+        let mut due = Vec::new();
+        for (config, account) in self.config.feeds.iter().zip(accounts) {
+            let Some(account) = account else {
+                self.metrics
+                    .on_error(&ClientError::AccountNotFound(config.feed));
+                continue;
+            };
+            let Some(data) = account
+                .data
+                .get(8..)
+                .and_then(|data| bytemuck::try_from_bytes::<PullFeedAccountData>(data).ok())
+            else {
+                self.metrics.on_error(&ClientError::InvalidAccountData {
+                    account: config.feed,
+                    reason: "Failed to parse pull feed data".to_string(),
+                });
+                continue;
+            };
+            self.context.pull_feed_cache.insert(config.feed, *data);
+            let simulated = simulated.get(&config.feed).copied();
+            if let Some(reason) = update_reason(config, data, simulated, slot, now) {
+                due.push((config.feed, reason));
+            }
+        }
+        Ok(due)
+    }
+
+    /// The median simulated value of each feed that has a deviation threshold.
+    /// A failed simulation is reported and skips the deviation checks.
+    async fn simulated_values(&self) -> HashMap<Pubkey, Decimal> {
+        let keys: Vec<Pubkey> = self
+            .config
+            .feeds
+            .iter()
+            .filter(|x| x.deviation_bps.is_some())
+            .map(|x| x.feed)
+            .collect();
+        if keys.is_empty() {
+            return HashMap::new();
+        }
+        let responses = match self
+            .config
+            .crossbar
+            .simulate_solana_feeds(self.config.network, &keys)
+            .await
+        {
+            Ok(responses) => responses,
+            Err(err) => {
+                self.metrics.on_error(&err);
+                return HashMap::new();
+            }
+        };
+        responses
+            .into_iter()
+            .filter_map(|response| {
+                let feed = response.feed.parse::<Pubkey>().ok()?;
+                let mut results: Vec<Decimal> = response.results.into_iter().flatten().collect();
+                if results.is_empty() {
+                    return None;
+                }
+                results.sort();
+                Some((feed, results[results.len() / 2]))
+            })
+            .collect()
+    }
+
+    /// Sends the update of `feeds`, retrying transient failures. Each attempt
+    /// fetches fresh signatures, since they are only valid for a few slots.
+    ///
+    /// A failure that stale cached data may cause invalidates the feeds and
+    /// every cached lookup table, including those of the queues and oracles,
+    /// and is retried once.
+    async fn send_update(&self, feeds: &[Pubkey]) -> Option<Signature> {
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut invalidated = false;
+        for attempt in 1..=max_attempts {
+            let mut submit_ixs = Vec::new();
+            match self.try_send_update(feeds, &mut submit_ixs).await {
+                Ok(signature) => {
+                    self.metrics.on_update_sent(feeds, &signature, attempt);
+                    return Some(signature);
+                }
+                Err(err) => {
+                    self.metrics.on_update_failed(feeds, &err, attempt);
+                    if err.is_stale_config(&submit_ixs) && !invalidated {
+                        for feed in feeds {
+                            self.context.invalidate_feed(feed);
+                        }
+                        self.context.lut_cache.clear();
+                        invalidated = true;
+                    } else if !err.is_transient() {
+                        return None;
+                    }
+                }
+            }
+            if attempt < max_attempts {
+                tokio::time::sleep(self.config.retry_delay).await;
+            }
+        }
+        None
+    }
+
+    /// Sends the update of `feeds`, recording the positions of its
+    /// Switchboard instructions in the transaction in `submit_ixs`.
+    async fn try_send_update(
+        &self,
+        feeds: &[Pubkey],
+        submit_ixs: &mut Vec<u8>,
+    ) -> Result<Signature, ClientError> {
+        let payer = self.config.payer.pubkey();
+        let (ixs, mut luts) = PullFeed::fetch_update_consensus_ix(
+            self.context.clone(),
+            &self.client,
+            FetchUpdateManyParams {
+                feeds: feeds.to_vec(),
+                payer,
+                gateway: self.config.gateway.clone(),
+                gateway_pool: self.config.gateway_pool.clone(),
+                crossbar: Some(self.config.crossbar.clone()),
+                num_signatures: self.config.num_signatures,
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await?;
+        luts.extend(self.config.transaction.lookup_tables.iter().cloned());
+        submit_ixs.extend(
+            ixs.iter()
+                .enumerate()
+                .filter(|(_, ix)| ix.program_id == *SWITCHBOARD_ON_DEMAND_PROGRAM_ID)
+                .map(|(idx, _)| idx as u8),
+        );
+        let tx = build_v0_transaction(
+            &self.client,
+            &payer,
+            &ixs,
+            &[self.config.payer.as_ref()],
+            &luts,
+            &self.config.transaction,
+        )
+        .await?;
+        Ok(self.client.send_and_confirm_transaction(&tx).await?)
+    }
+}
+
+/// Why `feed` is due for an update at `slot` and unix timestamp `now`, or
+/// `None` if it is fresh. `simulated` is the current off-chain value of the
+/// feed, if known.
+pub fn update_reason(
+    config: &KeeperFeed,
+    feed: &PullFeedAccountData,
+    simulated: Option<Decimal>,
+    slot: u64,
+    now: i64,
+) -> Option<UpdateReason> {
+    if feed.last_update_timestamp == 0 || feed.result.slot == 0 {
+        return Some(UpdateReason::NeverUpdated);
+    }
+
+    let stale_after = config
+        .stale_after_slots
+        .or_else(|| (feed.max_staleness > 0).then_some(feed.max_staleness as u64 * 3 / 4));
+    let slots = slot.saturating_sub(feed.result.slot);
+    if let Some(stale_after) = stale_after {
+        if slots >= stale_after {
+            return Some(UpdateReason::Stale { slots });
+        }
+    }
+
+    let elapsed_secs = now - feed.last_update_timestamp;
+    if let Some(heartbeat) = config.heartbeat {
+        if elapsed_secs >= heartbeat.as_secs() as i64 {
+            return Some(UpdateReason::Heartbeat { elapsed_secs });
+        }
+    }
+
+    if let (Some(threshold), Some(simulated)) = (config.deviation_bps, simulated) {
+        let bps = deviation_bps(feed.value(), simulated);
+        if bps >= threshold {
+            return Some(UpdateReason::Deviation { bps });
+        }
+    }
+    None
+}
+
+/// The deviation of `new` from `current` in basis points, rounded down.
+/// Any change from zero counts as `u64::MAX`.
+pub fn deviation_bps(current: Decimal, new: Decimal) -> u64 {
+    if current.is_zero() {
+        return if new.is_zero() { 0 } else { u64::MAX };
+    }
+    let bps = ((new - current) / current).abs() * Decimal::from(10_000);
+    bps.floor().to_u64().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle_job::OracleJob;
+    use crate::testing::{MockOracle, MockServer};
+    use std::sync::Mutex;
+
+    fn feed_at(value: i64, slot: u64, timestamp: i64) -> PullFeedAccountData {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.result.value = value as i128 * 10i128.pow(18);
+        feed.result.slot = slot;
+        feed.last_update_timestamp = timestamp;
+        feed
+    }
+
+    #[test]
+    fn test_deviation_bps() {
+        assert_eq!(deviation_bps(Decimal::from(100), Decimal::from(101)), 100);
+        assert_eq!(deviation_bps(Decimal::from(100), Decimal::from(99)), 100);
+        assert_eq!(deviation_bps(Decimal::from(100), Decimal::from(100)), 0);
+        assert_eq!(deviation_bps(Decimal::ZERO, Decimal::ZERO), 0);
+        assert_eq!(deviation_bps(Decimal::ZERO, Decimal::ONE), u64::MAX);
+    }
+
+    #[test]
+    fn test_update_reason() {
+        let config = KeeperFeed {
+            feed: Pubkey::new_unique(),
+            heartbeat: Some(Duration::from_secs(60)),
+            deviation_bps: Some(50),
+            stale_after_slots: None,
+        };
+        let fresh = feed_at(100, 1_000, 10_000);
+        assert_eq!(update_reason(&config, &fresh, None, 1_010, 10_030), None);
+        assert_eq!(
+            update_reason(&config, &feed_at(0, 0, 0), None, 1_010, 10_030),
+            Some(UpdateReason::NeverUpdated)
+        );
+        assert_eq!(
+            update_reason(&config, &fresh, None, 1_010, 10_060),
+            Some(UpdateReason::Heartbeat { elapsed_secs: 60 })
+        );
+        assert_eq!(
+            update_reason(&config, &fresh, Some(Decimal::new(1004, 1)), 1_010, 10_030),
+            None
+        );
+        assert_eq!(
+            update_reason(&config, &fresh, Some(Decimal::from(101)), 1_010, 10_030),
+            Some(UpdateReason::Deviation { bps: 100 })
+        );
+
+        // Falls back to 3/4 of the feed's max staleness.
+        let mut stale = fresh;
+        stale.max_staleness = 100;
+        assert_eq!(update_reason(&config, &stale, None, 1_074, 10_030), None);
+        assert_eq!(
+            update_reason(&config, &stale, None, 1_075, 10_030),
+            Some(UpdateReason::Stale { slots: 75 })
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingMetrics {
+        due: Mutex<Vec<(Pubkey, UpdateReason)>>,
+        sent: Mutex<Vec<(Vec<Pubkey>, u32)>>,
+        failed: Mutex<u32>,
+        errors: Mutex<Vec<String>>,
+    }
+
+    impl KeeperMetrics for RecordingMetrics {
+        fn on_update_due(&self, feed: &Pubkey, reason: &UpdateReason) {
+            self.due.lock().unwrap().push((*feed, reason.clone()));
+        }
+
+        fn on_update_sent(&self, feeds: &[Pubkey], _signature: &Signature, attempts: u32) {
+            self.sent.lock().unwrap().push((feeds.to_vec(), attempts));
+        }
+
+        fn on_update_failed(&self, _feeds: &[Pubkey], _error: &ClientError, _attempt: u32) {
+            *self.failed.lock().unwrap() += 1;
+        }
+
+        fn on_error(&self, error: &ClientError) {
+            self.errors.lock().unwrap().push(error.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_due_feeds_skips_missing_and_invalid_feeds() {
+        let server = MockServer::start(Pubkey::new_unique(), MockOracle::generate(1)).await;
+        server.set_slothash(1_000, [1; 32]);
+        let feeds = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        server.set_account(feeds[1], Pubkey::new_unique(), vec![0; 16]);
+        server.add_pull_feed(feeds[2], vec![OracleJob::builder().value(1.0).build()]);
+        let config = KeeperConfig::new(
+            feeds
+                .iter()
+                .map(|feed| KeeperFeed {
+                    feed: *feed,
+                    ..Default::default()
+                })
+                .collect(),
+            Arc::new(Keypair::new()),
+            server.gateway(),
+            server.crossbar(),
+        );
+        let metrics = Arc::new(RecordingMetrics::default());
+        let keeper =
+            Keeper::new(Arc::new(server.rpc_client()), config).with_metrics(metrics.clone());
+
+        let due = keeper.due_feeds().await.unwrap();
+
+        assert_eq!(due, vec![(feeds[2], UpdateReason::NeverUpdated)]);
+        assert_eq!(metrics.errors.lock().unwrap().len(), 2);
+        assert!(keeper.context().pull_feed_cache.get(&feeds[2]).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_tick_updates_due_feeds() {
+        let server = MockServer::start(Pubkey::new_unique(), MockOracle::generate(3)).await;
+        server.set_slothash(1_000, [1; 32]);
+        let feeds = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        // Fresh and within the deviation threshold.
+        let mut data =
+            server.add_pull_feed(feeds[0], vec![OracleJob::builder().value(100.0).build()]);
+        data.result = feed_at(100, 990, now).result;
+        data.last_update_timestamp = now;
+        server.set_pull_feed(feeds[0], &data);
+        // Fresh but deviating by 10%.
+        let mut data =
+            server.add_pull_feed(feeds[1], vec![OracleJob::builder().value(110.0).build()]);
+        data.result = feed_at(100, 990, now).result;
+        data.last_update_timestamp = now;
+        server.set_pull_feed(feeds[1], &data);
+        // Never updated.
+        server.add_pull_feed(feeds[2], vec![OracleJob::builder().value(1.0).build()]);
+        // The first send fails and is retried.
+        server.set_send_failures(1);
+
+        let mut config = KeeperConfig::new(
+            feeds
+                .iter()
+                .map(|feed| KeeperFeed {
+                    feed: *feed,
+                    heartbeat: Some(Duration::from_secs(3_600)),
+                    deviation_bps: Some(100),
+                    stale_after_slots: Some(100),
+                })
+                .collect(),
+            Arc::new(Keypair::new()),
+            server.gateway(),
+            server.crossbar(),
+        );
+        config.num_signatures = Some(3);
+        config.retry_delay = Duration::ZERO;
+        let metrics = Arc::new(RecordingMetrics::default());
+        let keeper =
+            Keeper::new(Arc::new(server.rpc_client()), config).with_metrics(metrics.clone());
+
+        let signatures = keeper.tick().await.unwrap();
+
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(
+            *metrics.due.lock().unwrap(),
+            vec![
+                (feeds[1], UpdateReason::Deviation { bps: 1_000 }),
+                (feeds[2], UpdateReason::NeverUpdated),
+            ]
+        );
+        assert_eq!(
+            *metrics.sent.lock().unwrap(),
+            vec![(vec![feeds[1], feeds[2]], 2)]
+        );
+        assert_eq!(*metrics.failed.lock().unwrap(), 1);
+        let sent = server.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].signatures[0], signatures[0]);
+    }
+}
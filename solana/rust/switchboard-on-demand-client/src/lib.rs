@@ -1,14 +1,22 @@
 #![allow(clippy::module_inception)]
+pub mod error;
+pub use error::*;
 pub mod instructions;
 pub use instructions::*;
 pub mod crossbar;
 pub use crossbar::*;
 pub mod gateway;
 pub use gateway::*;
+pub mod gateway_pool;
+pub use gateway_pool::*;
 pub mod pull_feed;
 pub use pull_feed::*;
+pub mod randomness;
+pub use randomness::*;
 pub mod associated_token_account;
 pub mod oracle_job;
+pub mod feed_hash;
+pub use feed_hash::*;
 pub use associated_token_account::*;
 pub mod recent_slothashes;
 pub use recent_slothashes::*;
@@ -33,6 +41,18 @@ use solana_sdk::transaction::Transaction;
 use std::str::FromStr;
 pub use prost;
 pub mod secp256k1;
+pub mod verify;
+pub use verify::*;
+pub mod transaction;
+pub use transaction::*;
+pub mod keeper;
+pub use keeper::*;
+pub mod cache;
+pub use cache::*;
+pub mod aggregation;
+pub use aggregation::*;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 lazy_static! {
     pub static ref ON_DEMAND_MAINNET_PID: Pubkey =
@@ -2,8 +2,6 @@ use crate::LUT_SIGNER_SEED;
 use crate::SWITCHBOARD_ON_DEMAND_PROGRAM_ID;
 #[allow(unused_imports)]
 use crate::*;
-use anyhow_ext::anyhow;
-use anyhow_ext::Error as AnyhowError;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::account::Account;
 #[cfg(not(feature = "solana_sdk_1_16"))]
@@ -31,23 +29,34 @@ pub trait LutOwner {
 pub async fn load_lookup_table<T: LutOwner + bytemuck::Pod>(
     client: &RpcClient,
     self_key: Pubkey,
-) -> Result<AddressLookupTableAccount, AnyhowError> {
+) -> Result<AddressLookupTableAccount, ClientError> {
     let account = client
-        .get_account_data(&self_key)
-        .await
-        .map_err(|_| anyhow!("LutOwner.load_lookup_table: Oracle not found"))?;
-    let account = account[8..].to_vec();
-    let data = bytemuck::try_from_bytes::<T>(&account)
-        .map_err(|_| anyhow!("LutOwner.load_lookup_table: Invalid data"))?;
+        .get_account_with_commitment(&self_key, client.commitment())
+        .await?
+        .value
+        .ok_or(ClientError::AccountNotFound(self_key))?;
+    let data = account
+        .data
+        .get(8..)
+        .and_then(|data| bytemuck::try_from_bytes::<T>(data).ok())
+        .ok_or_else(|| ClientError::InvalidAccountData {
+            account: self_key,
+            reason: "Failed to parse LUT owner data".to_string(),
+        })?;
     let lut_slot = data.lut_slot();
     let lut_signer = find_lut_signer(&self_key);
     let lut_key = derive_lookup_table_address(&lut_signer, lut_slot).0;
     let lut_account = client
-        .get_account_data(&lut_key)
-        .await
-        .map_err(|_| anyhow!("LutOwner.load_lookup_table: LUT not found"))?;
-    let parsed_lut = AddressLookupTable::deserialize(&lut_account)
-        .map_err(|_| anyhow!("LutOwner.load_lookup_table: Invalid LUT data"))?;
+        .get_account_with_commitment(&lut_key, client.commitment())
+        .await?
+        .value
+        .ok_or(ClientError::LutMissing(self_key))?;
+    let parsed_lut = AddressLookupTable::deserialize(&lut_account.data).map_err(|e| {
+        ClientError::InvalidAccountData {
+            account: lut_key,
+            reason: format!("Failed to parse lookup table: {}", e),
+        }
+    })?;
     Ok(AddressLookupTableAccount {
         addresses: parsed_lut.addresses.to_vec(),
         key: lut_key,
@@ -64,7 +73,7 @@ fn account_to_vec(account: Option<Account>) -> Vec<u8> {
 pub async fn load_lookup_tables<T: LutOwner + bytemuck::Pod>(
     client: &RpcClient,
     keys: &[Pubkey],
-) -> Result<Vec<AddressLookupTableAccount>, AnyhowError> {
+) -> Result<Vec<AddressLookupTableAccount>, ClientError> {
     let accounts_data = client
         .get_multiple_accounts(keys)
         .await?
@@ -7743,3 +7743,10 @@ pub const FILE_DESCRIPTOR_SET: &[u8] = &[
 ];
 include!("oracle_job.serde.rs");
 // @@protoc_insertion_point(module)
+
+pub mod builder;
+pub use builder::*;
+pub mod executor;
+pub use executor::*;
+pub mod validate;
+pub use validate::*;
@@ -0,0 +1,326 @@
+use super::oracle_job::*;
+use super::OracleJob;
+
+macro_rules! impl_into_task {
+    ($($variant:ident),* $(,)?) => {
+        $(
+            impl From<$variant> for Task {
+                fn from(task: $variant) -> Self {
+                    Task {
+                        task: Some(task::Task::$variant(task)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_into_task!(
+    HttpTask,
+    JsonParseTask,
+    MedianTask,
+    MeanTask,
+    MaxTask,
+    MinTask,
+    ValueTask,
+    WebsocketTask,
+    ConditionalTask,
+    DivideTask,
+    MultiplyTask,
+    AddTask,
+    SubtractTask,
+    RegexExtractTask,
+    PowTask,
+    ComparisonTask,
+    RoundTask,
+    BoundTask,
+    UnixTimeTask,
+);
+
+impl OracleJob {
+    /// Starts building a job from an empty task list.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let job = OracleJob::builder()
+    ///     .http("https://api.example.com/price")
+    ///     .json_parse("$.price")
+    ///     .multiply_by_feed("<feed pubkey>")
+    ///     .build();
+    /// ```
+    pub fn builder() -> OracleJobBuilder {
+        OracleJobBuilder::default()
+    }
+}
+
+/// Chains tasks into an `OracleJob`. Every method appends one task, which
+/// receives the output of the task before it.
+#[derive(Clone, Debug, Default)]
+pub struct OracleJobBuilder {
+    tasks: Vec<Task>,
+    weight: Option<u32>,
+}
+
+impl OracleJobBuilder {
+    /// Appends any task, for the task types without a dedicated method.
+    pub fn task(mut self, task: impl Into<Task>) -> Self {
+        self.tasks.push(task.into());
+        self
+    }
+
+    /// Sets the job's weight when aggregated with other jobs.
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn build(self) -> OracleJob {
+        OracleJob {
+            tasks: self.tasks,
+            weight: self.weight,
+        }
+    }
+
+    pub fn http(self, url: impl Into<String>) -> Self {
+        self.task(HttpTask {
+            url: Some(url.into()),
+            ..Default::default()
+        })
+    }
+
+    /// Appends an `HttpTask` with a method, headers and an optional body.
+    pub fn http_with(
+        self,
+        url: impl Into<String>,
+        method: http_task::Method,
+        headers: &[(&str, &str)],
+        body: Option<String>,
+    ) -> Self {
+        self.task(HttpTask {
+            url: Some(url.into()),
+            method: Some(method as i32),
+            headers: headers
+                .iter()
+                .map(|(key, value)| http_task::Header {
+                    key: Some(key.to_string()),
+                    value: Some(value.to_string()),
+                })
+                .collect(),
+            body,
+        })
+    }
+
+    pub fn websocket(self, url: impl Into<String>, subscription: impl Into<String>) -> Self {
+        self.task(WebsocketTask {
+            url: Some(url.into()),
+            subscription: Some(subscription.into()),
+            ..Default::default()
+        })
+    }
+
+    pub fn json_parse(self, path: impl Into<String>) -> Self {
+        self.task(JsonParseTask {
+            path: Some(path.into()),
+            aggregation_method: None,
+        })
+    }
+
+    /// Appends a `JsonParseTask` reducing every match of `path` with
+    /// `aggregation`.
+    pub fn json_parse_with(
+        self,
+        path: impl Into<String>,
+        aggregation: json_parse_task::AggregationMethod,
+    ) -> Self {
+        self.task(JsonParseTask {
+            path: Some(path.into()),
+            aggregation_method: Some(aggregation as i32),
+        })
+    }
+
+    pub fn regex_extract(self, pattern: impl Into<String>, group_number: i32) -> Self {
+        self.task(RegexExtractTask {
+            pattern: Some(pattern.into()),
+            group_number: Some(group_number),
+        })
+    }
+
+    pub fn value(self, value: f64) -> Self {
+        self.task(ValueTask {
+            value: Some(value_task::Value::Value(value)),
+        })
+    }
+
+    /// Appends a `ValueTask` holding a decimal string, without the precision
+    /// loss of an f64.
+    pub fn big_value(self, value: impl Into<String>) -> Self {
+        self.task(ValueTask {
+            value: Some(value_task::Value::Big(value.into())),
+        })
+    }
+
+    /// Appends a `ValueTask` resolving to the current value of a feed.
+    pub fn feed_value(self, feed: impl ToString) -> Self {
+        self.task(ValueTask {
+            value: Some(value_task::Value::AggregatorPubkey(feed.to_string())),
+        })
+    }
+
+    pub fn add(self, scalar: f64) -> Self {
+        self.task(AddTask {
+            addition: Some(add_task::Addition::Scalar(scalar)),
+        })
+    }
+
+    pub fn add_feed(self, feed: impl ToString) -> Self {
+        self.task(AddTask {
+            addition: Some(add_task::Addition::AggregatorPubkey(feed.to_string())),
+        })
+    }
+
+    pub fn add_job(self, job: OracleJob) -> Self {
+        self.task(AddTask {
+            addition: Some(add_task::Addition::Job(job)),
+        })
+    }
+
+    pub fn subtract(self, scalar: f64) -> Self {
+        self.task(SubtractTask {
+            subtraction: Some(subtract_task::Subtraction::Scalar(scalar)),
+        })
+    }
+
+    pub fn subtract_feed(self, feed: impl ToString) -> Self {
+        self.task(SubtractTask {
+            subtraction: Some(subtract_task::Subtraction::AggregatorPubkey(
+                feed.to_string(),
+            )),
+        })
+    }
+
+    pub fn subtract_job(self, job: OracleJob) -> Self {
+        self.task(SubtractTask {
+            subtraction: Some(subtract_task::Subtraction::Job(job)),
+        })
+    }
+
+    pub fn multiply(self, scalar: f64) -> Self {
+        self.task(MultiplyTask {
+            multiple: Some(multiply_task::Multiple::Scalar(scalar)),
+        })
+    }
+
+    pub fn multiply_by_feed(self, feed: impl ToString) -> Self {
+        self.task(MultiplyTask {
+            multiple: Some(multiply_task::Multiple::AggregatorPubkey(feed.to_string())),
+        })
+    }
+
+    pub fn multiply_by_job(self, job: OracleJob) -> Self {
+        self.task(MultiplyTask {
+            multiple: Some(multiply_task::Multiple::Job(job)),
+        })
+    }
+
+    pub fn divide(self, scalar: f64) -> Self {
+        self.task(DivideTask {
+            denominator: Some(divide_task::Denominator::Scalar(scalar)),
+        })
+    }
+
+    pub fn divide_by_feed(self, feed: impl ToString) -> Self {
+        self.task(DivideTask {
+            denominator: Some(divide_task::Denominator::AggregatorPubkey(feed.to_string())),
+        })
+    }
+
+    pub fn divide_by_job(self, job: OracleJob) -> Self {
+        self.task(DivideTask {
+            denominator: Some(divide_task::Denominator::Job(job)),
+        })
+    }
+
+    pub fn pow(self, exponent: f64) -> Self {
+        self.task(PowTask {
+            exponent: Some(pow_task::Exponent::Scalar(exponent)),
+        })
+    }
+
+    pub fn round(self, method: round_task::Method, decimals: i32) -> Self {
+        self.task(RoundTask {
+            method: Some(method as i32),
+            decimals: Some(decimals),
+        })
+    }
+
+    /// Appends a `BoundTask` clamping the value to the given bounds.
+    pub fn bound(self, lower: Option<&str>, upper: Option<&str>) -> Self {
+        self.task(BoundTask {
+            lower_bound_value: lower.map(str::to_string),
+            upper_bound_value: upper.map(str::to_string),
+            ..Default::default()
+        })
+    }
+
+    pub fn median(self, jobs: Vec<OracleJob>) -> Self {
+        self.task(MedianTask {
+            jobs,
+            ..Default::default()
+        })
+    }
+
+    pub fn mean(self, jobs: Vec<OracleJob>) -> Self {
+        self.task(MeanTask {
+            jobs,
+            ..Default::default()
+        })
+    }
+
+    pub fn min(self, jobs: Vec<OracleJob>) -> Self {
+        self.task(MinTask {
+            jobs,
+            ..Default::default()
+        })
+    }
+
+    pub fn max(self, jobs: Vec<OracleJob>) -> Self {
+        self.task(MaxTask {
+            jobs,
+            ..Default::default()
+        })
+    }
+
+    /// Appends a `ConditionalTask` running `on_failure` on the same input
+    /// if any task of `attempt` fails.
+    pub fn conditional(self, attempt: OracleJob, on_failure: OracleJob) -> Self {
+        self.task(ConditionalTask {
+            attempt: attempt.tasks,
+            on_failure: on_failure.tasks,
+        })
+    }
+
+    /// Appends a `ComparisonTask` comparing the current value with `rhs`
+    /// and resolving to `on_true` or `on_false`.
+    pub fn compare(
+        self,
+        op: comparison_task::Operation,
+        rhs: impl Into<String>,
+        on_true: impl Into<String>,
+        on_false: impl Into<String>,
+    ) -> Self {
+        self.task(ComparisonTask {
+            op: Some(op as i32),
+            rhs: Some(comparison_task::Rhs::RhsValue(rhs.into())),
+            on_true_value: Some(on_true.into()),
+            on_false_value: Some(on_false.into()),
+            ..Default::default()
+        })
+    }
+
+    pub fn unix_time(self, offset: i32) -> Self {
+        self.task(UnixTimeTask {
+            offset: Some(offset),
+        })
+    }
+}
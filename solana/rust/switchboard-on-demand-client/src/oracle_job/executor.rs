@@ -0,0 +1,699 @@
+use super::oracle_job::*;
+use super::OracleJob;
+use anyhow_ext::anyhow;
+use anyhow_ext::Error as AnyhowError;
+use regex::Regex;
+use rust_decimal::prelude::*;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Supplies the data for the tasks the executor cannot compute on its own.
+///
+/// Every method fails by default, so a fetcher only implements the sources
+/// the jobs under test use.
+pub trait TaskFetcher {
+    fn http(&self, task: &HttpTask) -> Result<String, AnyhowError> {
+        Err(anyhow!(
+            "TaskFetcher.http: No response for {}",
+            task.url.as_deref().unwrap_or_default()
+        ))
+    }
+
+    fn websocket(&self, task: &WebsocketTask) -> Result<String, AnyhowError> {
+        Err(anyhow!(
+            "TaskFetcher.websocket: No response for {}",
+            task.url.as_deref().unwrap_or_default()
+        ))
+    }
+
+    /// The current value of the feed at `feed`, for tasks referencing an
+    /// aggregator pubkey.
+    fn feed_value(&self, feed: &str) -> Result<Decimal, AnyhowError> {
+        Err(anyhow!("TaskFetcher.feed_value: No value for {}", feed))
+    }
+}
+
+/// A fetcher that fails every request.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OfflineFetcher;
+
+impl TaskFetcher for OfflineFetcher {}
+
+/// A fetcher serving canned responses keyed by url and feed pubkey.
+#[derive(Clone, Debug, Default)]
+pub struct StaticFetcher {
+    pub http: HashMap<String, String>,
+    pub websocket: HashMap<String, String>,
+    pub feeds: HashMap<String, Decimal>,
+}
+
+impl StaticFetcher {
+    pub fn with_http(mut self, url: impl Into<String>, body: impl Into<String>) -> Self {
+        self.http.insert(url.into(), body.into());
+        self
+    }
+
+    pub fn with_websocket(mut self, url: impl Into<String>, message: impl Into<String>) -> Self {
+        self.websocket.insert(url.into(), message.into());
+        self
+    }
+
+    pub fn with_feed(mut self, feed: impl ToString, value: Decimal) -> Self {
+        self.feeds.insert(feed.to_string(), value);
+        self
+    }
+}
+
+impl TaskFetcher for StaticFetcher {
+    fn http(&self, task: &HttpTask) -> Result<String, AnyhowError> {
+        let url = task.url.as_deref().unwrap_or_default();
+        self.http
+            .get(url)
+            .cloned()
+            .ok_or_else(|| anyhow!("StaticFetcher.http: No response for {}", url))
+    }
+
+    fn websocket(&self, task: &WebsocketTask) -> Result<String, AnyhowError> {
+        let url = task.url.as_deref().unwrap_or_default();
+        self.websocket
+            .get(url)
+            .cloned()
+            .ok_or_else(|| anyhow!("StaticFetcher.websocket: No response for {}", url))
+    }
+
+    fn feed_value(&self, feed: &str) -> Result<Decimal, AnyhowError> {
+        self.feeds
+            .get(feed)
+            .copied()
+            .ok_or_else(|| anyhow!("StaticFetcher.feed_value: No value for {}", feed))
+    }
+}
+
+/// The value passed from one task to the next.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskOutput {
+    Text(String),
+    Number(Decimal),
+}
+
+impl TaskOutput {
+    pub fn as_number(&self) -> Result<Decimal, AnyhowError> {
+        match self {
+            TaskOutput::Number(value) => Ok(*value),
+            TaskOutput::Text(text) => parse_decimal(text.trim()),
+        }
+    }
+
+    pub fn as_text(&self) -> String {
+        match self {
+            TaskOutput::Number(value) => value.normalize().to_string(),
+            TaskOutput::Text(text) => text.clone(),
+        }
+    }
+}
+
+/// Runs `OracleJob`s locally so they can be tested before being stored.
+///
+/// Only deterministic tasks are supported: values, json parsing, regex
+/// extraction, arithmetic, rounding, bounds, aggregations, comparisons,
+/// conditionals and the unix time. Http and websocket tasks and feed
+/// references are delegated to the `TaskFetcher`. Any other task fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let fetcher = StaticFetcher::default()
+///     .with_http("https://api.example.com/price", r#"{"price": "101.5"}"#);
+/// let value = JobExecutor::with_fetcher(fetcher).run(&job)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JobExecutor<F: TaskFetcher = OfflineFetcher> {
+    fetcher: F,
+    unix_time: Option<i64>,
+}
+
+impl JobExecutor<OfflineFetcher> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: TaskFetcher> JobExecutor<F> {
+    pub fn with_fetcher(fetcher: F) -> Self {
+        Self {
+            fetcher,
+            unix_time: None,
+        }
+    }
+
+    /// Pins the time returned by `UnixTimeTask`, instead of the system clock.
+    pub fn with_unix_time(mut self, unix_time: i64) -> Self {
+        self.unix_time = Some(unix_time);
+        self
+    }
+
+    /// Runs every task of `job` and returns its numeric result.
+    pub fn run(&self, job: &OracleJob) -> Result<Decimal, AnyhowError> {
+        self.run_tasks(&job.tasks, None)?.as_number()
+    }
+
+    /// Runs `tasks` in order, feeding `input` to the first one.
+    pub fn run_tasks(
+        &self,
+        tasks: &[Task],
+        input: Option<TaskOutput>,
+    ) -> Result<TaskOutput, AnyhowError> {
+        let mut output = input;
+        for task in tasks {
+            output = Some(self.run_task(task, output.as_ref())?);
+        }
+        output.ok_or_else(|| anyhow!("JobExecutor.run_tasks: Job has no tasks"))
+    }
+
+    pub fn run_task(
+        &self,
+        task: &Task,
+        input: Option<&TaskOutput>,
+    ) -> Result<TaskOutput, AnyhowError> {
+        let task = task
+            .task
+            .as_ref()
+            .ok_or_else(|| anyhow!("JobExecutor.run_task: Empty task"))?;
+        let number = |input: Option<&TaskOutput>| -> Result<Decimal, AnyhowError> {
+            input
+                .ok_or_else(|| anyhow!("JobExecutor.run_task: Task requires an input"))?
+                .as_number()
+        };
+        let output = match task {
+            task::Task::HttpTask(task) => TaskOutput::Text(self.fetcher.http(task)?),
+            task::Task::WebsocketTask(task) => TaskOutput::Text(self.fetcher.websocket(task)?),
+            task::Task::ValueTask(task) => match &task.value {
+                Some(value_task::Value::Value(value)) => TaskOutput::Number(from_f64(*value)?),
+                Some(value_task::Value::Big(value)) => TaskOutput::Number(parse_decimal(value)?),
+                Some(value_task::Value::AggregatorPubkey(feed)) => {
+                    TaskOutput::Number(self.fetcher.feed_value(feed)?)
+                }
+                Some(value_task::Value::Hex(value)) | Some(value_task::Value::Utf8(value)) => {
+                    TaskOutput::Text(value.clone())
+                }
+                None => return Err(anyhow!("JobExecutor.run_task: ValueTask has no value")),
+            },
+            task::Task::JsonParseTask(task) => {
+                let input = input
+                    .ok_or_else(|| anyhow!("JobExecutor.run_task: JsonParseTask requires input"))?;
+                json_parse(&input.as_text(), task)?
+            }
+            task::Task::RegexExtractTask(task) => {
+                let input = input.ok_or_else(|| {
+                    anyhow!("JobExecutor.run_task: RegexExtractTask requires input")
+                })?;
+                regex_extract(&input.as_text(), task)?
+            }
+            task::Task::AddTask(task) => {
+                let rhs = match &task.addition {
+                    Some(add_task::Addition::Scalar(value)) => from_f64(*value)?,
+                    Some(add_task::Addition::Big(value)) => parse_decimal(value)?,
+                    Some(add_task::Addition::AggregatorPubkey(feed)) => {
+                        self.fetcher.feed_value(feed)?
+                    }
+                    Some(add_task::Addition::Job(job)) => self.run(job)?,
+                    None => return Err(anyhow!("JobExecutor.run_task: AddTask has no operand")),
+                };
+                TaskOutput::Number(checked(number(input)?.checked_add(rhs))?)
+            }
+            task::Task::SubtractTask(task) => {
+                let rhs = match &task.subtraction {
+                    Some(subtract_task::Subtraction::Scalar(value)) => from_f64(*value)?,
+                    Some(subtract_task::Subtraction::Big(value)) => parse_decimal(value)?,
+                    Some(subtract_task::Subtraction::AggregatorPubkey(feed)) => {
+                        self.fetcher.feed_value(feed)?
+                    }
+                    Some(subtract_task::Subtraction::Job(job)) => self.run(job)?,
+                    None => {
+                        return Err(anyhow!("JobExecutor.run_task: SubtractTask has no operand"))
+                    }
+                };
+                TaskOutput::Number(checked(number(input)?.checked_sub(rhs))?)
+            }
+            task::Task::MultiplyTask(task) => {
+                let rhs = match &task.multiple {
+                    Some(multiply_task::Multiple::Scalar(value)) => from_f64(*value)?,
+                    Some(multiply_task::Multiple::Big(value)) => parse_decimal(value)?,
+                    Some(multiply_task::Multiple::AggregatorPubkey(feed)) => {
+                        self.fetcher.feed_value(feed)?
+                    }
+                    Some(multiply_task::Multiple::Job(job)) => self.run(job)?,
+                    None => {
+                        return Err(anyhow!("JobExecutor.run_task: MultiplyTask has no operand"))
+                    }
+                };
+                TaskOutput::Number(checked(number(input)?.checked_mul(rhs))?)
+            }
+            task::Task::DivideTask(task) => {
+                let rhs = match &task.denominator {
+                    Some(divide_task::Denominator::Scalar(value)) => from_f64(*value)?,
+                    Some(divide_task::Denominator::Big(value)) => parse_decimal(value)?,
+                    Some(divide_task::Denominator::AggregatorPubkey(feed)) => {
+                        self.fetcher.feed_value(feed)?
+                    }
+                    Some(divide_task::Denominator::Job(job)) => self.run(job)?,
+                    None => {
+                        return Err(anyhow!(
+                            "JobExecutor.run_task: DivideTask has no denominator"
+                        ))
+                    }
+                };
+                TaskOutput::Number(checked(number(input)?.checked_div(rhs))?)
+            }
+            task::Task::PowTask(task) => {
+                let exponent = match &task.exponent {
+                    Some(pow_task::Exponent::Scalar(value)) => from_f64(*value)?,
+                    Some(pow_task::Exponent::Big(value)) => parse_decimal(value)?,
+                    Some(pow_task::Exponent::AggregatorPubkey(feed)) => {
+                        self.fetcher.feed_value(feed)?
+                    }
+                    None => return Err(anyhow!("JobExecutor.run_task: PowTask has no exponent")),
+                };
+                TaskOutput::Number(pow(number(input)?, exponent)?)
+            }
+            task::Task::RoundTask(task) => {
+                let strategy = match task.method() {
+                    round_task::Method::RoundUp => RoundingStrategy::MidpointAwayFromZero,
+                    round_task::Method::RoundDown => RoundingStrategy::ToZero,
+                };
+                let decimals = u32::try_from(task.decimals.unwrap_or(0))
+                    .map_err(|_| anyhow!("JobExecutor.run_task: Negative RoundTask decimals"))?;
+                TaskOutput::Number(number(input)?.round_dp_with_strategy(decimals, strategy))
+            }
+            task::Task::BoundTask(task) => TaskOutput::Number(self.bound(number(input)?, task)?),
+            task::Task::MedianTask(task) => {
+                let mut values = self.collect(&task.tasks, &task.jobs, input)?;
+                let min_successful = task.min_successful_required.unwrap_or(1).max(1) as usize;
+                if values.len() < min_successful {
+                    return Err(anyhow!(
+                        "JobExecutor.run_task: MedianTask has {} of {} required results",
+                        values.len(),
+                        min_successful
+                    ));
+                }
+                TaskOutput::Number(median(&mut values)?)
+            }
+            task::Task::MeanTask(task) => {
+                let values = self.collect(&task.tasks, &task.jobs, input)?;
+                TaskOutput::Number(mean(&values)?)
+            }
+            task::Task::MinTask(task) => {
+                let values = self.collect(&task.tasks, &task.jobs, input)?;
+                TaskOutput::Number(checked(values.into_iter().min())?)
+            }
+            task::Task::MaxTask(task) => {
+                let values = self.collect(&task.tasks, &task.jobs, input)?;
+                TaskOutput::Number(checked(values.into_iter().max())?)
+            }
+            task::Task::ConditionalTask(task) => {
+                match self.run_tasks(&task.attempt, input.cloned()) {
+                    Ok(output) => output,
+                    Err(_) => self.run_tasks(&task.on_failure, input.cloned())?,
+                }
+            }
+            task::Task::ComparisonTask(task) => self.compare(task, input)?,
+            task::Task::UnixTimeTask(task) => {
+                let now = match self.unix_time {
+                    Some(unix_time) => unix_time,
+                    None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+                };
+                TaskOutput::Number(Decimal::from(now + task.offset.unwrap_or(0) as i64))
+            }
+            other => {
+                return Err(anyhow!(
+                    "JobExecutor.run_task: Unsupported task {}",
+                    task_name(other)
+                ))
+            }
+        };
+        Ok(output)
+    }
+
+    /// Runs every subtask on the current input and every subjob on its own,
+    /// skipping the ones that fail.
+    fn collect(
+        &self,
+        tasks: &[Task],
+        jobs: &[OracleJob],
+        input: Option<&TaskOutput>,
+    ) -> Result<Vec<Decimal>, AnyhowError> {
+        let from_tasks = tasks.iter().filter_map(|task| {
+            self.run_task(task, input)
+                .and_then(|output| output.as_number())
+                .ok()
+        });
+        let from_jobs = jobs.iter().filter_map(|job| self.run(job).ok());
+        let values: Vec<Decimal> = from_tasks.chain(from_jobs).collect();
+        if values.is_empty() {
+            return Err(anyhow!("JobExecutor.collect: No successful results"));
+        }
+        Ok(values)
+    }
+
+    fn resolve(
+        &self,
+        job: Option<&OracleJob>,
+        value: Option<&String>,
+    ) -> Result<Option<Decimal>, AnyhowError> {
+        match (value, job) {
+            (Some(value), _) => Ok(Some(parse_decimal(value)?)),
+            (None, Some(job)) => Ok(Some(self.run(job)?)),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn bound(&self, value: Decimal, task: &BoundTask) -> Result<Decimal, AnyhowError> {
+        let lower = self.resolve(task.lower_bound.as_ref(), task.lower_bound_value.as_ref())?;
+        let upper = self.resolve(task.upper_bound.as_ref(), task.upper_bound_value.as_ref())?;
+        if let Some(lower) = lower.filter(|lower| value < *lower) {
+            let replacement = self.resolve(
+                task.on_exceeds_lower_bound.as_ref(),
+                task.on_exceeds_lower_bound_value.as_ref(),
+            )?;
+            return Ok(replacement.unwrap_or(lower));
+        }
+        if let Some(upper) = upper.filter(|upper| value > *upper) {
+            let replacement = self.resolve(
+                task.on_exceeds_upper_bound.as_ref(),
+                task.on_exceeds_upper_bound_value.as_ref(),
+            )?;
+            return Ok(replacement.unwrap_or(upper));
+        }
+        Ok(value)
+    }
+
+    fn compare(
+        &self,
+        task: &ComparisonTask,
+        input: Option<&TaskOutput>,
+    ) -> Result<TaskOutput, AnyhowError> {
+        let outcome = (|| -> Result<bool, AnyhowError> {
+            let lhs = match &task.lhs {
+                Some(comparison_task::Lhs::Lhs(job)) => self.run(job)?,
+                Some(comparison_task::Lhs::LhsValue(value)) => parse_decimal(value)?,
+                None => input
+                    .ok_or_else(|| anyhow!("JobExecutor.compare: Missing lhs"))?
+                    .as_number()?,
+            };
+            let rhs = match &task.rhs {
+                Some(comparison_task::Rhs::Rhs(job)) => self.run(job)?,
+                Some(comparison_task::Rhs::RhsValue(value)) => parse_decimal(value)?,
+                None => return Err(anyhow!("JobExecutor.compare: Missing rhs")),
+            };
+            Ok(match task.op() {
+                comparison_task::Operation::Eq => lhs == rhs,
+                comparison_task::Operation::Gt => lhs > rhs,
+                comparison_task::Operation::Lt => lhs < rhs,
+            })
+        })();
+        let (job, value) = match outcome {
+            Ok(true) => (task.on_true.as_ref(), task.on_true_value.as_ref()),
+            Ok(false) => (task.on_false.as_ref(), task.on_false_value.as_ref()),
+            Err(err) => match (task.on_failure.as_ref(), task.on_failure_value.as_ref()) {
+                (None, None) => return Err(err),
+                branch => branch,
+            },
+        };
+        self.resolve(job, value)?
+            .map(TaskOutput::Number)
+            .ok_or_else(|| anyhow!("JobExecutor.compare: No result for comparison branch"))
+    }
+}
+
+fn json_parse(input: &str, task: &JsonParseTask) -> Result<TaskOutput, AnyhowError> {
+    let json: JsonValue = serde_json::from_str(input)
+        .map_err(|e| anyhow!("JobExecutor.json_parse: Invalid JSON: {}", e))?;
+    let path = task.path.as_deref().unwrap_or("$");
+    let matches = json_path(&json, path)?;
+    let aggregation = task.aggregation_method();
+    if aggregation == json_parse_task::AggregationMethod::None {
+        return match matches.as_slice() {
+            [single] => Ok(json_output(single)),
+            [] => Err(anyhow!("JobExecutor.json_parse: No match for {}", path)),
+            _ => Err(anyhow!(
+                "JobExecutor.json_parse: {} matches for {} without an aggregation method",
+                matches.len(),
+                path
+            )),
+        };
+    }
+    let mut values = matches
+        .iter()
+        .map(|value| json_output(value).as_number())
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.is_empty() {
+        return Err(anyhow!("JobExecutor.json_parse: No match for {}", path));
+    }
+    let value = match aggregation {
+        json_parse_task::AggregationMethod::Min => checked(values.into_iter().min())?,
+        json_parse_task::AggregationMethod::Max => checked(values.into_iter().max())?,
+        json_parse_task::AggregationMethod::Sum => values
+            .into_iter()
+            .try_fold(Decimal::ZERO, |sum, value| sum.checked_add(value))
+            .ok_or_else(|| anyhow!("JobExecutor.json_parse: Overflow"))?,
+        json_parse_task::AggregationMethod::Mean => mean(&values)?,
+        json_parse_task::AggregationMethod::Median => median(&mut values)?,
+        json_parse_task::AggregationMethod::None => unreachable!(),
+    };
+    Ok(TaskOutput::Number(value))
+}
+
+fn json_output(value: &JsonValue) -> TaskOutput {
+    match value {
+        JsonValue::String(text) => TaskOutput::Text(text.clone()),
+        other => TaskOutput::Text(other.to_string()),
+    }
+}
+
+/// Resolves the JSONPath subset used by oracle jobs: `$`, `.key`,
+/// `['key']`, `[index]`, `[*]` and `.*`.
+pub fn json_path<'a>(root: &'a JsonValue, path: &str) -> Result<Vec<&'a JsonValue>, AnyhowError> {
+    let invalid = || anyhow!("JobExecutor.json_path: Invalid path {}", path);
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut current = vec![root];
+    while !rest.is_empty() {
+        let (segment, next) = if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            (&after[..end], &after[end + 1..])
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        } else {
+            return Err(invalid());
+        };
+        let segment = segment.trim();
+        let quoted = segment
+            .strip_prefix('\'')
+            .and_then(|s| s.strip_suffix('\''))
+            .or_else(|| segment.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&JsonValue> {
+                match (quoted, segment) {
+                    (None, "*") => match value {
+                        JsonValue::Array(items) => items.iter().collect(),
+                        JsonValue::Object(fields) => fields.values().collect(),
+                        _ => vec![],
+                    },
+                    (Some(key), _) => value.get(key).into_iter().collect(),
+                    (None, segment) => match segment.parse::<usize>() {
+                        Ok(index) if value.is_array() => value.get(index).into_iter().collect(),
+                        _ => value.get(segment).into_iter().collect(),
+                    },
+                }
+            })
+            .collect();
+        rest = next;
+    }
+    Ok(current)
+}
+
+fn regex_extract(input: &str, task: &RegexExtractTask) -> Result<TaskOutput, AnyhowError> {
+    let pattern = task.pattern.as_deref().unwrap_or_default();
+    let regex = Regex::new(pattern)
+        .map_err(|e| anyhow!("JobExecutor.regex_extract: Invalid pattern: {}", e))?;
+    let group = task.group_number.unwrap_or(0).max(0) as usize;
+    regex
+        .captures(input)
+        .and_then(|captures| captures.get(group))
+        .map(|capture| TaskOutput::Text(capture.as_str().to_string()))
+        .ok_or_else(|| anyhow!("JobExecutor.regex_extract: No match for {}", pattern))
+}
+
+/// The largest integer exponent `pow` raises a number to exactly.
+const MAX_INTEGER_EXPONENT: u64 = 1 << 20;
+
+fn pow(base: Decimal, exponent: Decimal) -> Result<Decimal, AnyhowError> {
+    if exponent.fract().is_zero() {
+        let power = exponent
+            .abs()
+            .to_u64()
+            .filter(|power| *power <= MAX_INTEGER_EXPONENT)
+            .ok_or_else(|| anyhow!("JobExecutor.pow: Exponent out of range"))?;
+        // Square and multiply, without squaring past the highest bit.
+        let (mut result, mut square, mut power) = (Decimal::ONE, base, power);
+        while power > 0 {
+            if power & 1 == 1 {
+                result = checked(result.checked_mul(square))?;
+            }
+            power >>= 1;
+            if power > 0 {
+                square = checked(square.checked_mul(square))?;
+            }
+        }
+        if exponent.is_sign_negative() {
+            result = checked(Decimal::ONE.checked_div(result))?;
+        }
+        return Ok(result);
+    }
+    let base = checked(base.to_f64())?;
+    let exponent = checked(exponent.to_f64())?;
+    from_f64(base.powf(exponent))
+}
+
+fn median(values: &mut [Decimal]) -> Result<Decimal, AnyhowError> {
+    values.sort();
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => Err(anyhow!("JobExecutor.median: No values")),
+        len if len % 2 == 1 => Ok(values[mid]),
+        _ => checked(
+            values[mid - 1]
+                .checked_add(values[mid])
+                .and_then(|sum| sum.checked_div(Decimal::TWO)),
+        ),
+    }
+}
+
+fn mean(values: &[Decimal]) -> Result<Decimal, AnyhowError> {
+    let sum = values
+        .iter()
+        .try_fold(Decimal::ZERO, |sum, value| sum.checked_add(*value));
+    checked(sum.and_then(|sum| sum.checked_div(Decimal::from(values.len()))))
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, AnyhowError> {
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map_err(|_| anyhow!("JobExecutor: Invalid number {:?}", value))
+}
+
+fn from_f64(value: f64) -> Result<Decimal, AnyhowError> {
+    Decimal::from_f64(value).ok_or_else(|| anyhow!("JobExecutor: Invalid number {}", value))
+}
+
+fn checked<T>(value: Option<T>) -> Result<T, AnyhowError> {
+    value.ok_or_else(|| anyhow!("JobExecutor: Arithmetic overflow or empty input"))
+}
+
+fn task_name(task: &task::Task) -> String {
+    format!("{:?}", task)
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle_job::OracleJob;
+
+    #[test]
+    fn runs_http_json_pipeline() {
+        let fetcher = StaticFetcher::default()
+            .with_http("https://api.test/price", r#"{"data": {"price": "101.5"}}"#)
+            .with_feed("feed", Decimal::from(2));
+        let job = OracleJob::builder()
+            .http("https://api.test/price")
+            .json_parse("$.data.price")
+            .multiply_by_feed("feed")
+            .add(1.0)
+            .build();
+        let value = JobExecutor::with_fetcher(fetcher).run(&job).unwrap();
+        assert_eq!(value, Decimal::from_str("204").unwrap());
+    }
+
+    #[test]
+    fn aggregates_json_matches() {
+        let fetcher = StaticFetcher::default().with_http(
+            "https://api.test/book",
+            r#"{"bids": [[1, 5], [3, 1], [2, 7]]}"#,
+        );
+        let job = OracleJob::builder()
+            .http("https://api.test/book")
+            .json_parse_with("$.bids[*][0]", json_parse_task::AggregationMethod::Median)
+            .build();
+        let value = JobExecutor::with_fetcher(fetcher).run(&job).unwrap();
+        assert_eq!(value, Decimal::from(2));
+    }
+
+    #[test]
+    fn rounds_bounds_and_falls_back() {
+        let job = OracleJob::builder()
+            .value(1.23456)
+            .round(round_task::Method::RoundDown, 2)
+            .bound(Some("0"), Some("1"))
+            .build();
+        assert_eq!(JobExecutor::new().run(&job).unwrap(), Decimal::ONE);
+
+        let failing = OracleJob::builder().http("https://api.test/down").build();
+        let fallback = OracleJob::builder().value(7.0).build();
+        let job = OracleJob::builder().conditional(failing, fallback).build();
+        assert_eq!(JobExecutor::new().run(&job).unwrap(), Decimal::from(7));
+    }
+
+    #[test]
+    fn medians_subjobs_and_compares() {
+        let jobs = [1.0, 9.0, 4.0]
+            .iter()
+            .map(|value| OracleJob::builder().value(*value).build())
+            .collect();
+        let job = OracleJob::builder()
+            .median(jobs)
+            .compare(comparison_task::Operation::Gt, "3", "1", "0")
+            .build();
+        assert_eq!(JobExecutor::new().run(&job).unwrap(), Decimal::ONE);
+    }
+
+    #[test]
+    fn extracts_regex_and_time() {
+        let fetcher =
+            StaticFetcher::default().with_http("https://api.test/page", "price: 42.5 USD");
+        let job = OracleJob::builder()
+            .http("https://api.test/page")
+            .regex_extract(r"price: ([0-9.]+)", 1)
+            .build();
+        let value = JobExecutor::with_fetcher(fetcher).run(&job).unwrap();
+        assert_eq!(value, Decimal::from_str("42.5").unwrap());
+
+        let job = OracleJob::builder().unix_time(-10).build();
+        let executor = JobExecutor::new().with_unix_time(1_000);
+        assert_eq!(executor.run(&job).unwrap(), Decimal::from(990));
+    }
+
+    #[test]
+    fn raises_to_integer_powers() {
+        let two = Decimal::TWO;
+        assert_eq!(pow(two, Decimal::from(10)).unwrap(), Decimal::from(1024));
+        assert_eq!(pow(two, Decimal::ZERO).unwrap(), Decimal::ONE);
+        assert_eq!(
+            pow(two, Decimal::from(-2)).unwrap(),
+            Decimal::from_str("0.25").unwrap()
+        );
+        assert_eq!(pow(-two, Decimal::from(3)).unwrap(), Decimal::from(-8));
+        assert_eq!(
+            pow(Decimal::ONE, Decimal::from(MAX_INTEGER_EXPONENT)).unwrap(),
+            Decimal::ONE
+        );
+        assert!(pow(two, Decimal::from(200)).is_err());
+        assert!(pow(Decimal::ONE, Decimal::from(1_000_000_000_000_000_000u64)).is_err());
+    }
+}
@@ -0,0 +1,680 @@
+use super::oracle_job::*;
+use super::OracleJob;
+use lazy_static::lazy_static;
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref VARIABLE_REF: Regex = Regex::new(r"\$\{([A-Za-z0-9_]+)\}").unwrap();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The job will run but likely not as intended.
+    Warning,
+    /// The job will fail or produce a meaningless result.
+    Error,
+}
+
+/// A problem found in an `OracleJob` by `validate_job`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobIssue {
+    pub severity: Severity,
+    /// The index of the offending task in the job's top level task list.
+    pub task_index: usize,
+    /// The full path to the offending task, e.g. `tasks[2].jobs[0].tasks[1]`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for JobIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} at {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+/// Walks every task of `job`, including nested jobs and task lists, and
+/// reports structural problems without executing or fetching anything.
+///
+/// # Examples
+///
+/// ```ignore
+/// let issues = validate_job(&job);
+/// if issues.iter().any(|issue| issue.severity == Severity::Error) {
+///     return Err(anyhow!("Invalid job: {}", issues[0]));
+/// }
+/// ```
+pub fn validate_job(job: &OracleJob) -> Vec<JobIssue> {
+    let mut validator = Validator::default();
+    validator.job(job, "", None, &mut Scope::default());
+    validator.issues
+}
+
+/// Validates several jobs, returning every issue with the index of its job.
+pub fn validate_jobs(jobs: &[OracleJob]) -> Vec<(usize, JobIssue)> {
+    jobs.iter()
+        .enumerate()
+        .flat_map(|(idx, job)| validate_job(job).into_iter().map(move |issue| (idx, issue)))
+        .collect()
+}
+
+#[derive(Clone, Default)]
+struct Scope {
+    /// Variables defined by the `CacheTask`s run so far.
+    variables: HashSet<String>,
+    /// Whether a `SecretsTask` has run, which defines variables that cannot
+    /// be known offline.
+    has_secrets: bool,
+    /// Whether an http or websocket response is available to parse.
+    has_source: bool,
+}
+
+#[derive(Default)]
+struct Validator {
+    issues: Vec<JobIssue>,
+}
+
+impl Validator {
+    fn report(&mut self, severity: Severity, root: Option<usize>, path: &str, message: String) {
+        self.issues.push(JobIssue {
+            severity,
+            task_index: root.unwrap_or(0),
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn job(&mut self, job: &OracleJob, prefix: &str, root: Option<usize>, scope: &mut Scope) {
+        if job.tasks.is_empty() {
+            let path = if prefix.is_empty() { "job" } else { prefix };
+            self.report(Severity::Error, root, path, "Job has no tasks".to_string());
+        }
+        let mut job_scope = Scope {
+            has_source: false,
+            ..scope.clone()
+        };
+        self.tasks(&job.tasks, &join(prefix, "tasks"), root, &mut job_scope);
+        scope.variables.extend(job_scope.variables);
+        scope.has_secrets |= job_scope.has_secrets;
+    }
+
+    fn nested_jobs(&mut self, jobs: &[OracleJob], path: &str, root: Option<usize>, scope: &Scope) {
+        for (idx, job) in jobs.iter().enumerate() {
+            self.job(job, &format!("{}[{}]", path, idx), root, &mut scope.clone());
+        }
+    }
+
+    fn nested_job(
+        &mut self,
+        job: Option<&OracleJob>,
+        path: &str,
+        root: Option<usize>,
+        scope: &Scope,
+    ) {
+        if let Some(job) = job {
+            self.job(job, path, root, &mut scope.clone());
+        }
+    }
+
+    fn tasks(&mut self, tasks: &[Task], prefix: &str, root: Option<usize>, scope: &mut Scope) {
+        for (idx, task) in tasks.iter().enumerate() {
+            let path = format!("{}[{}]", prefix, idx);
+            let root = root.or(Some(idx));
+            self.task(task, &path, root, scope);
+        }
+    }
+
+    fn task(&mut self, task: &Task, path: &str, root: Option<usize>, scope: &mut Scope) {
+        self.variable_refs(task, path, root, scope);
+        let inner = match task.task.as_ref() {
+            Some(inner) => inner,
+            None => {
+                self.report(Severity::Error, root, path, "Task is empty".to_string());
+                return;
+            }
+        };
+        match inner {
+            task::Task::HttpTask(task) => {
+                self.require(task.url.as_deref(), "HttpTask", "url", path, root);
+                scope.has_source = true;
+            }
+            task::Task::WebsocketTask(task) => {
+                self.require(task.url.as_deref(), "WebsocketTask", "url", path, root);
+                self.require(
+                    task.subscription.as_deref(),
+                    "WebsocketTask",
+                    "subscription",
+                    path,
+                    root,
+                );
+                scope.has_source = true;
+            }
+            task::Task::ValueTask(task) => {
+                if task.value.is_none() {
+                    self.report(Severity::Error, root, path, "ValueTask has no value".into());
+                }
+                if let Some(value_task::Value::Hex(_) | value_task::Value::Utf8(_)) = &task.value {
+                    scope.has_source = true;
+                }
+            }
+            task::Task::JsonParseTask(task) => {
+                self.require(task.path.as_deref(), "JsonParseTask", "path", path, root);
+                if !scope.has_source {
+                    self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        "JsonParseTask has no preceding HttpTask or WebsocketTask".into(),
+                    );
+                }
+            }
+            task::Task::RegexExtractTask(task) => {
+                match task.pattern.as_deref().map(Regex::new) {
+                    None | Some(Ok(_)) => self.require(
+                        task.pattern.as_deref(),
+                        "RegexExtractTask",
+                        "pattern",
+                        path,
+                        root,
+                    ),
+                    Some(Err(e)) => self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        format!("RegexExtractTask has an invalid pattern: {}", e),
+                    ),
+                }
+                if !scope.has_source {
+                    self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        "RegexExtractTask has no preceding HttpTask or WebsocketTask".into(),
+                    );
+                }
+            }
+            task::Task::CacheTask(task) => {
+                if task.cache_items.is_empty() {
+                    self.report(Severity::Error, root, path, "CacheTask has no items".into());
+                }
+                for (idx, item) in task.cache_items.iter().enumerate() {
+                    let item_path = format!("{}.cacheItems[{}]", path, idx);
+                    match item.variable_name.as_deref().map(str::trim) {
+                        Some(name) if !name.is_empty() => {
+                            scope.variables.insert(name.to_string());
+                        }
+                        _ => self.report(
+                            Severity::Error,
+                            root,
+                            &item_path,
+                            "CacheTask item has an empty variable name".into(),
+                        ),
+                    }
+                    match &item.job {
+                        Some(job) => self.job(job, &join(&item_path, "job"), root, scope),
+                        None => self.report(
+                            Severity::Error,
+                            root,
+                            &item_path,
+                            "CacheTask item has no job".into(),
+                        ),
+                    }
+                }
+            }
+            task::Task::SecretsTask(_) => {
+                scope.has_secrets = true;
+            }
+            task::Task::DivideTask(task) => {
+                let divides_by_zero = match &task.denominator {
+                    Some(divide_task::Denominator::Scalar(value)) => *value == 0.0,
+                    Some(divide_task::Denominator::Big(value)) => is_literal_zero(value),
+                    Some(divide_task::Denominator::Job(job)) => job_is_literal_zero(job),
+                    Some(divide_task::Denominator::AggregatorPubkey(_)) => false,
+                    None => {
+                        self.report(
+                            Severity::Error,
+                            root,
+                            path,
+                            "DivideTask has no denominator".into(),
+                        );
+                        false
+                    }
+                };
+                if divides_by_zero {
+                    self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        "DivideTask divides by zero".into(),
+                    );
+                }
+                if let Some(divide_task::Denominator::Job(job)) = &task.denominator {
+                    self.job(job, &join(path, "job"), root, &mut scope.clone());
+                }
+            }
+            task::Task::MultiplyTask(task) => {
+                if let Some(multiply_task::Multiple::Job(job)) = &task.multiple {
+                    self.job(job, &join(path, "job"), root, &mut scope.clone());
+                }
+            }
+            task::Task::AddTask(task) => {
+                if let Some(add_task::Addition::Job(job)) = &task.addition {
+                    self.job(job, &join(path, "job"), root, &mut scope.clone());
+                }
+            }
+            task::Task::SubtractTask(task) => {
+                if let Some(subtract_task::Subtraction::Job(job)) = &task.subtraction {
+                    self.job(job, &join(path, "job"), root, &mut scope.clone());
+                }
+            }
+            task::Task::MedianTask(task) => {
+                self.aggregation("MedianTask", &task.tasks, &task.jobs, path, root, scope)
+            }
+            task::Task::MeanTask(task) => {
+                self.aggregation("MeanTask", &task.tasks, &task.jobs, path, root, scope)
+            }
+            task::Task::MinTask(task) => {
+                self.aggregation("MinTask", &task.tasks, &task.jobs, path, root, scope)
+            }
+            task::Task::MaxTask(task) => {
+                self.aggregation("MaxTask", &task.tasks, &task.jobs, path, root, scope)
+            }
+            task::Task::ConditionalTask(task) => {
+                if task.attempt.is_empty() {
+                    self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        "ConditionalTask has no attempt tasks".into(),
+                    );
+                }
+                if task.on_failure.is_empty() {
+                    self.report(
+                        Severity::Warning,
+                        root,
+                        path,
+                        "ConditionalTask has no onFailure fallback".into(),
+                    );
+                }
+                let mut attempt_scope = scope.clone();
+                self.tasks(
+                    &task.attempt,
+                    &join(path, "attempt"),
+                    root,
+                    &mut attempt_scope,
+                );
+                let mut failure_scope = scope.clone();
+                self.tasks(
+                    &task.on_failure,
+                    &join(path, "onFailure"),
+                    root,
+                    &mut failure_scope,
+                );
+                scope.has_source |= attempt_scope.has_source && failure_scope.has_source;
+            }
+            task::Task::ComparisonTask(task) => {
+                if task.op.is_none() {
+                    self.report(
+                        Severity::Warning,
+                        root,
+                        path,
+                        "ComparisonTask has no op, defaulting to OPERATION_EQ".into(),
+                    );
+                }
+                if task.rhs.is_none() {
+                    self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        "ComparisonTask has no rhs".into(),
+                    );
+                }
+                if let Some(comparison_task::Lhs::Lhs(job)) = &task.lhs {
+                    self.job(job, &join(path, "lhs"), root, &mut scope.clone());
+                }
+                if let Some(comparison_task::Rhs::Rhs(job)) = &task.rhs {
+                    self.job(job, &join(path, "rhs"), root, &mut scope.clone());
+                }
+                self.nested_job(task.on_true.as_ref(), &join(path, "onTrue"), root, scope);
+                self.nested_job(task.on_false.as_ref(), &join(path, "onFalse"), root, scope);
+                self.nested_job(
+                    task.on_failure.as_ref(),
+                    &join(path, "onFailure"),
+                    root,
+                    scope,
+                );
+            }
+            task::Task::BoundTask(task) => {
+                if task.lower_bound.is_none()
+                    && task.lower_bound_value.is_none()
+                    && task.upper_bound.is_none()
+                    && task.upper_bound_value.is_none()
+                {
+                    self.report(
+                        Severity::Warning,
+                        root,
+                        path,
+                        "BoundTask has no bounds".into(),
+                    );
+                }
+                self.nested_job(
+                    task.lower_bound.as_ref(),
+                    &join(path, "lowerBound"),
+                    root,
+                    scope,
+                );
+                self.nested_job(
+                    task.upper_bound.as_ref(),
+                    &join(path, "upperBound"),
+                    root,
+                    scope,
+                );
+            }
+            task::Task::SerumSwapTask(task) => {
+                self.require(
+                    task.serum_pool_address.as_deref(),
+                    "SerumSwapTask",
+                    "serumPoolAddress",
+                    path,
+                    root,
+                );
+            }
+            task::Task::JupiterSwapTask(task) => {
+                self.swap_tokens(
+                    "JupiterSwapTask",
+                    task.in_token_address.as_deref(),
+                    task.out_token_address.as_deref(),
+                    path,
+                    root,
+                );
+            }
+            task::Task::UniswapExchangeRateTask(task) => {
+                self.swap_tokens(
+                    "UniswapExchangeRateTask",
+                    task.in_token_address.as_deref(),
+                    task.out_token_address.as_deref(),
+                    path,
+                    root,
+                );
+                self.require(
+                    task.provider.as_deref(),
+                    "UniswapExchangeRateTask",
+                    "provider",
+                    path,
+                    root,
+                );
+            }
+            task::Task::SushiswapExchangeRateTask(task) => {
+                self.swap_tokens(
+                    "SushiswapExchangeRateTask",
+                    task.in_token_address.as_deref(),
+                    task.out_token_address.as_deref(),
+                    path,
+                    root,
+                );
+                self.require(
+                    task.provider.as_deref(),
+                    "SushiswapExchangeRateTask",
+                    "provider",
+                    path,
+                    root,
+                );
+            }
+            task::Task::PancakeswapExchangeRateTask(task) => {
+                self.swap_tokens(
+                    "PancakeswapExchangeRateTask",
+                    task.in_token_address.as_deref(),
+                    task.out_token_address.as_deref(),
+                    path,
+                    root,
+                );
+                self.require(
+                    task.provider.as_deref(),
+                    "PancakeswapExchangeRateTask",
+                    "provider",
+                    path,
+                    root,
+                );
+            }
+            task::Task::LpExchangeRateTask(task) => {
+                if task.pool_address.is_none() {
+                    self.report(
+                        Severity::Error,
+                        root,
+                        path,
+                        "LpExchangeRateTask has no pool address".into(),
+                    );
+                }
+            }
+            task::Task::MeteoraSwapTask(task) => {
+                self.require(task.pool.as_deref(), "MeteoraSwapTask", "pool", path, root);
+            }
+            task::Task::CurveFinanceTask(task) => {
+                self.require(
+                    task.pool_address.as_deref(),
+                    "CurveFinanceTask",
+                    "poolAddress",
+                    path,
+                    root,
+                );
+            }
+            task::Task::PumpAmmTask(task) => {
+                self.require(
+                    task.pool_address.as_deref(),
+                    "PumpAmmTask",
+                    "poolAddress",
+                    path,
+                    root,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn aggregation(
+        &mut self,
+        name: &str,
+        tasks: &[Task],
+        jobs: &[OracleJob],
+        path: &str,
+        root: Option<usize>,
+        scope: &Scope,
+    ) {
+        if tasks.is_empty() && jobs.is_empty() {
+            self.report(
+                Severity::Error,
+                root,
+                path,
+                format!("{} has no tasks or jobs", name),
+            );
+        }
+        self.tasks(tasks, &join(path, "tasks"), root, &mut scope.clone());
+        self.nested_jobs(jobs, &join(path, "jobs"), root, scope);
+    }
+
+    fn swap_tokens(
+        &mut self,
+        name: &str,
+        in_token: Option<&str>,
+        out_token: Option<&str>,
+        path: &str,
+        root: Option<usize>,
+    ) {
+        self.require(in_token, name, "inTokenAddress", path, root);
+        self.require(out_token, name, "outTokenAddress", path, root);
+        if in_token.is_some() && in_token == out_token {
+            self.report(
+                Severity::Error,
+                root,
+                path,
+                format!("{} swaps a token for itself", name),
+            );
+        }
+    }
+
+    fn require(
+        &mut self,
+        value: Option<&str>,
+        name: &str,
+        field: &str,
+        path: &str,
+        root: Option<usize>,
+    ) {
+        if value.map_or(true, |value| value.trim().is_empty()) {
+            self.report(
+                Severity::Error,
+                root,
+                path,
+                format!("{} is missing {}", name, field),
+            );
+        }
+    }
+
+    /// Reports every `${NAME}` in the task's own fields that no earlier
+    /// `CacheTask` defines.
+    fn variable_refs(&mut self, task: &Task, path: &str, root: Option<usize>, scope: &Scope) {
+        let json = match serde_json::to_value(task) {
+            Ok(json) => json,
+            Err(_) => return,
+        };
+        let mut names = Vec::new();
+        collect_refs(&json, &mut names);
+        names.sort();
+        names.dedup();
+        for name in names {
+            if scope.variables.contains(&name) {
+                continue;
+            }
+            // secrets are resolved by the oracle and cannot be checked here
+            let severity = if scope.has_secrets {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            self.report(
+                severity,
+                root,
+                path,
+                format!("${{{}}} is not defined by a preceding CacheTask", name),
+            );
+        }
+    }
+}
+
+/// Collects variable references, skipping nested jobs and task lists which
+/// are checked when the validator walks into them.
+fn collect_refs(value: &JsonValue, names: &mut Vec<String>) {
+    match value {
+        JsonValue::String(text) => {
+            names.extend(VARIABLE_REF.captures_iter(text).map(|c| c[1].to_string()));
+        }
+        JsonValue::Array(items) => items.iter().for_each(|item| collect_refs(item, names)),
+        JsonValue::Object(fields) => {
+            if fields.contains_key("tasks") {
+                return;
+            }
+            for (key, field) in fields {
+                if matches!(key.as_str(), "attempt" | "onFailure" | "cacheItems") {
+                    continue;
+                }
+                collect_refs(field, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_literal_zero(value: &str) -> bool {
+    Decimal::from_str(value.trim()).map_or(false, |value| value.is_zero())
+}
+
+fn job_is_literal_zero(job: &OracleJob) -> bool {
+    match job.tasks.as_slice() {
+        [Task {
+            task: Some(task::Task::ValueTask(ValueTask { value: Some(value) })),
+        }] => match value {
+            value_task::Value::Value(value) => *value == 0.0,
+            value_task::Value::Big(value) => is_literal_zero(value),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn join(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", prefix, field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_issue(issues: &[JobIssue], task_index: usize, needle: &str) -> bool {
+        issues
+            .iter()
+            .any(|issue| issue.task_index == task_index && issue.message.contains(needle))
+    }
+
+    #[test]
+    fn accepts_a_well_formed_job() {
+        let job = OracleJob::builder()
+            .http("https://api.test/price")
+            .json_parse("$.price")
+            .divide(2.0)
+            .build();
+        assert!(validate_job(&job).is_empty());
+    }
+
+    #[test]
+    fn reports_structural_errors() {
+        let job = OracleJob::builder()
+            .json_parse("$.price")
+            .divide(0.0)
+            .divide_by_job(OracleJob::builder().big_value("0.0").build())
+            .task(CacheTask {
+                cache_items: vec![cache_task::CacheItem {
+                    variable_name: Some(" ".into()),
+                    job: None,
+                }],
+            })
+            .http("https://api.test/${UNKNOWN}")
+            .task(JupiterSwapTask::default())
+            .conditional(
+                OracleJob::builder().value(1.0).build(),
+                OracleJob::default(),
+            )
+            .build();
+        let issues = validate_job(&job);
+        assert!(has_issue(&issues, 0, "no preceding HttpTask"));
+        assert!(has_issue(&issues, 1, "divides by zero"));
+        assert!(has_issue(&issues, 2, "divides by zero"));
+        assert!(has_issue(&issues, 3, "empty variable name"));
+        assert!(has_issue(&issues, 3, "has no job"));
+        assert!(has_issue(&issues, 4, "${UNKNOWN}"));
+        assert!(has_issue(&issues, 5, "inTokenAddress"));
+        assert!(has_issue(&issues, 6, "no onFailure"));
+    }
+
+    #[test]
+    fn resolves_cached_variables() {
+        let job = OracleJob::builder()
+            .task(CacheTask {
+                cache_items: vec![cache_task::CacheItem {
+                    variable_name: Some("KEY".into()),
+                    job: Some(OracleJob::builder().value(1.0).build()),
+                }],
+            })
+            .http("https://api.test/${KEY}")
+            .json_parse("$.price")
+            .build();
+        assert!(validate_job(&job).is_empty());
+    }
+}
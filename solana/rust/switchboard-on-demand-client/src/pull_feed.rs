@@ -2,16 +2,13 @@ use crate::Gateway;
 use crate::OracleAccountData;
 use crate::State;
 use crate::*;
-use anyhow_ext::anyhow;
-use anyhow_ext::Context;
-use anyhow_ext::Error as AnyhowError;
+use crate::ClientError;
 use associated_token_account::get_associated_token_address;
 use associated_token_account::NATIVE_MINT;
 use associated_token_account::SPL_TOKEN_PROGRAM_ID;
 use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
 use bs58;
 use bytemuck;
-use dashmap::DashMap;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -23,10 +20,12 @@ use solana_sdk::instruction::AccountMeta;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::system_program;
+use std::collections::HashMap;
 use std::result::Result;
 use std::sync::Arc;
 use tokio::join;
-use tokio::sync::OnceCell;
+use std::path::Path;
+use std::time::Duration;
 use sha2::{Digest, Sha256};
 use solana_sdk::secp256k1_instruction::DATA_START;
 use solana_sdk::secp256k1_instruction::SecpSignatureOffsets;
@@ -35,9 +34,9 @@ use solana_sdk::secp256k1_instruction::construct_eth_pubkey;
 use crate::secp256k1::Secp256k1InstructionUtils;
 use crate::secp256k1::SecpSignature;
 
-type LutCache = DashMap<Pubkey, AddressLookupTableAccount>;
-type JobCache = DashMap<[u8; 32], OnceCell<Vec<OracleJob>>>;
-type PullFeedCache = DashMap<Pubkey, OnceCell<PullFeedAccountData>>;
+type LutCache = TtlCache<Pubkey, AddressLookupTableAccount>;
+type JobCache = TtlCache<[u8; 32], Vec<OracleJob>>;
+type PullFeedCache = TtlCache<Pubkey, PullFeedAccountData>;
 
 pub fn generate_combined_checksum(
     queue_key: &[u8; 32],
@@ -63,6 +62,34 @@ pub fn generate_combined_checksum(
     hasher.finalize().into()
 }
 
+/// The checksum an oracle is expected to sign for a
+/// `pull_feed_submit_response_many` submission: the sha256 hash of the
+/// checksums of each feed's value, in the order of `feeds`.
+///
+/// This layout is not yet pinned against a gateway response or the program,
+/// so submissions that fail to verify against it are reported rather than
+/// dropped.
+pub fn generate_multi_checksum(
+    feeds: &[PullFeedAccountData],
+    signed_slothash: &[u8; 32],
+    submission_values: &[i128],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for (feed, &value) in feeds.iter().zip(submission_values) {
+        hasher.update(feed.generate_checksum(value, *signed_slothash));
+    }
+    hasher.finalize().into()
+}
+
+/// How long each `SbContext` cache keeps its values. `None` keeps them until
+/// they are invalidated.
+#[derive(Clone, Debug, Default)]
+pub struct CacheConfig {
+    pub lut_ttl: Option<Duration>,
+    pub job_ttl: Option<Duration>,
+    pub pull_feed_ttl: Option<Duration>,
+}
+
 pub struct SbContext {
     pub lut_cache: LutCache,
     pub job_cache: JobCache,
@@ -70,25 +97,84 @@ pub struct SbContext {
 }
 impl SbContext {
     pub fn new() -> Arc<Self> {
+        Self::with_config(CacheConfig::default())
+    }
+
+    pub fn with_config(config: CacheConfig) -> Arc<Self> {
         Arc::new(SbContext {
-            lut_cache: DashMap::new(),
-            job_cache: DashMap::new(),
-            pull_feed_cache: DashMap::new(),
+            lut_cache: TtlCache::new(config.lut_ttl),
+            job_cache: TtlCache::new(config.job_ttl),
+            pull_feed_cache: TtlCache::new(config.pull_feed_ttl),
         })
     }
+
+    /// Drops the cached account data of `feed`, so its `feed_hash`,
+    /// `max_variance`, `min_responses` and `lut_slot` are reloaded on the
+    /// next update. Returns whether the feed was cached.
+    pub fn invalidate_feed(&self, feed: &Pubkey) -> bool {
+        self.pull_feed_cache.invalidate(feed)
+    }
+
+    /// Drops the cached lookup table of the feed or oracle `key`.
+    /// Returns whether the lookup table was cached.
+    pub fn invalidate_lut(&self, key: &Pubkey) -> bool {
+        self.lut_cache.invalidate(key)
+    }
+
+    /// Writes the unexpired jobs of the job cache to `path` as JSON, keyed by
+    /// hex encoded feed hash.
+    pub fn save_job_cache(&self, path: impl AsRef<Path>) -> Result<(), ClientError> {
+        let jobs: HashMap<String, Vec<OracleJob>> = self
+            .job_cache
+            .entries()
+            .into_iter()
+            .map(|(feed_hash, jobs)| (hex::encode(feed_hash), jobs))
+            .collect();
+        let data = serde_json::to_vec(&jobs).map_err(std::io::Error::from)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Adds the jobs saved by `save_job_cache` at `path` to the job cache. A
+    /// missing file loads nothing.
+    /// # Returns
+    /// * `Result<usize, ClientError>` - The number of feed hashes loaded
+    pub fn load_job_cache(&self, path: impl AsRef<Path>) -> Result<usize, ClientError> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let jobs: HashMap<String, Vec<OracleJob>> =
+            serde_json::from_slice(&data).map_err(std::io::Error::from)?;
+        let num_loaded = jobs.len();
+        for (feed_hash, jobs) in jobs {
+            let feed_hash: [u8; 32] = hex::decode(&feed_hash)
+                .ok()
+                .and_then(|hash| hash.try_into().ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Invalid feed hash {}", feed_hash),
+                    )
+                })?;
+            self.job_cache.insert(feed_hash, jobs);
+        }
+        Ok(num_loaded)
+    }
 }
 
 pub async fn fetch_and_cache_luts<T: bytemuck::Pod + lut_owner::LutOwner>(
     client: &RpcClient,
     context: Arc<SbContext>,
     oracle_keys: &[Pubkey],
-) -> Result<Vec<AddressLookupTableAccount>, AnyhowError> {
+) -> Result<Vec<AddressLookupTableAccount>, ClientError> {
     let mut luts = Vec::new();
     let mut keys_to_fetch = Vec::new();
 
     for &key in oracle_keys {
         if let Some(cached_lut) = context.lut_cache.get(&key) {
-            luts.push(cached_lut.clone());
+            luts.push(cached_lut);
         } else {
             keys_to_fetch.push(key);
         }
@@ -105,6 +191,175 @@ pub async fn fetch_and_cache_luts<T: bytemuck::Pod + lut_owner::LutOwner>(
     Ok(luts)
 }
 
+fn parse_jobs(jobs_data: serde_json::Value) -> Result<Vec<OracleJob>, ClientError> {
+    let jobs = jobs_data
+        .get("jobs")
+        .cloned()
+        .ok_or_else(|| ClientError::CrossbarDecode("Response has no jobs".to_string()))?;
+    serde_json::from_value(jobs).map_err(|e| ClientError::CrossbarDecode(e.to_string()))
+}
+
+fn decode_oracle_pubkey(oracle_pubkey: &str) -> Result<Pubkey, ClientError> {
+    let bytes = decode_fixed(hex::decode(oracle_pubkey).ok(), "oracle pubkey")?;
+    Ok(Pubkey::new_from_array(bytes))
+}
+
+fn decode_fixed<const N: usize>(
+    bytes: Option<Vec<u8>>,
+    what: &str,
+) -> Result<[u8; N], ClientError> {
+    bytes
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ClientError::SignatureDecode(format!("Invalid {}", what)))
+}
+
+/// Decodes the gateway's per-oracle responses to a single feed request.
+///
+/// Oracles whose pubkey is malformed, or whose successful response carries a
+/// malformed signature, are skipped and reported in the returned errors
+/// instead of failing the whole update.
+pub fn parse_oracle_responses(
+    responses: &[FeedEvalResponse],
+) -> (Vec<OracleResponse>, Vec<OracleError>) {
+    let mut parsed = Vec::new();
+    let mut malformed = Vec::new();
+    for x in responses {
+        let oracle = match decode_oracle_pubkey(&x.oracle_pubkey) {
+            Ok(oracle) => oracle,
+            Err(e) => {
+                malformed.push(OracleError {
+                    oracle: x.oracle_pubkey.clone(),
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let value = x
+            .success_value
+            .parse::<i128>()
+            .ok()
+            .map(|val| Decimal::from_i128_with_scale(val, 18));
+        let signature = match decode_fixed(base64.decode(&x.signature).ok(), "signature") {
+            Ok(signature) => signature,
+            // Failed responses are submitted unsigned.
+            Err(_) if value.is_none() => [0; 64],
+            Err(e) => {
+                malformed.push(OracleError {
+                    oracle: x.oracle_pubkey.clone(),
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        parsed.push(OracleResponse {
+            value,
+            error: x.failure_error.clone(),
+            oracle,
+            signature,
+            recovery_id: x.recovery_id as u8,
+            timestamp: x.timestamp,
+        });
+    }
+    (parsed, malformed)
+}
+
+/// Decodes the oracle keys and secp256k1 signatures of a consensus response.
+///
+/// Malformed oracle responses are skipped and reported in the returned
+/// errors. The keys and signatures are returned in the same order.
+pub fn parse_consensus_responses(
+    responses: &[ConsensusOracleResponse],
+) -> (Vec<(Pubkey, SecpSignature)>, Vec<OracleError>) {
+    let mut parsed = Vec::new();
+    let mut malformed = Vec::new();
+    for x in responses {
+        match parse_consensus_response(x) {
+            Ok(entry) => parsed.push(entry),
+            Err(e) => malformed.push(OracleError {
+                oracle: x.oracle_pubkey.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    (parsed, malformed)
+}
+
+fn parse_consensus_response(
+    x: &ConsensusOracleResponse,
+) -> Result<(Pubkey, SecpSignature), ClientError> {
+    let feed_response = x.feed_responses.first().ok_or_else(|| {
+        ClientError::GatewayDecode("Oracle response has no feed responses".to_string())
+    })?;
+    let oracle = decode_oracle_pubkey(&feed_response.oracle_pubkey)?;
+    let signature = SecpSignature {
+        eth_address: decode_fixed(hex::decode(&x.eth_address).ok(), "eth address")?,
+        signature: decode_fixed(base64.decode(&x.signature).ok(), "signature")?,
+        message: base64
+            .decode(&x.checksum)
+            .map_err(|_| ClientError::SignatureDecode("Invalid checksum".to_string()))?,
+        recovery_id: x.recovery_id as u8,
+    };
+    Ok((oracle, signature))
+}
+
+/// Decodes the gateway's per-oracle responses to a multi feed request into a
+/// submission per oracle, holding a value per feed in request order.
+///
+/// Malformed oracle responses, including ones that do not cover exactly
+/// `num_feeds` feeds, are skipped and reported in the returned errors.
+pub fn parse_multi_responses(
+    responses: &[FeedEvalManyResponse],
+    num_feeds: usize,
+) -> (Vec<(Pubkey, MultiSubmission)>, Vec<OracleError>) {
+    let mut parsed = Vec::new();
+    let mut malformed = Vec::new();
+    for x in responses {
+        match parse_multi_response(x, num_feeds) {
+            Ok(entry) => parsed.push(entry),
+            Err(e) => malformed.push(OracleError {
+                oracle: x
+                    .feed_responses
+                    .first()
+                    .map(|resp| resp.oracle_pubkey.clone())
+                    .unwrap_or_default(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    (parsed, malformed)
+}
+
+fn parse_multi_response(
+    x: &FeedEvalManyResponse,
+    num_feeds: usize,
+) -> Result<(Pubkey, MultiSubmission), ClientError> {
+    if x.feed_responses.len() != num_feeds {
+        return Err(ClientError::GatewayDecode(format!(
+            "Oracle responded to {} of {} feeds",
+            x.feed_responses.len(),
+            num_feeds
+        )));
+    }
+    let oracle = decode_oracle_pubkey(&x.feed_responses[0].oracle_pubkey)?;
+    let values = x
+        .feed_responses
+        .iter()
+        .map(|resp| resp.success_value.parse::<i128>().unwrap_or(i128::MAX))
+        .collect();
+    let submission = MultiSubmission {
+        values,
+        signature: decode_fixed(base64.decode(&x.signature).ok(), "signature")?,
+        recovery_id: x.recovery_id as u8,
+    };
+    Ok((oracle, submission))
+}
+
+/// The number of signatures needed to reach the feed's minimum sample size
+/// with a third of the oracles failing.
+fn min_num_signatures(data: &PullFeedAccountData) -> u32 {
+    data.min_sample_size as u32 + ((data.min_sample_size as f64) / 3.0).ceil() as u32
+}
+
 #[derive(Clone, Debug)]
 pub struct OracleResponse {
     pub value: Option<Decimal>,
@@ -112,6 +367,8 @@ pub struct OracleResponse {
     pub oracle: Pubkey,
     pub signature: [u8; 64],
     pub recovery_id: u8,
+    /// The timestamp included in the signed checksum, if any.
+    pub timestamp: Option<i64>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -119,6 +376,9 @@ pub struct FetchUpdateParams {
     pub feed: Pubkey,
     pub payer: Pubkey,
     pub gateway: Gateway,
+    /// Fetches the signatures from the best scoring gateway of the pool,
+    /// failing over to the next ones, instead of from `gateway`.
+    pub gateway_pool: Option<Arc<GatewayPool>>,
     pub crossbar: Option<CrossbarClient>,
     pub num_signatures: Option<u32>,
     pub debug: Option<bool>,
@@ -129,9 +389,18 @@ pub struct FetchUpdateManyParams {
     pub feeds: Vec<Pubkey>,
     pub payer: Pubkey,
     pub gateway: Gateway,
+    /// Fetches the signatures from the best scoring gateway of the pool,
+    /// failing over to the next ones, instead of from `gateway`.
+    pub gateway_pool: Option<Arc<GatewayPool>>,
     pub crossbar: Option<CrossbarClient>,
     pub num_signatures: Option<u32>,
     pub debug: Option<bool>,
+    /// Fail instead of updating each queue separately when the feeds belong
+    /// to different queues. Only used by the consensus update.
+    pub single_queue: Option<bool>,
+    /// The gateways serving the oracles of queues other than the queue of
+    /// `gateway`. Queues without an entry use `gateway_pool` or `gateway`.
+    pub queue_gateways: HashMap<Pubkey, Gateway>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -139,6 +408,9 @@ pub struct FetchUpdateBatchParams {
     pub feeds: Vec<Pubkey>,
     pub payer: Pubkey,
     pub gateway: Gateway,
+    /// Fetches the signatures from the best scoring gateway of the pool,
+    /// failing over to the next ones, instead of from `gateway`.
+    pub gateway_pool: Option<Arc<GatewayPool>>,
     pub crossbar: Option<CrossbarClient>,
     pub num_signatures: Option<u32>,
     pub debug: Option<bool>,
@@ -151,28 +423,381 @@ pub struct SolanaSubmitSignaturesParams {
     pub payer: Pubkey,
 }
 
+/// A feed of a batch update without a verified successful response, left
+/// out of the update instructions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFeed {
+    pub feed: Pubkey,
+    pub per_oracle_errors: Vec<OracleError>,
+}
+
+/// The pool the signatures of an update are fetched from: `pool`, if set,
+/// or else a pool of `gateway` alone.
+fn gateway_source(gateway: &Gateway, pool: &Option<Arc<GatewayPool>>) -> Arc<GatewayPool> {
+    pool.clone().unwrap_or_else(|| Arc::new(GatewayPool::new(vec![gateway.clone()])))
+}
+
 pub struct PullFeed;
 
 impl PullFeed {
     pub async fn load_data(
         client: &RpcClient,
         key: &Pubkey,
-    ) -> Result<PullFeedAccountData, AnyhowError> {
+    ) -> Result<PullFeedAccountData, ClientError> {
         let account = client
-            .get_account_data(key)
-            .await
-            .map_err(|_| anyhow!("PullFeed.load_data: Account not found"))?;
-        let account = account[8..].to_vec();
-        let data = bytemuck::try_from_bytes::<PullFeedAccountData>(&account)
-            .map_err(|_| anyhow!("PullFeed.load_data: Failed to parse data"))?;
+            .get_account_with_commitment(key, client.commitment())
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound(*key))?;
+        let data = account
+            .data
+            .get(8..)
+            .and_then(|data| bytemuck::try_from_bytes::<PullFeedAccountData>(data).ok())
+            .ok_or_else(|| ClientError::InvalidAccountData {
+                account: *key,
+                reason: "Failed to parse pull feed data".to_string(),
+            })?;
         Ok(*data)
     }
 
+    /// Loads the data and encoded jobs of each of `feeds` through the context
+    /// caches, fetching missing jobs from `crossbar`.
+    async fn load_feeds(
+        context: &SbContext,
+        client: &RpcClient,
+        feeds: &[Pubkey],
+        crossbar: &Option<CrossbarClient>,
+    ) -> Result<Vec<(PullFeedAccountData, Vec<String>)>, ClientError> {
+        let mut loaded = Vec::new();
+        for feed in feeds {
+            let data = context
+                .pull_feed_cache
+                .get_or_try_init(*feed, || PullFeed::load_data(client, feed))
+                .await?;
+            // Fetch jobs from the crossbar (or use cache) and encode them.
+            let jobs = context
+                .job_cache
+                .get_or_try_init(data.feed_hash, || {
+                    let crossbar = crossbar.clone().unwrap_or_default();
+                    async move {
+                        let jobs_data = crossbar.fetch(&hex::encode(data.feed_hash)).await?;
+                        parse_jobs(jobs_data)
+                    }
+                })
+                .await?;
+            loaded.push((data, encode_jobs(&jobs)));
+        }
+        Ok(loaded)
+    }
+
+    /// The queue shared by all of `feeds`.
+    fn shared_queue(feeds: &[(PullFeedAccountData, Vec<String>)]) -> Result<Pubkey, ClientError> {
+        let queue = feeds
+            .first()
+            .map(|(data, _)| data.queue)
+            .ok_or_else(|| ClientError::InvalidInput("No feeds to update".to_string()))?;
+        if feeds.iter().any(|(data, _)| data.queue != queue) {
+            return Err(ClientError::InvalidInput(
+                "Feeds must belong to the same queue".to_string(),
+            ));
+        }
+        Ok(queue)
+    }
+
+    async fn fetch_luts(
+        context: Arc<SbContext>,
+        client: &RpcClient,
+        oracles: &[Pubkey],
+        feeds: &[Pubkey],
+        queue: Pubkey,
+    ) -> Result<Vec<AddressLookupTableAccount>, ClientError> {
+        let queue_key = [queue];
+        let (oracle_luts, pull_feed_luts, queue_lut) = join!(
+            fetch_and_cache_luts::<OracleAccountData>(client, context.clone(), oracles),
+            fetch_and_cache_luts::<PullFeedAccountData>(client, context.clone(), feeds),
+            fetch_and_cache_luts::<QueueAccountData>(client, context.clone(), &queue_key)
+        );
+        let mut luts = oracle_luts?;
+        luts.extend(pull_feed_luts?);
+        luts.extend(queue_lut?);
+        Ok(luts)
+    }
+
+    /// Fetch one signature per oracle over the values of all feeds via the
+    /// multi endpoint, and build a single `pull_feed_submit_response_many`
+    /// instruction updating them. Submissions whose signature does not verify
+    /// against the oracle's secp256k1 signer under `generate_multi_checksum`
+    /// are still submitted, and reported.
+    ///
+    /// # Arguments
+    /// * `context` - Shared context holding caches for feeds, jobs, and lookup tables.
+    /// * `client` - The RPC client for connecting to the cluster.
+    /// * `params` - Parameters for fetching updates. All feeds must belong to the same queue.
+    ///
+    /// # Returns
+    /// A tuple containing:
+    ///   1. The feed update instruction.
+    ///   2. A vector of AddressLookupTableAccount to include in the transaction.
+    ///   3. The submitted oracles whose signature did not verify.
+    pub async fn fetch_update_many_ix(
+        context: Arc<SbContext>,
+        client: &RpcClient,
+        params: FetchUpdateManyParams,
+    ) -> Result<
+        (
+            Instruction,
+            Vec<AddressLookupTableAccount>,
+            Vec<OracleError>,
+        ),
+        ClientError,
+    > {
+        let loaded = PullFeed::load_feeds(&context, client, &params.feeds, &params.crossbar).await?;
+        let queue = PullFeed::shared_queue(&loaded)?;
+        let num_signatures = loaded
+            .iter()
+            .map(|(data, _)| min_num_signatures(data))
+            .fold(params.num_signatures.unwrap_or(1), u32::max);
+        let (feed_datas, feed_configs): (Vec<PullFeedAccountData>, Vec<FeedConfig>) = loaded
+            .into_iter()
+            .map(|(data, encoded_jobs)| {
+                let config = FeedConfig {
+                    encoded_jobs,
+                    max_variance: Some((data.max_variance / 1_000_000_000) as u32),
+                    min_responses: Some(data.min_responses),
+                };
+                (data, config)
+            })
+            .unzip();
+
+        let latest_slot = SlotHashSysvar::get_latest_slothash(client).await?;
+        let price_signatures = gateway_source(&params.gateway, &params.gateway_pool)
+            .fetch_signatures_multi(FetchSignaturesMultiParams {
+                recent_hash: Some(bs58::encode(latest_slot.hash).into_string()),
+                feed_configs,
+                num_signatures: Some(num_signatures),
+                use_timestamp: Some(false),
+            })
+            .await?;
+        if params.debug.unwrap_or(false) {
+            println!("priceSignatures: {:?}", price_signatures);
+        }
+
+        let (parsed, malformed) =
+            parse_multi_responses(&price_signatures.oracle_responses, params.feeds.len());
+
+        let responding_oracles: Vec<Pubkey> = parsed.iter().map(|(oracle, _)| *oracle).collect();
+        let signers = load_oracle_signers(client, &responding_oracles).await?;
+        let unverified: Vec<OracleError> = parsed
+            .iter()
+            .filter(|(oracle, submission)| {
+                !signers.get(oracle).map_or(false, |signer| {
+                    verify_multi_submission(&feed_datas, signer, submission, &latest_slot.hash)
+                })
+            })
+            .map(|(oracle, _)| OracleError {
+                oracle: hex::encode(oracle),
+                error: "Signature does not match the oracle's secp256k1 signer".to_string(),
+            })
+            .collect();
+        if params.debug.unwrap_or(false) {
+            for err in &malformed {
+                eprintln!("Skipping invalid response from {}: {}", err.oracle, err.error);
+            }
+            for err in &unverified {
+                eprintln!("Unverified response from {}: {}", err.oracle, err.error);
+            }
+        }
+        if parsed.is_empty() {
+            let per_oracle_errors = malformed
+                .into_iter()
+                .chain(price_signatures.errors.iter().flatten().map(|error| OracleError {
+                    oracle: String::new(),
+                    error: error.clone(),
+                }))
+                .collect();
+            return Err(ClientError::NoSuccessfulResponses { per_oracle_errors });
+        }
+        let (oracle_keys, submissions): (Vec<Pubkey>, Vec<MultiSubmission>) =
+            parsed.into_iter().unzip();
+
+        let mut submit_ix = Instruction {
+            program_id: *SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            data: PullFeedSubmitResponseManyParams {
+                slot: latest_slot.slot,
+                submissions,
+            }
+            .data(),
+            accounts: PullFeedSubmitResponseMany {
+                queue,
+                program_state: State::key(),
+                recent_slothashes: solana_sdk::sysvar::slot_hashes::ID,
+                payer: params.payer,
+                system_program: system_program::ID,
+                reward_vault: get_associated_token_address(&queue, &NATIVE_MINT),
+                token_program: *SPL_TOKEN_PROGRAM_ID,
+                token_mint: *NATIVE_MINT,
+            }
+            .to_account_metas(None),
+        };
+        for feed in &params.feeds {
+            submit_ix.accounts.push(AccountMeta::new(*feed, false));
+        }
+        for oracle in &oracle_keys {
+            submit_ix.accounts.push(AccountMeta::new_readonly(*oracle, false));
+        }
+        for oracle in &oracle_keys {
+            let stats_key = OracleAccountData::stats_key(oracle);
+            submit_ix.accounts.push(AccountMeta::new(stats_key, false));
+        }
+
+        let luts =
+            PullFeed::fetch_luts(context, client, &oracle_keys, &params.feeds, queue).await?;
+        Ok((submit_ix, luts, unverified))
+    }
+
+    /// Fetch per-feed oracle signatures for all feeds in one request via the
+    /// batch endpoint, and build a `pull_feed_submit_response` instruction for
+    /// each feed with a successful response. Signatures that do not verify
+    /// against the oracle's secp256k1 signer are dropped.
+    ///
+    /// # Arguments
+    /// * `context` - Shared context holding caches for feeds, jobs, and lookup tables.
+    /// * `client` - The RPC client for connecting to the cluster.
+    /// * `params` - Parameters for fetching updates. All feeds must belong to the same queue.
+    ///
+    /// # Returns
+    /// A tuple containing:
+    ///   1. The update instructions, in the order of `params.feeds`, skipping
+    ///      feeds without a successful response.
+    ///   2. A vector of AddressLookupTableAccount to include in the transaction.
+    ///   3. The skipped feeds, with the errors of their oracles.
+    pub async fn fetch_update_batch_ix(
+        context: Arc<SbContext>,
+        client: &RpcClient,
+        params: FetchUpdateBatchParams,
+    ) -> Result<
+        (
+            Vec<Instruction>,
+            Vec<AddressLookupTableAccount>,
+            Vec<SkippedFeed>,
+        ),
+        ClientError,
+    > {
+        let loaded = PullFeed::load_feeds(&context, client, &params.feeds, &params.crossbar).await?;
+        let queue = PullFeed::shared_queue(&loaded)?;
+        let num_signatures = loaded
+            .iter()
+            .map(|(data, _)| min_num_signatures(data))
+            .fold(params.num_signatures.unwrap_or(1), u32::max);
+        let feed_configs = loaded
+            .iter()
+            .map(|(data, encoded_jobs)| BatchFeedRequest {
+                jobs_b64_encoded: encoded_jobs.clone(),
+                max_variance: data.max_variance,
+                min_responses: data.min_responses,
+            })
+            .collect();
+
+        let latest_slot = SlotHashSysvar::get_latest_slothash(client).await?;
+        let price_signatures = gateway_source(&params.gateway, &params.gateway_pool)
+            .fetch_signatures_batch(FetchSignaturesBatchParams {
+                recent_hash: Some(bs58::encode(latest_slot.hash).into_string()),
+                feed_configs,
+                num_signatures: Some(num_signatures),
+                use_timestamp: Some(false),
+            })
+            .await?;
+        if params.debug.unwrap_or(false) {
+            println!("priceSignatures: {:?}", price_signatures);
+        }
+
+        // The oracles' responses to each feed and the errors of the responses
+        // that failed to parse, in the order of the feeds.
+        let mut feed_responses = Vec::new();
+        for idx in 0..params.feeds.len() {
+            let responses: Vec<FeedEvalResponse> = price_signatures
+                .oracle_responses
+                .iter()
+                .filter_map(|x| x.feed_responses.get(idx).cloned())
+                .collect();
+            feed_responses.push(parse_oracle_responses(&responses));
+        }
+        let mut responding_oracles: Vec<Pubkey> = Vec::new();
+        for oracle in feed_responses
+            .iter()
+            .flat_map(|(responses, _)| responses)
+            .map(|x| x.oracle)
+        {
+            if !responding_oracles.contains(&oracle) {
+                responding_oracles.push(oracle);
+            }
+        }
+        let signers = load_oracle_signers(client, &responding_oracles).await?;
+
+        let mut ixs = Vec::new();
+        let mut skipped = Vec::new();
+        let mut malformed = Vec::new();
+        let mut oracle_keys: Vec<Pubkey> = Vec::new();
+        for ((feed, (data, _)), (responses, mut errors)) in
+            params.feeds.iter().zip(&loaded).zip(feed_responses)
+        {
+            let (responses, invalid) =
+                verify_oracle_responses(data, &latest_slot.hash, &signers, responses);
+            errors.extend(invalid);
+            if !responses.iter().any(|x| x.value.is_some()) {
+                errors.extend(responses.iter().map(|x| OracleError {
+                    oracle: hex::encode(x.oracle),
+                    error: x.error.clone(),
+                }));
+                malformed.extend(errors.iter().cloned());
+                skipped.push(SkippedFeed {
+                    feed: *feed,
+                    per_oracle_errors: errors,
+                });
+                continue;
+            }
+            malformed.extend(errors);
+            for oracle in responses.iter().map(|x| x.oracle) {
+                if !oracle_keys.contains(&oracle) {
+                    oracle_keys.push(oracle);
+                }
+            }
+            ixs.push(PullFeed::get_solana_submit_signatures_ix(
+                latest_slot.slot,
+                responses,
+                SolanaSubmitSignaturesParams {
+                    feed: *feed,
+                    queue,
+                    payer: params.payer,
+                },
+            )?);
+        }
+        if params.debug.unwrap_or(false) {
+            for err in &malformed {
+                eprintln!("Skipping invalid response from {}: {}", err.oracle, err.error);
+            }
+        }
+        if ixs.is_empty() {
+            let per_oracle_errors = malformed
+                .into_iter()
+                .chain(price_signatures.errors.iter().map(|error| OracleError {
+                    oracle: String::new(),
+                    error: error.clone(),
+                }))
+                .collect();
+            return Err(ClientError::NoSuccessfulResponses { per_oracle_errors });
+        }
+
+        let luts =
+            PullFeed::fetch_luts(context, client, &oracle_keys, &params.feeds, queue).await?;
+        Ok((ixs, luts, skipped))
+    }
+
     fn get_solana_submit_signatures_ix(
         slot: u64,
         responses: Vec<OracleResponse>,
         params: SolanaSubmitSignaturesParams,
-    ) -> Result<Instruction, AnyhowError> {
+    ) -> Result<Instruction, ClientError> {
         let mut submissions = Vec::new();
         for resp in &responses {
             let mut value_i128 = i128::MAX;
@@ -226,44 +851,30 @@ impl PullFeed {
             usize,
             Vec<AddressLookupTableAccount>,
         ),
-        AnyhowError,
+        ClientError,
     > {
         let latest_slot = SlotHashSysvar::get_latest_slothash(client)
-            .await
-            .context("PullFeed.fetchUpdateIx: Failed to fetch latest slot")?;
+            .await?;
 
-        let feed_data = *context
+        let feed_data = context
             .pull_feed_cache
-            .entry(params.feed)
-            .or_insert_with(OnceCell::new)
-            .get_or_try_init(|| PullFeed::load_data(client, &params.feed))
+            .get_or_try_init(params.feed, || PullFeed::load_data(client, &params.feed))
             .await?;
 
         let feed_hash = feed_data.feed_hash;
         let jobs = context
             .job_cache
-            .entry(feed_hash)
-            .or_insert_with(OnceCell::new)
-            .get_or_try_init(|| {
+            .get_or_try_init(feed_hash, || {
                 let crossbar = params.crossbar.clone().unwrap_or_default();
                 async move {
-                    let jobs_data = crossbar
-                        .fetch(&hex::encode(feed_hash))
-                        .await
-                        .context("PullFeed.fetchUpdateIx: Failed to fetch jobs")?;
-
-                    let jobs: Vec<OracleJob> =
-                        serde_json::from_value(jobs_data.get("jobs").unwrap().clone())
-                            .context("PullFeed.fetchUpdateIx: Failed to deserialize jobs")?;
-
-                    Ok::<Vec<OracleJob>, AnyhowError>(jobs)
+                    let jobs_data = crossbar.fetch(&hex::encode(feed_hash)).await?;
+                    parse_jobs(jobs_data)
                 }
             })
-            .await?
-            .clone();
+            .await?;
 
         let encoded_jobs = encode_jobs(&jobs);
-        let gateway = params.gateway;
+        let gateway = gateway_source(&params.gateway, &params.gateway_pool);
 
         let num_signatures = if params.num_signatures.is_none() {
             (feed_data.min_sample_size as f64 + ((feed_data.min_sample_size as f64) / 3.0).ceil())
@@ -281,47 +892,41 @@ impl PullFeed {
                 min_responses: Some(feed_data.min_responses),
                 use_timestamp: Some(false),
             })
-            .await
-            .context("PullFeed.fetchUpdateIx: Failed to fetch signatures")?;
+            .await?;
 
-        let mut num_successes = 0;
-        let oracle_responses: Vec<OracleResponse> = price_signatures
-            .responses
-            .iter()
-            .map(|x| {
-                let value = x.success_value.parse::<i128>().ok();
-                let mut formatted_value = None;
-                if let Some(val) = value {
-                    num_successes += 1;
-                    formatted_value = Some(Decimal::from_i128_with_scale(val, 18));
-                }
-                OracleResponse {
-                    value: formatted_value,
-                    error: x.failure_error.clone(),
-                    oracle: Pubkey::new_from_array(
-                        hex::decode(x.oracle_pubkey.clone())
-                            .unwrap()
-                            .try_into()
-                            .unwrap(),
-                    ),
-                    recovery_id: x.recovery_id as u8,
-                    signature: base64
-                        .decode(x.signature.clone())
-                        .unwrap_or_default()
-                        .try_into()
-                        .unwrap_or([0; 64]),
-                }
-            })
-            .collect();
+        let (oracle_responses, mut malformed) =
+            parse_oracle_responses(&price_signatures.responses);
+        // Drop responses that would fail the on-chain signature check.
+        let responding_oracles: Vec<Pubkey> = oracle_responses.iter().map(|x| x.oracle).collect();
+        let signers = load_oracle_signers(client, &responding_oracles).await?;
+        let (oracle_responses, invalid) =
+            verify_oracle_responses(&feed_data, &latest_slot.hash, &signers, oracle_responses);
+        malformed.extend(invalid);
+        let num_successes = oracle_responses.iter().filter(|x| x.value.is_some()).count();
 
         if params.debug.unwrap_or(false) {
             println!("priceSignatures: {:?}", price_signatures);
+            for err in &malformed {
+                eprintln!("Skipping invalid response from {}: {}", err.oracle, err.error);
+            }
         }
 
         if num_successes == 0 {
-            return Err(anyhow_ext::Error::msg(
-                "PullFeed.fetchUpdateIx Failure: No successful responses".to_string(),
-            ));
+            // Failures not tied to a responding oracle are reported with an
+            // empty oracle key.
+            let per_oracle_errors = oracle_responses
+                .iter()
+                .map(|x| OracleError {
+                    oracle: hex::encode(x.oracle),
+                    error: x.error.clone(),
+                })
+                .chain(malformed)
+                .chain(price_signatures.failures.iter().map(|error| OracleError {
+                    oracle: String::new(),
+                    error: error.clone(),
+                }))
+                .collect();
+            return Err(ClientError::NoSuccessfulResponses { per_oracle_errors });
         }
 
         let submit_signatures_ix = PullFeed::get_solana_submit_signatures_ix(
@@ -332,8 +937,7 @@ impl PullFeed {
                 queue: feed_data.queue,
                 payer: params.payer,
             },
-        )
-        .context("PullFeed.fetchUpdateIx: Failed to create submit signatures instruction")?;
+        )?;
 
         let oracle_keys: Vec<Pubkey> = oracle_responses.iter().map(|x| x.oracle).collect();
         let feed_key = [params.feed];
@@ -359,6 +963,11 @@ impl PullFeed {
 /// build the necessary secp256k1 verification instruction and the feed update instruction,
 /// and return these instructions along with the required lookup tables.
 ///
+/// Feeds are grouped by queue, with one consensus request and one pair of
+/// instructions per queue, in the order the queues first appear in `feeds`.
+/// Each secp256k1 instruction references its own position, so the returned
+/// instructions must be placed at the start of the transaction, in order.
+///
 /// # Arguments
 /// * `context` - Shared context holding caches for feeds, jobs, and lookup tables.
 /// * `client` - The RPC client for connecting to the cluster.
@@ -366,62 +975,89 @@ impl PullFeed {
 ///     - `feeds`: A vector of feed public keys.
 ///     - `payer`: The payer public key.
 ///     - `gateway`: A Gateway instance for the API calls.
+///     - `gateway_pool`: Optional pool of gateways to use with failover instead of `gateway`.
 ///     - `crossbar`: Optional CrossbarClient instance.
 ///     - `num_signatures`: Optional override for the number of signatures to fetch.
 ///     - `debug`: Optional flag to print debug logs.
+///     - `single_queue`: Optional flag to fail if the feeds span several queues.
+///     - `queue_gateways`: The gateways to use for queues other than the queue of `gateway`.
 ///
 /// # Returns
 /// A tuple containing:
-///   1. A vector of two Instructions (first is secp256k1 verification, second is the feed update).
-///   2. A vector of AddressLookupTableAccount to include in the transaction.
+///   1. A vector of two Instructions per queue (first is secp256k1 verification, second is the feed update).
+///   2. A vector of AddressLookupTableAccount to include in the transaction, without duplicates.
     pub async fn fetch_update_consensus_ix(
         context: Arc<SbContext>,
         client: &RpcClient,
         params: FetchUpdateManyParams,
-    ) -> Result<(Vec<Instruction>, Vec<AddressLookupTableAccount>), AnyhowError> {
-        let gateway = params.gateway;
+    ) -> Result<(Vec<Instruction>, Vec<AddressLookupTableAccount>), ClientError> {
+        // For each feed, load its on-chain data and encoded jobs, and group them by queue.
+        let loaded = PullFeed::load_feeds(&context, client, &params.feeds, &params.crossbar).await?;
+        let mut groups: Vec<(Pubkey, Vec<(Pubkey, PullFeedAccountData, Vec<String>)>)> = Vec::new();
+        for (feed, (data, encoded_jobs)) in params.feeds.iter().zip(loaded) {
+            match groups.iter_mut().find(|(queue, _)| *queue == data.queue) {
+                Some((_, feeds)) => feeds.push((*feed, data, encoded_jobs)),
+                None => groups.push((data.queue, vec![(*feed, data, encoded_jobs)])),
+            }
+        }
+        if groups.is_empty() {
+            return Err(ClientError::InvalidInput("No feeds to update".to_string()));
+        }
+        if groups.len() > 1 && params.single_queue.unwrap_or(false) {
+            return Err(ClientError::InvalidInput(format!(
+                "Feeds belong to {} different queues",
+                groups.len()
+            )));
+        }
+
+        // Get the latest slot.
+        let latest_slot = SlotHashSysvar::get_latest_slothash(client)
+            .await?;
+
+        let mut ixs = Vec::new();
+        let mut luts: Vec<AddressLookupTableAccount> = Vec::new();
+        for (queue, feeds) in groups {
+            let instruction_index = u8::try_from(ixs.len()).map_err(|_| {
+                ClientError::InvalidInput("Too many queues for one transaction".to_string())
+            })?;
+            let (queue_ixs, queue_luts) = PullFeed::fetch_queue_consensus_ix(
+                context.clone(),
+                client,
+                &params,
+                queue,
+                feeds,
+                &latest_slot,
+                instruction_index,
+            )
+            .await?;
+            ixs.extend(queue_ixs);
+            for lut in queue_luts {
+                if !luts.iter().any(|x| x.key == lut.key) {
+                    luts.push(lut);
+                }
+            }
+        }
+        Ok((ixs, luts))
+    }
+
+    /// The secp256k1 and submit instructions updating `feeds` of `queue`,
+    /// with the secp256k1 instruction placed at `instruction_index` in the
+    /// transaction.
+    async fn fetch_queue_consensus_ix(
+        context: Arc<SbContext>,
+        client: &RpcClient,
+        params: &FetchUpdateManyParams,
+        queue: Pubkey,
+        feeds: Vec<(Pubkey, PullFeedAccountData, Vec<String>)>,
+        latest_slot: &SlotHash,
+        instruction_index: u8,
+    ) -> Result<(Vec<Instruction>, Vec<AddressLookupTableAccount>), ClientError> {
         let mut num_signatures = params.num_signatures.unwrap_or(1);
         let mut feed_configs = Vec::new();
-        let mut queue = Pubkey::default();
         let mut feed_datas = Vec::new();
-        // For each feed, load its on-chain data and build its configuration (jobs, encoded jobs, etc.)
-        for feed in &params.feeds {
-            let data = *context
-                .pull_feed_cache
-                .entry(*feed)
-                .or_insert_with(OnceCell::new)
-                .get_or_try_init(|| PullFeed::load_data(client, feed))
-                .await?;
+        for (feed, data, encoded_jobs) in feeds {
             feed_datas.push((feed, data));
-            let num_sig_lower_bound =
-                data.min_sample_size as u32 + ((data.min_sample_size as f64) / 3.0).ceil() as u32;
-            if num_signatures < num_sig_lower_bound {
-                num_signatures = num_sig_lower_bound;
-            }
-            queue = data.queue;
-            // Fetch jobs from the crossbar (or use cache) and encode them.
-            let jobs = context
-                .job_cache
-                .entry(data.feed_hash)
-                .or_insert_with(OnceCell::new)
-                .get_or_try_init(|| {
-                    let crossbar = params.crossbar.clone().unwrap_or_default();
-                    async move {
-                        let jobs_data = crossbar
-                            .fetch(&hex::encode(data.feed_hash))
-                            .await
-                            .context("PullFeed.fetchUpdateIx: Failed to fetch jobs")?;
-
-                        let jobs: Vec<OracleJob> =
-                            serde_json::from_value(jobs_data.get("jobs").unwrap().clone())
-                                .context("PullFeed.fetchUpdateIx: Failed to deserialize jobs")?;
-
-                        Ok::<Vec<OracleJob>, AnyhowError>(jobs)
-                    }
-                })
-                .await?
-                .clone();
-            let encoded_jobs = encode_jobs(&jobs);
+            num_signatures = num_signatures.max(min_num_signatures(&data));
             let max_variance = (data.max_variance / 1_000_000_000) as u32;
             let min_responses = data.min_responses;
             // Build the feed configuration required by the gateway.
@@ -432,12 +1068,11 @@ impl PullFeed {
             });
         }
 
-        // Get the latest slot.
-        let latest_slot = SlotHashSysvar::get_latest_slothash(client)
-            .await
-            .context("PullFeed.fetchUpdateIx: Failed to fetch latest slot")?;
-        
         // Call the gateway consensus endpoint and fetch signatures
+        let gateway = match params.queue_gateways.get(&queue) {
+            Some(gateway) => gateway_source(gateway, &None),
+            None => gateway_source(&params.gateway, &params.gateway_pool),
+        };
         let price_signatures = gateway
             .fetch_signatures_consensus(FetchSignaturesConsensusParams {
                 recent_hash: Some(bs58::encode(latest_slot.hash).into_string()),
@@ -445,11 +1080,12 @@ impl PullFeed {
                 feed_configs,
                 use_timestamp: Some(false),
             })
-            .await
-            .context("PullFeed.fetchUpdateIx: fetch signatures consensus failure")?;
+            .await?;
         if params.debug.unwrap_or(false) {
             println!("priceSignatures: {:?}", price_signatures);
         }
+        let (parsed, mut malformed) =
+            parse_consensus_responses(&price_signatures.oracle_responses);
 
         // Parse the median responses into i128 values and build the consensus payload.
         let consensus_values: Vec<i128> = price_signatures
@@ -457,48 +1093,64 @@ impl PullFeed {
             .iter()
             .map(|mr| mr.value.parse::<i128>().unwrap_or(i128::MAX))
             .collect();
+
+        // Drop signatures that would fail the secp256k1 program or the
+        // on-chain checksum check.
+        let signed_feeds: Vec<PullFeedAccountData> = price_signatures
+            .median_responses
+            .iter()
+            .map(|median_response| {
+                feed_datas
+                    .iter()
+                    .find(|(_, data)| hex::encode(data.feed_hash) == median_response.feed_hash)
+                    .map(|(_, data)| *data)
+                    .unwrap_or_else(bytemuck::Zeroable::zeroed)
+            })
+            .collect();
+        let checksum = generate_combined_checksum(
+            &queue.to_bytes(),
+            &signed_feeds,
+            &latest_slot.hash,
+            &consensus_values,
+        );
+        let responding_oracles: Vec<Pubkey> = parsed.iter().map(|(oracle, _)| *oracle).collect();
+        let signers = load_oracle_signers(client, &responding_oracles).await?;
+        let (oracle_keys, secp_signatures): (Vec<Pubkey>, Vec<SecpSignature>) = parsed
+            .into_iter()
+            .filter(|(oracle, signature)| {
+                let valid = signers.get(oracle).map_or(false, |signer| {
+                    verify_consensus_signature(signer, &checksum, signature)
+                });
+                if !valid {
+                    malformed.push(OracleError {
+                        oracle: hex::encode(oracle),
+                        error: "Signature does not match the consensus checksum".to_string(),
+                    });
+                }
+                valid
+            })
+            .unzip();
+        if params.debug.unwrap_or(false) {
+            for err in &malformed {
+                eprintln!("Skipping invalid response from {}: {}", err.oracle, err.error);
+            }
+        }
+        if oracle_keys.is_empty() {
+            return Err(ClientError::NoSuccessfulResponses {
+                per_oracle_errors: malformed,
+            });
+        }
         // Build the consensus Ix data.
         let consensus_ix_data = PullFeedSubmitResponseConsensusParams {
             slot: latest_slot.slot,
             values: consensus_values,
         };
-        // Extract oracle keys from the gateway responses.
         let mut remaining_accounts = Vec::new();
-        let oracle_keys: Vec<Pubkey> = price_signatures
-            .oracle_responses
-            .iter()
-            .map(|x| {
-                Pubkey::new_from_array(
-                    hex::decode(x.feed_responses.first().unwrap().oracle_pubkey.clone())
-                        .unwrap_or_default()
-                        .try_into()
-                        .unwrap(),
-                )
-            })
-            .collect();
-        // Map the gateway oracle responses to our SecpSignature struct.
-        let secp_signatures: Vec<SecpSignature> = price_signatures
-            .oracle_responses
-            .iter()
-            .map(|oracle_response| SecpSignature {
-                eth_address: hex::decode(&oracle_response.eth_address)  
-                    .unwrap()
-                    .try_into()
-                    .expect("slice with incorrect length"),
-                signature: base64.decode(&oracle_response.signature)  
-                    .unwrap()
-                    .try_into()
-                    .expect("slice with incorrect length"),
-                message: base64.decode(&oracle_response.checksum)
-                    .unwrap()
-                    .try_into()
-                    .expect("slice with incorrect length"),
-                recovery_id: oracle_response.recovery_id as u8,
-            })
-            .collect();
 
         // Build the secp256k1 instruction:
-        let secp_ix = Secp256k1InstructionUtils::build_secp256k1_instruction(&secp_signatures, 0).unwrap();
+        let secp_ix =
+            Secp256k1InstructionUtils::build_secp256k1_instruction(&secp_signatures, instruction_index)
+            .map_err(|e| ClientError::SignatureDecode(e.to_string()))?;
 
         // Match each median response to its corresponding feed account by comparing feed hashes.
         let feed_pubkeys: Vec<Pubkey> = price_signatures
@@ -510,7 +1162,7 @@ impl PullFeed {
                     feed_hash_hex == median_response.feed_hash
                 });
                 if let Some((feed, _)) = matching {
-                    **feed
+                    *feed
                 } else {
                     if params.debug.unwrap_or(false) {
                         eprintln!("Feed not found for hash: {}", median_response.feed_hash);
@@ -531,9 +1183,10 @@ impl PullFeed {
         }
         // Load lookup tables for oracle, feed, and queue accounts concurrently.
         let queue_key = [queue];
+        let feed_keys: Vec<Pubkey> = feed_datas.iter().map(|(feed, _)| *feed).collect();
         let (oracle_luts_result, pull_feed_luts_result, queue_lut_result) = join!(
             fetch_and_cache_luts::<OracleAccountData>(client, context.clone(), &oracle_keys),
-            fetch_and_cache_luts::<PullFeedAccountData>(client, context.clone(), &params.feeds),
+            fetch_and_cache_luts::<PullFeedAccountData>(client, context.clone(), &feed_keys),
             fetch_and_cache_luts::<QueueAccountData>(client, context.clone(), &queue_key)
         );
 
@@ -568,3 +1221,214 @@ impl PullFeed {
         Ok((ixs, luts))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    const ORACLE: &str = "0b1c3e5a7f9d2b4c6e8a0d2f4b6d8f0a1c3e5a7f9d2b4c6e8a0d2f4b6d8f0a1c";
+
+    fn feed_eval_response(oracle_pubkey: &str, success_value: &str) -> serde_json::Value {
+        serde_json::json!({
+            "oracle_pubkey": oracle_pubkey,
+            "queue_pubkey": "",
+            "oracle_signing_pubkey": "",
+            "feed_hash": "",
+            "recent_hash": "",
+            "failure_error": if success_value.is_empty() { "fetch failed" } else { "" },
+            "success_value": success_value,
+            "msg": "",
+            "signature": base64.encode([7u8; 64]),
+            "recovery_id": 1,
+            "recent_successes_if_failed": [],
+            "timestamp": null,
+        })
+    }
+
+    fn single_response_json() -> String {
+        serde_json::json!({
+            "responses": [
+                feed_eval_response(ORACLE, "1000000000000000000"),
+                feed_eval_response(ORACLE, ""),
+            ],
+            "caller": "",
+            "failures": [],
+        })
+        .to_string()
+    }
+
+    fn consensus_response_json() -> String {
+        serde_json::json!({
+            "median_responses": [{ "value": "1000000000000000000", "feed_hash": "00" }],
+            "oracle_responses": [{
+                "oracle_pubkey": ORACLE,
+                "eth_address": hex::encode([3u8; 20]),
+                "signature": base64.encode([7u8; 64]),
+                "checksum": base64.encode([9u8; 32]),
+                "recovery_id": 0,
+                "feed_responses": [feed_eval_response(ORACLE, "1000000000000000000")],
+                "errors": [],
+            }],
+        })
+        .to_string()
+    }
+
+    /// Deterministically corrupts `json` by overwriting, dropping or
+    /// truncating bytes, driven by a xorshift generator seeded with `seed`.
+    fn corrupt(json: &str, seed: u64) -> String {
+        const JUNK: &[u8] = b"{}[]\":,0aZ-";
+        let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut bytes = json.as_bytes().to_vec();
+        for _ in 0..(next() % 4 + 1) {
+            if bytes.is_empty() {
+                break;
+            }
+            let idx = (next() % bytes.len() as u64) as usize;
+            match next() % 3 {
+                0 => bytes[idx] = JUNK[(next() % JUNK.len() as u64) as usize],
+                1 => {
+                    bytes.remove(idx);
+                }
+                _ => bytes.truncate(idx),
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    #[test]
+    fn test_parse_oracle_responses() {
+        let resp: FeedEvalResponseSingle = serde_json::from_str(&single_response_json()).unwrap();
+        let (parsed, malformed) = parse_oracle_responses(&resp.responses);
+        assert!(malformed.is_empty());
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].value, Some(Decimal::ONE));
+        assert_eq!(parsed[0].signature, [7u8; 64]);
+        assert_eq!(parsed[1].value, None);
+        assert_eq!(parsed[1].error, "fetch failed");
+    }
+
+    #[test]
+    fn test_parse_oracle_responses_skips_malformed() {
+        let mut bad_signature = feed_eval_response(ORACLE, "1");
+        bad_signature["signature"] = serde_json::json!("not base64");
+        let responses: Vec<FeedEvalResponse> = serde_json::from_value(serde_json::json!([
+            feed_eval_response("zz", "1"),
+            feed_eval_response(&ORACLE[2..], "1"),
+            bad_signature,
+            feed_eval_response(ORACLE, "1"),
+        ]))
+        .unwrap();
+        let (parsed, malformed) = parse_oracle_responses(&responses);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(malformed.len(), 3);
+        assert_eq!(malformed[0].oracle, "zz");
+    }
+
+    #[test]
+    fn test_parse_consensus_responses_skips_malformed() {
+        let mut resp: FetchSignaturesConsensusResponse =
+            serde_json::from_str(&consensus_response_json()).unwrap();
+        let mut bad_eth = resp.oracle_responses[0].clone();
+        bad_eth.eth_address = "0102".to_string();
+        let mut no_feeds = resp.oracle_responses[0].clone();
+        no_feeds.feed_responses.clear();
+        resp.oracle_responses.extend([bad_eth, no_feeds]);
+
+        let (parsed, malformed) = parse_consensus_responses(&resp.oracle_responses);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].1.eth_address, [3u8; 20]);
+        assert_eq!(parsed[0].1.message, vec![9u8; 32]);
+        assert_eq!(malformed.len(), 2);
+    }
+
+    #[test]
+    fn test_corrupted_single_response_never_panics() {
+        let json = single_response_json();
+        for seed in 0..2_000 {
+            let raw = corrupt(&json, seed);
+            let decoded = crate::gateway::decode_response::<FeedEvalResponseSingle>(
+                StatusCode::OK,
+                raw,
+            );
+            if let Ok(resp) = decoded {
+                let (parsed, malformed) = parse_oracle_responses(&resp.responses);
+                assert_eq!(parsed.len() + malformed.len(), resp.responses.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_corrupted_consensus_response_never_panics() {
+        let json = consensus_response_json();
+        for seed in 0..2_000 {
+            let raw = corrupt(&json, seed);
+            let decoded = crate::gateway::decode_response::<FetchSignaturesConsensusResponse>(
+                StatusCode::OK,
+                raw,
+            );
+            if let Ok(resp) = decoded {
+                let (parsed, malformed) = parse_consensus_responses(&resp.oracle_responses);
+                assert_eq!(parsed.len() + malformed.len(), resp.oracle_responses.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_response_reports_status_and_body() {
+        let err = crate::gateway::decode_response::<FeedEvalResponseSingle>(
+            StatusCode::BAD_GATEWAY,
+            "upstream down".to_string(),
+        )
+        .unwrap_err();
+        assert!(err.is_transient());
+        assert!(matches!(
+            err,
+            ClientError::GatewayHttp { status: Some(502), ref body } if body == "upstream down"
+        ));
+
+        let err = crate::gateway::decode_response::<FeedEvalResponseSingle>(
+            StatusCode::OK,
+            "{\"responses\": [".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ClientError::GatewayDecode(_)));
+    }
+
+    #[test]
+    fn test_job_cache_round_trips_through_disk() {
+        let path = std::env::temp_dir().join(format!("sb-job-cache-{}.json", std::process::id()));
+        let jobs = vec![OracleJob::builder().value(1.5).build()];
+        let context = SbContext::new();
+        context.job_cache.insert([1; 32], jobs.clone());
+        context.save_job_cache(&path).unwrap();
+
+        let restored = SbContext::new();
+        assert_eq!(restored.load_job_cache(&path).unwrap(), 1);
+        assert_eq!(restored.job_cache.get(&[1; 32]), Some(jobs));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(restored.load_job_cache(&path).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_invalidate_feed() {
+        let context = SbContext::with_config(CacheConfig {
+            pull_feed_ttl: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+        let feed = Pubkey::new_unique();
+        context
+            .pull_feed_cache
+            .insert(feed, bytemuck::Zeroable::zeroed());
+        assert!(context.pull_feed_cache.get(&feed).is_some());
+        assert!(context.invalidate_feed(&feed));
+        assert!(context.pull_feed_cache.get(&feed).is_none());
+        assert!(!context.invalidate_lut(&feed));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::*;
+use anyhow_ext::anyhow;
+use anyhow_ext::Context;
+use anyhow_ext::Error as AnyhowError;
+use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use solana_client::nonblocking::rpc_client::RpcClient;
+#[cfg(not(feature = "solana_sdk_1_16"))]
+use solana_sdk::address_lookup_table::instruction::derive_lookup_table_address;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::signer::keypair::Keypair;
+use solana_sdk::system_program;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SEED_SLOT_POLL_INTERVAL: Duration = Duration::from_millis(400);
+const SEED_SLOT_MAX_POLLS: usize = 150;
+
+pub struct Randomness;
+
+impl Randomness {
+    /// Builds the instruction creating `randomness` on `queue`, with `payer`
+    /// as its authority.
+    pub fn init_ix(
+        randomness: Pubkey,
+        queue: Pubkey,
+        payer: Pubkey,
+        recent_slot: u64,
+    ) -> Instruction {
+        let lut_signer = find_lut_signer(&randomness);
+        let lut = derive_lookup_table_address(&lut_signer, recent_slot).0;
+        Instruction {
+            program_id: *SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            data: RandomnessInitParams { recent_slot }.data(),
+            accounts: RandomnessInit {
+                randomness,
+                reward_escrow: RandomnessAccountData::reward_escrow(&randomness),
+                authority: payer,
+                queue,
+                payer,
+                system_program: system_program::ID,
+                token_program: *SPL_TOKEN_PROGRAM_ID,
+                associated_token_program: *SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+                wrapped_sol_mint: *NATIVE_MINT,
+                program_state: State::key(),
+                lut_signer,
+                lut,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Builds the instruction committing `randomness` to the next slothash,
+    /// assigning `oracle` to reveal it.
+    pub fn commit_ix(
+        randomness: Pubkey,
+        queue: Pubkey,
+        oracle: Pubkey,
+        authority: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: *SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            data: RandomnessCommitParams.data(),
+            accounts: RandomnessCommit {
+                randomness,
+                queue,
+                oracle,
+                recent_slothashes: solana_sdk::sysvar::slot_hashes::ID,
+                authority,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Builds the instruction revealing a committed randomness account with
+    /// the value signed by its assigned oracle.
+    pub fn reveal_ix(
+        randomness: Pubkey,
+        data: &RandomnessAccountData,
+        payer: Pubkey,
+        response: &RandomnessRevealResponse,
+    ) -> Result<Instruction, AnyhowError> {
+        let signature: [u8; 64] = base64
+            .decode(&response.signature)
+            .context("Randomness.reveal_ix: Failed to decode signature")?
+            .try_into()
+            .map_err(|_| anyhow!("Randomness.reveal_ix: Invalid signature length"))?;
+        let value: [u8; 32] = response
+            .value
+            .clone()
+            .try_into()
+            .map_err(|_| anyhow!("Randomness.reveal_ix: Invalid value length"))?;
+        Ok(Instruction {
+            program_id: *SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            data: RandomnessRevealParams {
+                signature,
+                recovery_id: response.recovery_id as u8,
+                value,
+            }
+            .data(),
+            accounts: RandomnessReveal {
+                randomness,
+                oracle: data.oracle,
+                queue: data.queue,
+                stats: RandomnessAccountData::stats_key(&data.oracle),
+                authority: data.authority,
+                payer,
+                recent_slothashes: solana_sdk::sysvar::slot_hashes::ID,
+                system_program: system_program::ID,
+                reward_escrow: RandomnessAccountData::reward_escrow(&randomness),
+                token_program: *SPL_TOKEN_PROGRAM_ID,
+                associated_token_program: *SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+                wrapped_sol_mint: *NATIVE_MINT,
+                program_state: State::key(),
+            }
+            .to_account_metas(None),
+        })
+    }
+
+    /// Picks an oracle on the queue that has heartbeated recently and
+    /// advertises a gateway.
+    pub async fn select_oracle(
+        client: &RpcClient,
+        queue: &Pubkey,
+    ) -> Result<(Pubkey, OracleAccountData), AnyhowError> {
+        let queue_data = QueueAccountData::load(client, queue).await?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let fresh: Vec<(Pubkey, OracleAccountData)> = queue_data
+            .fetch_oracle_accounts(client)
+            .await?
+            .into_iter()
+            .filter(|(_, oracle)| now - oracle.last_heartbeat <= MAX_STALE_SECONDS)
+            .filter(|(_, oracle)| oracle.gateway_uri().is_some())
+            .collect();
+        if fresh.is_empty() {
+            return Err(anyhow!(
+                "Randomness.select_oracle: No fresh oracles on queue"
+            ));
+        }
+        let slot = client.get_slot().await?;
+        Ok(fresh[slot as usize % fresh.len()])
+    }
+
+    /// Creates a randomness account on `queue`, commits it, waits for the
+    /// seed slot to pass, reveals it through the assigned oracle's gateway
+    /// and returns the revealed 32 byte value.
+    pub async fn commit_and_reveal(
+        client: &RpcClient,
+        payer: &Keypair,
+        queue: Pubkey,
+    ) -> Result<[u8; 32], AnyhowError> {
+        let randomness = Keypair::new();
+        let randomness_key = randomness.pubkey();
+
+        let recent_slot = client.get_slot().await?;
+        let init_ix = Self::init_ix(randomness_key, queue, payer.pubkey(), recent_slot);
+        Self::send(client, &[init_ix], &[payer, &randomness])
+            .await
+            .context("Randomness.commit_and_reveal: Failed to create randomness account")?;
+
+        let (oracle_key, oracle) = Self::select_oracle(client, &queue).await?;
+        let commit_ix = Self::commit_ix(randomness_key, queue, oracle_key, payer.pubkey());
+        Self::send(client, &[commit_ix], &[payer])
+            .await
+            .context("Randomness.commit_and_reveal: Failed to commit randomness")?;
+
+        let data = RandomnessAccountData::load(client, &randomness_key).await?;
+        Self::wait_for_slot_after(client, data.seed_slot).await?;
+
+        let gateway = Gateway::new(oracle.gateway_uri().unwrap_or_default());
+        let response = gateway
+            .fetch_randomness_reveal(FetchRandomnessRevealParams {
+                randomness_key: randomness_key.to_bytes(),
+                slot: data.seed_slot,
+                slothash: data.seed_slothash,
+            })
+            .await
+            .context("Randomness.commit_and_reveal: Failed to fetch reveal")?;
+        let signer = oracle
+            .libsecp256k1_signer()
+            .ok_or_else(|| anyhow!("Randomness.commit_and_reveal: Oracle has no signer"))?;
+        if !verify_randomness_reveal(&signer, &response) {
+            return Err(anyhow!(
+                "Randomness.commit_and_reveal: Reveal not signed by the oracle"
+            ));
+        }
+        let reveal_ix = Self::reveal_ix(randomness_key, &data, payer.pubkey(), &response)?;
+        Self::send(client, &[reveal_ix], &[payer])
+            .await
+            .context("Randomness.commit_and_reveal: Failed to reveal randomness")?;
+
+        let data = RandomnessAccountData::load(client, &randomness_key).await?;
+        if !data.is_revealed() {
+            return Err(anyhow!(
+                "Randomness.commit_and_reveal: Randomness not revealed"
+            ));
+        }
+        Ok(data.value)
+    }
+
+    async fn wait_for_slot_after(client: &RpcClient, seed_slot: u64) -> Result<(), AnyhowError> {
+        for _ in 0..SEED_SLOT_MAX_POLLS {
+            if client.get_slot().await? > seed_slot {
+                return Ok(());
+            }
+            tokio::time::sleep(SEED_SLOT_POLL_INTERVAL).await;
+        }
+        Err(anyhow!(
+            "Randomness.commit_and_reveal: Timed out waiting for seed slot"
+        ))
+    }
+
+    async fn send(
+        client: &RpcClient,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), AnyhowError> {
+        let blockhash = client.get_latest_blockhash().await?;
+        let tx = ix_to_tx(ixs, signers, blockhash)?;
+        client.send_and_confirm_transaction(&tx).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshDeserialize;
+
+    #[test]
+    fn test_reveal_ix_uses_committed_accounts() {
+        let mut data: RandomnessAccountData = bytemuck::Zeroable::zeroed();
+        data.authority = Pubkey::new_unique();
+        data.queue = Pubkey::new_unique();
+        data.oracle = Pubkey::new_unique();
+        let (randomness, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut response = RandomnessRevealResponse {
+            signature: base64.encode([1u8; 64]),
+            recovery_id: 1,
+            value: vec![3; 32],
+        };
+
+        let ix = Randomness::reveal_ix(randomness, &data, payer, &response).unwrap();
+        let params = RandomnessRevealParams::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(ix.data[..8], get_discriminator("randomness_reveal")[..]);
+        assert_eq!(
+            (params.signature, params.recovery_id, params.value),
+            ([1; 64], 1, [3; 32])
+        );
+        let accounts: Vec<Pubkey> = ix.accounts.iter().map(|x| x.pubkey).collect();
+        assert_eq!(
+            accounts[..6],
+            [
+                randomness,
+                data.oracle,
+                data.queue,
+                RandomnessAccountData::stats_key(&data.oracle),
+                data.authority,
+                payer
+            ]
+        );
+        assert_eq!(
+            accounts[8],
+            RandomnessAccountData::reward_escrow(&randomness)
+        );
+
+        response.value.pop();
+        assert!(Randomness::reveal_ix(randomness, &data, payer, &response).is_err());
+        response.value.push(3);
+        response.signature = base64.encode([1u8; 63]);
+        assert!(Randomness::reveal_ix(randomness, &data, payer, &response).is_err());
+    }
+}
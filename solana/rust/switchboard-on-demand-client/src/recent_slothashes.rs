@@ -1,5 +1,4 @@
-use anyhow_ext::Context;
-use anyhow_ext::Error as AnyhowError;
+use crate::ClientError;
 use arrayref::array_ref;
 use bytemuck;
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -22,17 +21,22 @@ impl SlotHash {
 
 pub struct SlotHashSysvar;
 impl<'a> SlotHashSysvar {
-    pub async fn get_latest_slothash(client: &RpcClient) -> Result<SlotHash, AnyhowError> {
+    pub async fn get_latest_slothash(client: &RpcClient) -> Result<SlotHash, ClientError> {
         let slots_data = client
             .get_account_with_commitment(
                 &solana_sdk::sysvar::slot_hashes::ID,
                 CommitmentConfig::confirmed(),
             )
-            .await
-            .context("Failed to fetch slot hashes")?
+            .await?
             .value
-            .context("Failed to fetch slot hashes")?
+            .ok_or(ClientError::AccountNotFound(solana_sdk::sysvar::slot_hashes::ID))?
             .data;
+        if slots_data.len() < 8 + 20_480 {
+            return Err(ClientError::InvalidAccountData {
+                account: solana_sdk::sysvar::slot_hashes::ID,
+                reason: "Slot hashes sysvar is too short".to_string(),
+            });
+        }
         let slots: &[u8] = array_ref![slots_data, 8, 20_480];
         // 20_480 / 40 = 512
         let slots: &[SlotHash] = bytemuck::cast_slice::<u8, SlotHash>(slots);
@@ -0,0 +1,1523 @@
+use crate::oracle_job::{JobExecutor, OracleJob, StaticFetcher};
+use crate::*;
+use base64::prelude::*;
+use prost::Message as _;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// An oracle with a locally generated secp256k1 signing key.
+#[derive(Clone)]
+pub struct MockOracle {
+    pub pubkey: Pubkey,
+    secret_key: libsecp256k1::SecretKey,
+}
+
+impl fmt::Debug for MockOracle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockOracle")
+            .field("pubkey", &self.pubkey)
+            .field("eth_address", &hex::encode(self.eth_address()))
+            .finish()
+    }
+}
+
+impl MockOracle {
+    /// Returns `None` if `secret_key` is not a valid secp256k1 secret key.
+    pub fn new(pubkey: Pubkey, secret_key: [u8; 32]) -> Option<Self> {
+        let secret_key = libsecp256k1::SecretKey::parse(&secret_key).ok()?;
+        Some(Self { pubkey, secret_key })
+    }
+
+    /// Generates `n` oracles with deterministic keys.
+    pub fn generate(n: usize) -> Vec<Self> {
+        (0..n)
+            .map(|i| {
+                let seed: [u8; 32] = Sha256::digest(format!("mock-oracle-{}", i)).into();
+                let pubkey = Pubkey::new_from_array(Sha256::digest(seed).into());
+                Self::new(pubkey, seed).expect("hash is a valid secret key")
+            })
+            .collect()
+    }
+
+    pub fn secp_pubkey(&self) -> libsecp256k1::PublicKey {
+        libsecp256k1::PublicKey::from_secret_key(&self.secret_key)
+    }
+
+    /// The ethereum address of the signing key, as checked by the secp256k1
+    /// program.
+    pub fn eth_address(&self) -> [u8; 20] {
+        eth_address(&self.secp_pubkey())
+    }
+
+    /// Signs the 32 byte `hash`, as feed checksums are signed. Returns the
+    /// signature and its recovery id.
+    pub fn sign_hash(&self, hash: &[u8; 32]) -> ([u8; 64], u8) {
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(hash), &self.secret_key);
+        (signature.serialize(), recovery_id.serialize())
+    }
+
+    /// Signs the keccak256 hash of `message`, as verified by the secp256k1
+    /// program. Returns the signature and its recovery id.
+    pub fn sign_message(&self, message: &[u8]) -> ([u8; 64], u8) {
+        self.sign_hash(&Keccak256::digest(message).into())
+    }
+
+    /// The oracle account registering this oracle's secp256k1 signer.
+    pub fn account_data(&self) -> OracleAccountData {
+        let mut data: OracleAccountData = bytemuck::Zeroable::zeroed();
+        data.enclave
+            .secp256k1_signer
+            .copy_from_slice(&self.secp_pubkey().serialize()[1..]);
+        data
+    }
+}
+
+struct MockState {
+    queue: Pubkey,
+    oracles: Vec<MockOracle>,
+    executor: JobExecutor<StaticFetcher>,
+    slothash: SlotHash,
+    jobs: HashMap<[u8; 32], Vec<OracleJob>>,
+    accounts: HashMap<Pubkey, (Pubkey, Vec<u8>)>,
+    forced_status: Option<u16>,
+    prioritization_fees: Vec<u64>,
+    units_consumed: u64,
+    simulation_error: Option<TransactionError>,
+    send_failures: usize,
+    sent_transactions: Vec<VersionedTransaction>,
+}
+
+/// A local HTTP server serving the gateway, crossbar and RPC routes used by
+/// this crate, all on the same address, for tests without network access.
+///
+/// The mock oracles evaluate the requested jobs with a local `JobExecutor`
+/// and sign the results with their secp256k1 keys, so responses verify the
+/// same way as those of real oracles.
+///
+/// Gateway routes: `/gateway/api/v1/test`, the `fetch_signatures`, `_multi`,
+/// `_batch` and `_consensus` routes, `ping`, `randomness_reveal`,
+/// `gateway_fetch_quote`, `gateway_attest_enclave` and
+/// `gateway_bridge_enclave`. The first oracle serves the gateway and the last
+/// one acts as its guardian. Crossbar routes: `/fetch`,
+/// `/store`, `/simulate` and `/simulate/solana`. RPC methods: `getVersion`,
+/// `getSlot`, `getAccountInfo`, `getMultipleAccounts`, `getLatestBlockhash`,
+/// `getRecentPrioritizationFees`, `simulateTransaction`, `sendTransaction` and
+/// `getSignatureStatuses`. Sent transactions are confirmed immediately.
+///
+/// # Examples
+///
+/// ```ignore
+/// let server = MockServer::start(queue, MockOracle::generate(3)).await;
+/// server.add_pull_feed(feed, vec![OracleJob::builder().value(101.5).build()]);
+/// let (ix, responses, num_successes, luts) = PullFeed::fetch_update_ix(
+///     SbContext::new(),
+///     &server.rpc_client(),
+///     FetchUpdateParams {
+///         feed,
+///         payer,
+///         gateway: server.gateway(),
+///         crossbar: Some(server.crossbar()),
+///         ..Default::default()
+///     },
+/// )
+/// .await?;
+/// ```
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    handle: JoinHandle<()>,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl MockServer {
+    /// Starts a server for `queue` with `oracles` answering every request.
+    /// The oracle accounts are created with their secp256k1 signers.
+    pub async fn start(queue: Pubkey, oracles: Vec<MockOracle>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(MockState {
+            queue,
+            oracles: oracles.clone(),
+            executor: JobExecutor::with_fetcher(StaticFetcher::default()),
+            slothash: SlotHash {
+                slot: 1,
+                hash: [1; 32],
+            },
+            jobs: HashMap::new(),
+            accounts: HashMap::new(),
+            forced_status: None,
+            prioritization_fees: vec![],
+            units_consumed: 200_000,
+            simulation_error: None,
+            send_failures: 0,
+            sent_transactions: vec![],
+        }));
+        let handle = tokio::spawn(serve(listener, state.clone()));
+        let server = Self {
+            addr,
+            state,
+            handle,
+        };
+        for oracle in &oracles {
+            server.set_oracle(oracle.pubkey, &oracle.account_data());
+        }
+        server
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn gateway(&self) -> Gateway {
+        Gateway::new(self.url())
+    }
+
+    pub fn crossbar(&self) -> CrossbarClient {
+        CrossbarClient::new(&self.url(), false)
+    }
+
+    pub fn rpc_client(&self) -> RpcClient {
+        RpcClient::new(self.url())
+    }
+
+    /// Sets the fetcher the oracles use for http, websocket and feed tasks.
+    pub fn set_fetcher(&self, fetcher: StaticFetcher) {
+        self.state.lock().unwrap().executor = JobExecutor::with_fetcher(fetcher);
+    }
+
+    /// Sets the slot hash the RPC reports as the most recent one.
+    pub fn set_slothash(&self, slot: u64, hash: [u8; 32]) {
+        self.state.lock().unwrap().slothash = SlotHash { slot, hash };
+    }
+
+    /// Sets the fees `getRecentPrioritizationFees` reports, one per slot.
+    pub fn set_prioritization_fees(&self, fees: &[u64]) {
+        self.state.lock().unwrap().prioritization_fees = fees.to_vec();
+    }
+
+    /// Sets the outcome of every `simulateTransaction` call.
+    pub fn set_simulation_result(&self, units_consumed: u64, error: Option<TransactionError>) {
+        let mut state = self.state.lock().unwrap();
+        state.units_consumed = units_consumed;
+        state.simulation_error = error;
+    }
+
+    /// Makes the next `count` `sendTransaction` calls fail.
+    pub fn set_send_failures(&self, count: usize) {
+        self.state.lock().unwrap().send_failures = count;
+    }
+
+    /// The transactions sent with `sendTransaction`, in order.
+    pub fn sent_transactions(&self) -> Vec<VersionedTransaction> {
+        self.state.lock().unwrap().sent_transactions.clone()
+    }
+
+    /// Makes every gateway and crossbar route respond with `status` and an
+    /// empty body, until reset with `None`. RPC methods are not affected.
+    pub fn force_status(&self, status: Option<u16>) {
+        self.state.lock().unwrap().forced_status = status;
+    }
+
+    /// Sets the data of `key`, including the 8 byte discriminator.
+    pub fn set_account(&self, key: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        self.state
+            .lock()
+            .unwrap()
+            .accounts
+            .insert(key, (owner, data));
+    }
+
+    /// Stores `jobs` for the crossbar and returns their feed hash.
+    pub fn store_jobs(&self, jobs: Vec<OracleJob>) -> [u8; 32] {
+        let queue = self.state.lock().unwrap().queue;
+        self.store_jobs_on_queue(&queue, jobs)
+    }
+
+    /// Stores `jobs` under their feed hash on `queue`, for feeds of another
+    /// queue whose jobs are served by this crossbar.
+    pub fn store_jobs_on_queue(&self, queue: &Pubkey, jobs: Vec<OracleJob>) -> [u8; 32] {
+        let feed_hash = compute_feed_hash(queue, &jobs);
+        self.state.lock().unwrap().jobs.insert(feed_hash, jobs);
+        feed_hash
+    }
+
+    /// Stores `jobs` and creates a pull feed account for them at `feed`,
+    /// with a max variance of 1 and a single required response.
+    pub fn add_pull_feed(&self, feed: Pubkey, jobs: Vec<OracleJob>) -> PullFeedAccountData {
+        let mut data: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        data.queue = self.state.lock().unwrap().queue;
+        data.feed_hash = self.store_jobs(jobs);
+        data.max_variance = 1_000_000_000;
+        data.min_responses = 1;
+        data.min_sample_size = 1;
+        self.set_pull_feed(feed, &data);
+        data
+    }
+
+    pub fn set_oracle(&self, oracle: Pubkey, data: &OracleAccountData) {
+        let mut account = vec![0; 8];
+        account.extend_from_slice(bytemuck::bytes_of(data));
+        self.set_account(oracle, *SWITCHBOARD_ON_DEMAND_PROGRAM_ID, account);
+    }
+
+    pub fn set_pull_feed(&self, feed: Pubkey, data: &PullFeedAccountData) {
+        let mut account = vec![0; 8];
+        account.extend_from_slice(bytemuck::bytes_of(data));
+        self.set_account(feed, *SWITCHBOARD_ON_DEMAND_PROGRAM_ID, account);
+    }
+
+    pub fn set_queue(&self, queue: Pubkey, data: &QueueAccountData) {
+        let mut account = vec![0; 8];
+        account.extend_from_slice(bytemuck::bytes_of(data));
+        self.set_account(queue, *SWITCHBOARD_ON_DEMAND_PROGRAM_ID, account);
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<MockState>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, &state).await;
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: &Mutex<MockState>) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = &buf[header_end..buf.len().min(header_end + content_length)];
+
+    let (status, body) = route(&mut state.lock().unwrap(), &method, &path, body);
+    let response = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn route(state: &mut MockState, method: &str, path: &str, body: &[u8]) -> (u16, String) {
+    let path = path.split('?').next().unwrap_or_default();
+    if method == "POST" && path == "/" {
+        return match serde_json::from_slice(body) {
+            Ok(request) => (200, state.rpc(request).to_string()),
+            Err(e) => (400, e.to_string()),
+        };
+    }
+    if let Some(status) = state.forced_status {
+        return (status, String::new());
+    }
+
+    let body = if method == "POST" {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(body) => body,
+            Err(e) => return (400, e.to_string()),
+        }
+    } else {
+        Value::Null
+    };
+    let result = match (method, path) {
+        ("GET", "/gateway/api/v1/test") => Ok(json!("ok")),
+        ("POST", "/gateway/api/v1/fetch_signatures") => state.fetch_signatures(&body),
+        ("POST", "/gateway/api/v1/fetch_signatures_multi") => state.fetch_signatures_multi(&body),
+        ("POST", "/gateway/api/v1/fetch_signatures_batch") => state.fetch_signatures_batch(&body),
+        ("POST", "/gateway/api/v1/fetch_signatures_consensus") => {
+            state.fetch_signatures_consensus(&body)
+        }
+        ("POST", "/gateway/api/v1/ping") => state.ping(),
+        ("POST", "/gateway/api/v1/randomness_reveal") => state.randomness_reveal(&body),
+        ("POST", "/gateway/api/v1/gateway_fetch_quote") => state.fetch_quote(&body),
+        ("POST", "/gateway/api/v1/gateway_attest_enclave") => state.attest_enclave(&body),
+        ("POST", "/gateway/api/v1/gateway_bridge_enclave") => state.bridge_enclave(&body),
+        ("POST", "/store") => state.store(&body),
+        ("GET", _) if path.starts_with("/fetch/") => state.fetch(&path["/fetch/".len()..]),
+        ("GET", _) if path.starts_with("/simulate/solana/") => {
+            let feeds = path.rsplit('/').next().unwrap_or_default();
+            state.simulate_solana(feeds)
+        }
+        ("GET", _) if path.starts_with("/simulate/") => state.simulate(&path["/simulate/".len()..]),
+        _ => Err((404, format!("No route for {} {}", method, path))),
+    };
+    match result {
+        Ok(value) => (200, value.to_string()),
+        Err((status, message)) => (status, json!({ "error": message }).to_string()),
+    }
+}
+
+type RouteResult = Result<Value, (u16, String)>;
+
+fn bad_request(e: impl ToString) -> (u16, String) {
+    (400, e.to_string())
+}
+
+/// A single feed request as sent in the gateway request bodies.
+struct FeedRequest {
+    jobs_b64_encoded: Vec<String>,
+    max_variance: u64,
+    min_responses: u32,
+}
+
+impl FeedRequest {
+    fn parse(value: &Value) -> Result<Self, (u16, String)> {
+        Ok(Self {
+            jobs_b64_encoded: serde_json::from_value(value["jobs_b64_encoded"].clone())
+                .map_err(bad_request)?,
+            max_variance: value["max_variance"].as_u64().unwrap_or(1_000_000_000),
+            min_responses: value["min_responses"].as_u64().unwrap_or(1) as u32,
+        })
+    }
+}
+
+/// The outcome of evaluating one feed request.
+struct FeedResult {
+    feed_hash: [u8; 32],
+    max_variance: u64,
+    min_responses: u32,
+    value: Result<i128, String>,
+}
+
+impl FeedResult {
+    fn checksum(&self, queue: &Pubkey, slothash: &[u8; 32]) -> [u8; 32] {
+        PullFeedAccountData::generate_checksum_inner(
+            *queue,
+            self.feed_hash,
+            self.value.clone().unwrap_or(i128::MAX),
+            *slothash,
+            self.max_variance,
+            self.min_responses,
+        )
+    }
+}
+
+impl MockState {
+    fn num_oracles(&self, body: &Value) -> usize {
+        let requested = body["num_oracles"].as_u64().unwrap_or(1) as usize;
+        requested.min(self.oracles.len())
+    }
+
+    fn recent_hash(body: &Value, field: &str) -> Result<[u8; 32], (u16, String)> {
+        let encoded = body[field].as_str().unwrap_or_default();
+        bs58::decode(encoded)
+            .into_vec()
+            .ok()
+            .and_then(|hash| hash.try_into().ok())
+            .ok_or_else(|| bad_request(format!("Invalid {}", field)))
+    }
+
+    /// Decodes the jobs of `request`, hashes them as the feed hash of the
+    /// queue and runs them, taking the median of the job results.
+    fn evaluate(&self, request: &FeedRequest) -> Result<FeedResult, (u16, String)> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.queue.to_bytes());
+        let mut jobs = Vec::new();
+        for encoded in &request.jobs_b64_encoded {
+            let bytes = BASE64_STANDARD.decode(encoded).map_err(bad_request)?;
+            hasher.update(&bytes);
+            jobs.push(OracleJob::decode_length_delimited(bytes.as_slice()).map_err(bad_request)?);
+        }
+        Ok(FeedResult {
+            feed_hash: hasher.finalize().into(),
+            max_variance: request.max_variance,
+            min_responses: request.min_responses,
+            value: self.run_jobs(&jobs),
+        })
+    }
+
+    fn run_jobs(&self, jobs: &[OracleJob]) -> Result<i128, String> {
+        let mut values = jobs
+            .iter()
+            .map(|job| self.executor.run(job))
+            .collect::<Result<Vec<Decimal>, _>>()
+            .map_err(|e| e.to_string())?;
+        if values.is_empty() {
+            return Err("Feed has no jobs".to_string());
+        }
+        values.sort();
+        let mid = values.len() / 2;
+        let mut median = if values.len() % 2 == 0 {
+            (values[mid - 1] + values[mid]) / Decimal::TWO
+        } else {
+            values[mid]
+        };
+        median.rescale(18);
+        Ok(median.mantissa())
+    }
+
+    fn feed_response(
+        &self,
+        oracle: &MockOracle,
+        result: &FeedResult,
+        recent_hash: &str,
+        slothash: &[u8; 32],
+    ) -> FeedEvalResponse {
+        let checksum = result.checksum(&self.queue, slothash);
+        let (signature, recovery_id) = oracle.sign_hash(&checksum);
+        FeedEvalResponse {
+            oracle_pubkey: hex::encode(oracle.pubkey),
+            queue_pubkey: hex::encode(self.queue),
+            oracle_signing_pubkey: hex::encode(oracle.eth_address()),
+            feed_hash: hex::encode(result.feed_hash),
+            recent_hash: recent_hash.to_string(),
+            failure_error: result.value.clone().err().unwrap_or_default(),
+            success_value: result
+                .value
+                .clone()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            msg: String::new(),
+            signature: BASE64_STANDARD.encode(signature),
+            recovery_id: recovery_id as i32,
+            recent_successes_if_failed: vec![],
+            timestamp: None,
+        }
+    }
+
+    fn fetch_signatures(&self, body: &Value) -> RouteResult {
+        let request = FeedRequest::parse(body)?;
+        let result = self.evaluate(&request)?;
+        let slothash = Self::recent_hash(body, "recent_chainhash")?;
+        let recent_hash = body["recent_chainhash"].as_str().unwrap_or_default();
+        let responses = self.oracles[..self.num_oracles(body)]
+            .iter()
+            .map(|oracle| self.feed_response(oracle, &result, recent_hash, &slothash))
+            .collect();
+        let response = FeedEvalResponseSingle {
+            responses,
+            caller: String::new(),
+            failures: vec![],
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn evaluate_all(&self, body: &Value) -> Result<Vec<FeedResult>, (u16, String)> {
+        body["feed_requests"]
+            .as_array()
+            .ok_or_else(|| bad_request("Missing feed_requests"))?
+            .iter()
+            .map(|request| self.evaluate(&FeedRequest::parse(request)?))
+            .collect()
+    }
+
+    fn fetch_signatures_multi(&self, body: &Value) -> RouteResult {
+        let results = self.evaluate_all(body)?;
+        let slothash = Self::recent_hash(body, "recent_hash")?;
+        let recent_hash = body["recent_hash"].as_str().unwrap_or_default();
+        let oracle_responses = self.oracles[..self.num_oracles(body)]
+            .iter()
+            .map(|oracle| {
+                let mut hasher = Sha256::new();
+                for result in &results {
+                    hasher.update(result.checksum(&self.queue, &slothash));
+                }
+                let (signature, recovery_id) = oracle.sign_hash(&hasher.finalize().into());
+                FeedEvalManyResponse {
+                    feed_responses: results
+                        .iter()
+                        .map(|result| self.feed_response(oracle, result, recent_hash, &slothash))
+                        .collect(),
+                    signature: BASE64_STANDARD.encode(signature),
+                    recovery_id: recovery_id as i32,
+                    errors: results
+                        .iter()
+                        .map(|result| result.value.clone().err())
+                        .collect(),
+                }
+            })
+            .collect();
+        let response = FetchSignaturesMultiResponse {
+            oracle_responses,
+            errors: vec![],
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn fetch_signatures_batch(&self, body: &Value) -> RouteResult {
+        let results = self.evaluate_all(body)?;
+        let slothash = Self::recent_hash(body, "recent_hash")?;
+        let recent_hash = body["recent_hash"].as_str().unwrap_or_default();
+        let oracle_responses = self.oracles[..self.num_oracles(body)]
+            .iter()
+            .map(|oracle| FeedEvalBatchResponse {
+                feed_responses: results
+                    .iter()
+                    .map(|result| self.feed_response(oracle, result, recent_hash, &slothash))
+                    .collect(),
+                errors: results
+                    .iter()
+                    .map(|result| result.value.clone().err())
+                    .collect(),
+            })
+            .collect();
+        let response = FetchSignaturesBatchResponse {
+            oracle_responses,
+            errors: vec![],
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn fetch_signatures_consensus(&self, body: &Value) -> RouteResult {
+        let results = self.evaluate_all(body)?;
+        let slothash = Self::recent_hash(body, "recent_hash")?;
+        let recent_hash = body["recent_hash"].as_str().unwrap_or_default();
+        let feeds: Vec<PullFeedAccountData> = results
+            .iter()
+            .map(|result| {
+                let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+                feed.feed_hash = result.feed_hash;
+                feed.max_variance = result.max_variance;
+                feed.min_responses = result.min_responses;
+                feed
+            })
+            .collect();
+        let values: Vec<i128> = results
+            .iter()
+            .map(|result| result.value.clone().unwrap_or(i128::MAX))
+            .collect();
+        let checksum =
+            generate_combined_checksum(&self.queue.to_bytes(), &feeds, &slothash, &values);
+        let oracle_responses = self.oracles[..self.num_oracles(body)]
+            .iter()
+            .map(|oracle| {
+                let (signature, recovery_id) = oracle.sign_message(&checksum);
+                ConsensusOracleResponse {
+                    oracle_pubkey: hex::encode(oracle.pubkey),
+                    eth_address: hex::encode(oracle.eth_address()),
+                    signature: BASE64_STANDARD.encode(signature),
+                    checksum: BASE64_STANDARD.encode(checksum),
+                    recovery_id: recovery_id as i32,
+                    feed_responses: results
+                        .iter()
+                        .map(|result| self.feed_response(oracle, result, recent_hash, &slothash))
+                        .collect(),
+                    errors: results
+                        .iter()
+                        .map(|result| result.value.clone().err())
+                        .collect(),
+                }
+            })
+            .collect();
+        let median_responses = results
+            .iter()
+            .zip(values.iter())
+            .map(|(result, value)| MedianResponse {
+                value: value.to_string(),
+                feed_hash: hex::encode(result.feed_hash),
+            })
+            .collect();
+        let response = FetchSignaturesConsensusResponse {
+            median_responses,
+            oracle_responses,
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn gateway_oracle(&self) -> Result<&MockOracle, (u16, String)> {
+        self.oracles
+            .first()
+            .ok_or_else(|| (503, "No oracles".to_string()))
+    }
+
+    fn guardian(&self) -> Result<&MockOracle, (u16, String)> {
+        self.oracles
+            .last()
+            .ok_or_else(|| (503, "No oracles".to_string()))
+    }
+
+    fn ping(&self) -> RouteResult {
+        let oracle = self.gateway_oracle()?;
+        let response = PingResponse {
+            oracle_pubkey: oracle.pubkey.to_string(),
+            oracle_authority: oracle.pubkey.to_string(),
+            queue: self.queue.to_string(),
+            rate_limit: 100,
+            version: "mock".to_string(),
+            mr_enclave: hex::encode([0u8; 32]),
+            is_push_oracle: false,
+            is_pull_oracle: true,
+            is_gateway: true,
+            is_guardian: self.oracles.len() == 1,
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    /// Reveals the sha256 hash of the randomness key and its seed, signed by
+    /// the gateway oracle. Cross-chain requests are seeded with their
+    /// timestamp, solana requests with their slot and slothash.
+    fn randomness_reveal(&self, body: &Value) -> RouteResult {
+        let oracle = self.gateway_oracle()?;
+        let randomness_key = body["randomness_key"]
+            .as_str()
+            .ok_or_else(|| bad_request("Missing randomness_key"))?;
+        let mut hasher = Sha256::new();
+        hasher.update(randomness_key.as_bytes());
+        if let Some(timestamp) = body["timestamp"].as_i64() {
+            hasher.update(timestamp.to_le_bytes());
+        } else {
+            let slothash: Vec<u8> =
+                serde_json::from_value(body["slothash"].clone()).map_err(bad_request)?;
+            hasher.update(slothash);
+            hasher.update(body["slot"].as_u64().unwrap_or_default().to_le_bytes());
+        }
+        let value: [u8; 32] = hasher.finalize().into();
+        let (signature, recovery_id) = oracle.sign_hash(&value);
+        let response = RandomnessRevealResponse {
+            signature: BASE64_STANDARD.encode(signature),
+            recovery_id: recovery_id as i32,
+            value: value.to_vec(),
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn quote(&self, oracle: &MockOracle, blockhash: &str) -> FetchQuoteResponse {
+        let quote: [u8; 32] = Sha256::digest(format!("{}{}", oracle.pubkey, blockhash)).into();
+        FetchQuoteResponse {
+            oracle_pubkey: oracle.pubkey.to_string(),
+            queue: self.queue.to_string(),
+            now: 0,
+            mr_enclave: hex::encode([0u8; 32]),
+            ed25519_pubkey: oracle.pubkey.to_string(),
+            secp256k1_pubkey: hex::encode(oracle.secp_pubkey().serialize()),
+            quote: BASE64_STANDARD.encode(quote),
+        }
+    }
+
+    fn fetch_quote(&self, body: &Value) -> RouteResult {
+        let blockhash = body["blockhash"].as_str().unwrap_or_default();
+        let mut quotes = Vec::new();
+        if body["get_for_oracle"].as_bool().unwrap_or_default() {
+            quotes.push(self.quote(self.gateway_oracle()?, blockhash));
+        }
+        if body["get_for_guardian"].as_bool().unwrap_or_default() {
+            quotes.push(self.quote(self.guardian()?, blockhash));
+        }
+        serde_json::to_value(quotes).map_err(|e| (500, e.to_string()))
+    }
+
+    /// Signs the keccak256 hash of the request's oracle, signers, quote,
+    /// timestamp and chain hash, in that order, with the guardian key.
+    fn attest_enclave(&self, body: &Value) -> RouteResult {
+        let guardian = self.guardian()?;
+        let message = [
+            "oracle_pubkey",
+            "oracle_reward_wallet",
+            "oracle_ed25519_enclave_signer",
+            "oracle_secp256k1_enclave_signer",
+            "quote",
+        ]
+        .iter()
+        .map(|field| body[*field].as_str().unwrap_or_default().to_string())
+        .chain([
+            body["timestamp"].as_i64().unwrap_or_default().to_string(),
+            body["chain_hash"].as_str().unwrap_or_default().to_string(),
+        ])
+        .collect::<String>();
+        let (signature, recovery_id) = guardian.sign_message(message.as_bytes());
+        let response = AttestEnclaveResponse {
+            guardian: guardian.pubkey.to_string(),
+            signature: BASE64_STANDARD.encode(signature),
+            recovery_id: recovery_id as i32,
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    /// Signs the keccak256 hash of `msg`, the requested chain hash, oracle and
+    /// queue, with the guardian key.
+    fn bridge_enclave(&self, body: &Value) -> RouteResult {
+        let guardian = self.guardian()?;
+        let oracle = body["oracle_pubkey"].as_str().unwrap_or_default();
+        let queue = body["queue_pubkey"].as_str().unwrap_or_default();
+        let chain_hash = body["chain_hash"].as_str().unwrap_or_default();
+        let msg = format!("{}{}{}", chain_hash, oracle, queue);
+        let msg_prehash: [u8; 32] = Keccak256::digest(msg.as_bytes()).into();
+        let (signature, recovery_id) = guardian.sign_hash(&msg_prehash);
+        let response = BridgeEnclaveResponse {
+            guardian: guardian.pubkey.to_string(),
+            oracle: oracle.to_string(),
+            queue: queue.to_string(),
+            mr_enclave: hex::encode([0u8; 32]),
+            chain_hash: chain_hash.to_string(),
+            oracle_ed25519_enclave_signer: oracle.to_string(),
+            oracle_secp256k1_enclave_signer: String::new(),
+            msg: hex::encode(msg.as_bytes()),
+            msg_prehash: hex::encode(msg_prehash),
+            signature: BASE64_STANDARD.encode(signature),
+            recovery_id: recovery_id as i32,
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn fetch(&self, feed_hash: &str) -> RouteResult {
+        let feed_hash: [u8; 32] = hex::decode(feed_hash.trim_start_matches("0x"))
+            .ok()
+            .and_then(|hash| hash.try_into().ok())
+            .ok_or_else(|| bad_request("Invalid feed hash"))?;
+        let jobs = self
+            .jobs
+            .get(&feed_hash)
+            .ok_or_else(|| (404, "Feed hash not found".to_string()))?;
+        Ok(json!({ "jobs": jobs }))
+    }
+
+    fn store(&mut self, body: &Value) -> RouteResult {
+        let queue: [u8; 32] = bs58::decode(body["queue"].as_str().unwrap_or_default())
+            .into_vec()
+            .ok()
+            .and_then(|queue| queue.try_into().ok())
+            .ok_or_else(|| bad_request("Invalid queue"))?;
+        let jobs: Vec<OracleJob> =
+            serde_json::from_value(body["jobs"].clone()).map_err(bad_request)?;
+        let feed_hash = compute_feed_hash(&Pubkey::new_from_array(queue), &jobs);
+        self.jobs.insert(feed_hash, jobs);
+        let response = StoreResponse {
+            cid: format!("mock-{}", hex::encode(&feed_hash[..8])),
+            feedHash: hex::encode(feed_hash),
+            queueHex: hex::encode(queue),
+        };
+        serde_json::to_value(response).map_err(|e| (500, e.to_string()))
+    }
+
+    fn simulate_hash(&self, feed_hash: &[u8; 32]) -> Result<Decimal, (u16, String)> {
+        let jobs = self.jobs.get(feed_hash).ok_or_else(|| {
+            (
+                404,
+                format!("Feed hash {} not found", hex::encode(feed_hash)),
+            )
+        })?;
+        let value = self.run_jobs(jobs).map_err(|e| (500, e))?;
+        Ok(Decimal::from_i128_with_scale(value, 18).normalize())
+    }
+
+    fn simulate(&self, feed_hashes: &str) -> RouteResult {
+        let mut responses = Vec::new();
+        for feed_hash in feed_hashes.split(',') {
+            let bytes: [u8; 32] = hex::decode(feed_hash.trim_start_matches("0x"))
+                .ok()
+                .and_then(|hash| hash.try_into().ok())
+                .ok_or_else(|| bad_request("Invalid feed hash"))?;
+            responses.push(SimulateFeedsResponse {
+                feedHash: feed_hash.to_string(),
+                results: vec![self.simulate_hash(&bytes)?],
+            });
+        }
+        serde_json::to_value(responses).map_err(|e| (500, e.to_string()))
+    }
+
+    fn simulate_solana(&self, feeds: &str) -> RouteResult {
+        let mut responses = Vec::new();
+        for feed in feeds.split(',') {
+            let key: Pubkey = feed.parse().map_err(bad_request)?;
+            let data = self
+                .accounts
+                .get(&key)
+                .and_then(|(_, data)| data.get(8..))
+                .and_then(|data| bytemuck::try_from_bytes::<PullFeedAccountData>(data).ok())
+                .ok_or_else(|| (404, format!("Feed {} not found", feed)))?;
+            responses.push(SimulateSolanaFeedsResponse {
+                feed: feed.to_string(),
+                feedHash: hex::encode(data.feed_hash),
+                results: vec![self.simulate_hash(&data.feed_hash).ok()],
+            });
+        }
+        serde_json::to_value(responses).map_err(|e| (500, e.to_string()))
+    }
+
+    fn slot_hashes_data(&self) -> Vec<u8> {
+        let mut data = vec![0; 8 + 20_480];
+        data[..8].copy_from_slice(&1u64.to_le_bytes());
+        data[8..48].copy_from_slice(bytemuck::bytes_of(&self.slothash));
+        data
+    }
+
+    fn ui_account(&self, key: &Pubkey) -> Value {
+        let (owner, data) = if *key == solana_sdk::sysvar::slot_hashes::ID {
+            (solana_sdk::sysvar::ID, self.slot_hashes_data())
+        } else {
+            match self.accounts.get(key) {
+                Some((owner, data)) => (*owner, data.clone()),
+                None => return Value::Null,
+            }
+        };
+        json!({
+            "lamports": 1_000_000_000u64,
+            "data": [BASE64_STANDARD.encode(&data), "base64"],
+            "owner": owner.to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len(),
+        })
+    }
+
+    fn send_transaction(&mut self, params: &Value) -> Result<Value, String> {
+        if self.send_failures > 0 {
+            self.send_failures -= 1;
+            return Err("Transaction dropped".to_string());
+        }
+        let tx = params[0]
+            .as_str()
+            .and_then(|tx| BASE64_STANDARD.decode(tx).ok())
+            .and_then(|tx| bincode::deserialize::<VersionedTransaction>(&tx).ok())
+            .ok_or_else(|| "Failed to decode transaction".to_string())?;
+        let signature = tx.signatures[0].to_string();
+        self.sent_transactions.push(tx);
+        Ok(json!(signature))
+    }
+
+    fn rpc(&mut self, request: Value) -> Value {
+        let params = &request["params"];
+        let context = json!({ "slot": self.slothash.slot });
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "getVersion" => json!({ "solana-core": "1.18.0", "feature-set": 0 }),
+            "getSlot" => json!(self.slothash.slot),
+            "getAccountInfo" => {
+                let key = params[0].as_str().and_then(|key| key.parse().ok());
+                let value = key.map(|key| self.ui_account(&key)).unwrap_or(Value::Null);
+                json!({ "context": context, "value": value })
+            }
+            "getMultipleAccounts" => {
+                let value: Vec<Value> = params[0]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|key| match key.as_str().and_then(|key| key.parse().ok()) {
+                        Some(key) => self.ui_account(&key),
+                        None => Value::Null,
+                    })
+                    .collect();
+                json!({ "context": context, "value": value })
+            }
+            "getLatestBlockhash" => {
+                let blockhash = bs58::encode(self.slothash.hash).into_string();
+                let value = json!({ "blockhash": blockhash, "lastValidBlockHeight": 1_000 });
+                json!({ "context": context, "value": value })
+            }
+            "getRecentPrioritizationFees" => {
+                let fees: Vec<Value> = self
+                    .prioritization_fees
+                    .iter()
+                    .enumerate()
+                    .map(|(slot, fee)| json!({ "slot": slot, "prioritizationFee": fee }))
+                    .collect();
+                json!(fees)
+            }
+            "sendTransaction" => match self.send_transaction(params) {
+                Ok(signature) => signature,
+                Err(message) => {
+                    return json!({
+                        "jsonrpc": "2.0",
+                        "error": { "code": -32005, "message": message },
+                        "id": request["id"],
+                    });
+                }
+            },
+            "getSignatureStatuses" => {
+                let status = json!({
+                    "slot": self.slothash.slot,
+                    "confirmations": null,
+                    "err": null,
+                    "status": { "Ok": null },
+                    "confirmationStatus": "finalized",
+                });
+                let count = params[0].as_array().map_or(0, Vec::len);
+                json!({ "context": context, "value": vec![status; count] })
+            }
+            "simulateTransaction" => {
+                let value = json!({
+                    "err": self.simulation_error,
+                    "logs": [],
+                    "accounts": null,
+                    "unitsConsumed": self.units_consumed,
+                    "returnData": null,
+                });
+                json!({ "context": context, "value": value })
+            }
+            method => {
+                let message = format!("Method not found: {}", method);
+                return json!({
+                    "jsonrpc": "2.0",
+                    "error": { "code": -32601, "message": message },
+                    "id": request["id"],
+                });
+            }
+        };
+        json!({ "jsonrpc": "2.0", "result": result, "id": request["id"] })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshDeserialize;
+    use solana_sdk::secp256k1_program;
+
+    async fn server() -> MockServer {
+        MockServer::start(Pubkey::new_unique(), MockOracle::generate(3)).await
+    }
+
+    fn jobs(values: &[f64]) -> Vec<OracleJob> {
+        values
+            .iter()
+            .map(|value| OracleJob::builder().value(*value).build())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_ix_end_to_end() {
+        let server = server().await;
+        server.set_slothash(42, [7; 32]);
+        let feed = Pubkey::new_unique();
+        let data = server.add_pull_feed(feed, jobs(&[101.0, 103.0, 102.0]));
+
+        let (ix, responses, num_successes, _luts) = PullFeed::fetch_update_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateParams {
+                feed,
+                payer: Pubkey::new_unique(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(num_successes, 3);
+        assert_eq!(ix.program_id, *SWITCHBOARD_ON_DEMAND_PROGRAM_ID);
+        let checksum = data.generate_checksum(102 * 10i128.pow(18), [7; 32]);
+        let oracles = MockOracle::generate(3);
+        for (response, oracle) in responses.iter().zip(oracles.iter()) {
+            assert_eq!(response.value, Some(Decimal::from(102)));
+            assert_eq!(response.oracle, oracle.pubkey);
+            let signer =
+                recover_secp256k1_signer(&checksum, &response.signature, response.recovery_id);
+            assert_eq!(signer, Some(oracle.secp_pubkey()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_ix_fails_over_to_pooled_gateway() {
+        let server = server().await;
+        let feed = Pubkey::new_unique();
+        server.add_pull_feed(feed, jobs(&[1.0]));
+        let unreachable = Gateway::new("http://127.0.0.1:1".to_string());
+        let pool = GatewayPool::new(vec![unreachable.clone(), server.gateway()]);
+        let pool = Arc::new(pool);
+
+        let (_ix, _responses, num_successes, _luts) = PullFeed::fetch_update_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateParams {
+                feed,
+                payer: Pubkey::new_unique(),
+                gateway: unreachable,
+                gateway_pool: Some(pool.clone()),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(num_successes, 3);
+        let stats = pool.stats();
+        assert_eq!((stats[0].1.failures, stats[0].1.successes), (1, 0));
+        assert_eq!((stats[1].1.failures, stats[1].1.successes), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_ix_drops_unverified_signatures() {
+        let server = server().await;
+        let feed = Pubkey::new_unique();
+        server.add_pull_feed(feed, jobs(&[1.0]));
+        // The first oracle's on-chain signer no longer matches its key.
+        let oracles = MockOracle::generate(4);
+        server.set_oracle(oracles[0].pubkey, &oracles[3].account_data());
+
+        let (_ix, responses, num_successes, _luts) = PullFeed::fetch_update_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateParams {
+                feed,
+                payer: Pubkey::new_unique(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(num_successes, 2);
+        let oracle_keys: Vec<Pubkey> = responses.iter().map(|x| x.oracle).collect();
+        assert_eq!(oracle_keys, vec![oracles[1].pubkey, oracles[2].pubkey]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_consensus_ix_end_to_end() {
+        let server = server().await;
+        let feeds = [Pubkey::new_unique(), Pubkey::new_unique()];
+        server.add_pull_feed(feeds[0], jobs(&[1.5]));
+        server.add_pull_feed(feeds[1], jobs(&[2.0, 3.0]));
+
+        let (ixs, _luts) = PullFeed::fetch_update_consensus_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateManyParams {
+                feeds: feeds.to_vec(),
+                payer: Pubkey::new_unique(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].program_id, secp256k1_program::ID);
+        assert_eq!(ixs[0].data[0], 3);
+        let feed_accounts: Vec<Pubkey> = ixs[1].accounts.iter().map(|meta| meta.pubkey).collect();
+        assert!(feeds.iter().all(|feed| feed_accounts.contains(feed)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_consensus_ix_groups_feeds_by_queue() {
+        let server = server().await;
+        let other_queue = Pubkey::new_unique();
+        let other_server = MockServer::start(other_queue, MockOracle::generate(3)).await;
+        let feeds = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let queue = server.add_pull_feed(feeds[0], jobs(&[1.0])).queue;
+        let mut other_feed = server.add_pull_feed(feeds[1], jobs(&[2.0]));
+        other_feed.queue = other_queue;
+        other_feed.feed_hash = server.store_jobs_on_queue(&other_queue, jobs(&[2.0]));
+        server.set_pull_feed(feeds[1], &other_feed);
+        server.add_pull_feed(feeds[2], jobs(&[3.0]));
+        let params = FetchUpdateManyParams {
+            feeds: feeds.to_vec(),
+            payer: Pubkey::new_unique(),
+            gateway: server.gateway(),
+            crossbar: Some(server.crossbar()),
+            num_signatures: Some(3),
+            queue_gateways: HashMap::from([(other_queue, other_server.gateway())]),
+            ..Default::default()
+        };
+
+        let (ixs, _luts) = PullFeed::fetch_update_consensus_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            params.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ixs.len(), 4);
+        // Each secp256k1 instruction references its own index.
+        assert_eq!(ixs[0].data[3], 0);
+        assert_eq!(ixs[2].data[3], 2);
+        let accounts = |ix: &solana_sdk::instruction::Instruction| -> Vec<Pubkey> {
+            ix.accounts.iter().map(|meta| meta.pubkey).collect()
+        };
+        assert_eq!(ixs[1].accounts[0].pubkey, queue);
+        assert!(accounts(&ixs[1]).contains(&feeds[0]));
+        assert!(accounts(&ixs[1]).contains(&feeds[2]));
+        assert!(!accounts(&ixs[1]).contains(&feeds[1]));
+        assert_eq!(ixs[3].accounts[0].pubkey, other_queue);
+        assert!(accounts(&ixs[3]).contains(&feeds[1]));
+
+        let err = PullFeed::fetch_update_consensus_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateManyParams {
+                single_queue: Some(true),
+                ..params
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ClientError::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_many_ix_end_to_end() {
+        let server = server().await;
+        let feeds = [Pubkey::new_unique(), Pubkey::new_unique()];
+        server.add_pull_feed(feeds[0], jobs(&[1.5]));
+        server.add_pull_feed(feeds[1], jobs(&[2.0, 3.0]));
+
+        let (ix, _luts, unverified) = PullFeed::fetch_update_many_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateManyParams {
+                feeds: feeds.to_vec(),
+                payer: Pubkey::new_unique(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(unverified.is_empty());
+        let params = PullFeedSubmitResponseManyParams::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(params.slot, 1);
+        assert_eq!(params.submissions.len(), 3);
+        for submission in &params.submissions {
+            assert_eq!(
+                submission.values,
+                vec![15 * 10i128.pow(17), 25 * 10i128.pow(17)]
+            );
+        }
+        // The feeds follow the 9 fixed accounts, then the oracles and their stats.
+        let remaining: Vec<Pubkey> = ix.accounts[9..].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(remaining[..2], feeds);
+        let oracles = MockOracle::generate(3);
+        assert_eq!(remaining[2], oracles[0].pubkey);
+        assert_eq!(
+            remaining[5],
+            OracleAccountData::stats_key(&oracles[0].pubkey)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_many_ix_reports_unverified_signatures() {
+        let server = server().await;
+        let feeds = [Pubkey::new_unique(), Pubkey::new_unique()];
+        server.add_pull_feed(feeds[0], jobs(&[1.5]));
+        server.add_pull_feed(feeds[1], jobs(&[2.0]));
+        // The first oracle's on-chain signer no longer matches its key.
+        let oracles = MockOracle::generate(4);
+        server.set_oracle(oracles[0].pubkey, &oracles[3].account_data());
+        let params = FetchUpdateManyParams {
+            feeds: feeds.to_vec(),
+            payer: Pubkey::new_unique(),
+            gateway: server.gateway(),
+            crossbar: Some(server.crossbar()),
+            num_signatures: Some(3),
+            debug: None,
+            ..Default::default()
+        };
+
+        let (ix, _luts, unverified) =
+            PullFeed::fetch_update_many_ix(SbContext::new(), &server.rpc_client(), params.clone())
+                .await
+                .unwrap();
+
+        // The unverified submission is still submitted.
+        let submitted = PullFeedSubmitResponseManyParams::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(submitted.submissions.len(), 3);
+        let remaining: Vec<Pubkey> = ix.accounts[9..].iter().map(|meta| meta.pubkey).collect();
+        let submitted_oracles: Vec<Pubkey> = oracles[..3].iter().map(|x| x.pubkey).collect();
+        assert_eq!(remaining[2..5], submitted_oracles);
+        let unverified: Vec<String> = unverified.into_iter().map(|x| x.oracle).collect();
+        assert_eq!(unverified, vec![hex::encode(oracles[0].pubkey)]);
+
+        for oracle in &oracles[1..3] {
+            server.set_oracle(oracle.pubkey, &oracles[3].account_data());
+        }
+        let (ix, _luts, unverified) =
+            PullFeed::fetch_update_many_ix(SbContext::new(), &server.rpc_client(), params)
+                .await
+                .unwrap();
+        let submitted = PullFeedSubmitResponseManyParams::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(submitted.submissions.len(), 3);
+        assert_eq!(unverified.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_batch_ix_end_to_end() {
+        let server = server().await;
+        let feeds = [Pubkey::new_unique(), Pubkey::new_unique()];
+        server.add_pull_feed(feeds[0], jobs(&[1.5]));
+        server.add_pull_feed(feeds[1], jobs(&[4.0]));
+
+        let (ixs, _luts, skipped) = PullFeed::fetch_update_batch_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateBatchParams {
+                feeds: feeds.to_vec(),
+                payer: Pubkey::new_unique(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(skipped.is_empty());
+        assert_eq!(ixs.len(), 2);
+        for (ix, (feed, value)) in ixs.iter().zip(feeds.iter().zip([15, 40])) {
+            assert_eq!(ix.accounts[0].pubkey, *feed);
+            let params = PullFeedSubmitResponseParams::try_from_slice(&ix.data[8..]).unwrap();
+            assert_eq!(params.submissions.len(), 3);
+            assert!(params
+                .submissions
+                .iter()
+                .all(|submission| submission.value == value * 10i128.pow(17)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_update_batch_ix_reports_skipped_feeds() {
+        let server = server().await;
+        let feeds = [Pubkey::new_unique(), Pubkey::new_unique()];
+        // A feed without jobs fails on every oracle.
+        server.add_pull_feed(feeds[0], vec![]);
+        server.add_pull_feed(feeds[1], jobs(&[4.0]));
+
+        let (ixs, _luts, skipped) = PullFeed::fetch_update_batch_ix(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateBatchParams {
+                feeds: feeds.to_vec(),
+                payer: Pubkey::new_unique(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ixs.len(), 1);
+        assert_eq!(ixs[0].accounts[0].pubkey, feeds[1]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].feed, feeds[0]);
+        let oracles: Vec<String> = MockOracle::generate(3)
+            .iter()
+            .map(|oracle| hex::encode(oracle.pubkey))
+            .collect();
+        let errors = &skipped[0].per_oracle_errors;
+        assert_eq!(
+            errors.iter().map(|x| x.oracle.clone()).collect::<Vec<_>>(),
+            oracles
+        );
+        assert!(errors.iter().all(|x| x.error == "Feed has no jobs"));
+    }
+
+    #[tokio::test]
+    async fn test_crossbar_store_fetch_and_simulate() {
+        let server = server().await;
+        let crossbar = server.crossbar();
+        let queue = Pubkey::new_unique();
+        let job_values: Vec<Value> = jobs(&[4.0, 6.0])
+            .iter()
+            .map(|job| serde_json::to_value(job).unwrap())
+            .collect();
+
+        let stored = crossbar.store(queue, &job_values).await.unwrap();
+        let feed_hash = compute_feed_hash(&queue, &jobs(&[4.0, 6.0]));
+        assert_eq!(stored.feedHash, hex::encode(feed_hash));
+
+        let fetched = crossbar.fetch(&stored.feedHash).await.unwrap();
+        assert_eq!(fetched["jobs"], Value::Array(job_values));
+
+        let simulated = crossbar
+            .simulate_feeds(&[stored.feedHash.as_str()])
+            .await
+            .unwrap();
+        assert_eq!(simulated[0].results, vec![Decimal::from(5)]);
+
+        let err = crossbar.fetch(&hex::encode([0u8; 32])).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ClientError::CrossbarHttp {
+                status: Some(404),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_failures() {
+        let server = server().await;
+        let gateway = server.gateway();
+        assert!(gateway.test_gateway().await);
+
+        server.force_status(Some(503));
+        let params = FetchSignaturesParams {
+            recent_hash: None,
+            encoded_jobs: encode_jobs(&jobs(&[1.0])),
+            num_signatures: 1,
+            max_variance: None,
+            min_responses: None,
+            use_timestamp: None,
+        };
+        let err = gateway
+            .fetch_signatures_from_encoded(params)
+            .await
+            .unwrap_err();
+        assert!(err.is_transient());
+    }
+
+    fn recover_base64(
+        hash: &[u8; 32],
+        signature: &str,
+        recovery_id: i32,
+    ) -> Option<libsecp256k1::PublicKey> {
+        let signature: [u8; 64] = BASE64_STANDARD.decode(signature).ok()?.try_into().ok()?;
+        recover_secp256k1_signer(hash, &signature, recovery_id as u8)
+    }
+
+    #[tokio::test]
+    async fn test_gateway_ping_and_quote() {
+        let server = server().await;
+        let gateway = server.gateway();
+        let oracles = MockOracle::generate(3);
+
+        let ping = gateway.ping().await.unwrap();
+        assert_eq!(ping.oracle_pubkey, oracles[0].pubkey.to_string());
+        assert!(ping.is_gateway && ping.is_pull_oracle);
+        assert!(!ping.is_guardian);
+
+        let quotes = gateway
+            .fetch_quote(FetchQuoteParams {
+                blockhash: "blockhash".to_string(),
+                get_for_oracle: true,
+                get_for_guardian: true,
+            })
+            .await
+            .unwrap();
+        let quoted: Vec<String> = quotes.into_iter().map(|x| x.oracle_pubkey).collect();
+        assert_eq!(
+            quoted,
+            vec![oracles[0].pubkey.to_string(), oracles[2].pubkey.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gateway_guardian_signatures() {
+        let server = server().await;
+        let gateway = server.gateway();
+        let guardian = &MockOracle::generate(3)[2];
+
+        let attestation = gateway
+            .attest_enclave(AttestEnclaveParams {
+                timestamp: 10,
+                quote: "quote".to_string(),
+                oracle_pubkey: "oracle".to_string(),
+                recent_hash: "hash".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(attestation.guardian, guardian.pubkey.to_string());
+        let hash: [u8; 32] = Keccak256::digest("oraclequote10hash").into();
+        assert_eq!(
+            recover_base64(&hash, &attestation.signature, attestation.recovery_id),
+            Some(guardian.secp_pubkey())
+        );
+
+        let bridge = gateway
+            .bridge_enclave(BridgeEnclaveParams {
+                chain_hash: "hash".to_string(),
+                oracle_pubkey: "oracle".to_string(),
+                queue_pubkey: "queue".to_string(),
+            })
+            .await
+            .unwrap();
+        let prehash: [u8; 32] = hex::decode(&bridge.msg_prehash)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            recover_base64(&prehash, &bridge.signature, bridge.recovery_id),
+            Some(guardian.secp_pubkey())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gateway_randomness_reveal() {
+        let server = server().await;
+        let gateway = server.gateway();
+        let oracle = &MockOracle::generate(1)[0];
+
+        let reveal = gateway
+            .fetch_randomness_reveal(FetchRandomnessRevealParams {
+                randomness_key: [4; 32],
+                slot: 7,
+                slothash: [5; 32],
+            })
+            .await
+            .unwrap();
+        let cross_chain = gateway
+            .fetch_cross_chain_randomness_reveal(FetchCrossChainRandomnessRevealParams {
+                randomness_id: hex::encode([4; 32]),
+                timestamp: 100,
+                min_staleness_seconds: 0,
+            })
+            .await
+            .unwrap();
+        assert_ne!(reveal.value, cross_chain.value);
+        for reveal in [reveal, cross_chain] {
+            assert!(verify_randomness_reveal(&oracle.secp_pubkey(), &reveal));
+        }
+    }
+}
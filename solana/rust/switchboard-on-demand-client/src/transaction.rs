@@ -0,0 +1,373 @@
+use crate::*;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+#[cfg(not(feature = "solana_sdk_1_16"))]
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+#[cfg(feature = "solana_sdk_1_16")]
+use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::keypair::Keypair;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+
+/// The most compute units a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// The most accounts `getRecentPrioritizationFees` accepts.
+const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 75;
+const DEFAULT_COMPUTE_UNIT_MARGIN: f64 = 1.2;
+
+#[derive(Clone, Debug, Default)]
+pub struct TransactionConfig {
+    /// The percentile of the recent prioritization fees of the written
+    /// accounts to pay, from 0 to 100. Defaults to 75.
+    pub priority_fee_percentile: Option<u8>,
+    /// Caps the compute unit price, in micro-lamports.
+    pub max_priority_fee: Option<u64>,
+    /// The factor applied to the simulated compute units. Defaults to 1.2.
+    pub compute_unit_margin: Option<f64>,
+    /// Skips the simulation and requests this many compute units instead.
+    pub compute_unit_limit: Option<u32>,
+    /// Lookup tables for the accounts of the caller's instructions.
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+/// Fetches the update instruction of `params.feed` and builds a signed v0
+/// transaction running it before `ixs`, using the cached lookup tables.
+///
+/// The compute unit limit is estimated by simulating the transaction and the
+/// compute unit price is set from the recent prioritization fees. `ixs` should
+/// not contain compute budget instructions.
+/// # Arguments
+/// * `context` - The context caching feed data and lookup tables.
+/// * `client` - The RPC client used for the feed, simulation and fees.
+/// * `params` - The parameters of the update instruction.
+/// * `ixs` - The instructions to run after the update, e.g. ones reading the feed.
+/// * `signers` - The signers of the transaction, including `params.payer`.
+/// * `config` - The priority fee and compute unit settings.
+/// # Returns
+/// The signed transaction, the oracle responses and the number of successful
+/// responses.
+pub async fn build_update_transaction(
+    context: Arc<SbContext>,
+    client: &RpcClient,
+    params: FetchUpdateParams,
+    ixs: Vec<Instruction>,
+    signers: &[&Keypair],
+    config: TransactionConfig,
+) -> Result<(VersionedTransaction, Vec<OracleResponse>, usize), ClientError> {
+    let payer = params.payer;
+    let (update_ix, responses, num_successes, mut luts) =
+        PullFeed::fetch_update_ix(context, client, params).await?;
+    luts.extend(config.lookup_tables.iter().cloned());
+
+    let mut all_ixs = vec![update_ix];
+    all_ixs.extend(ixs);
+    let tx = build_v0_transaction(client, &payer, &all_ixs, signers, &luts, &config).await?;
+    Ok((tx, responses, num_successes))
+}
+
+/// Builds a signed v0 transaction of `ixs`, followed by compute budget
+/// instructions for the simulated compute units and the recent
+/// prioritization fees. `ixs` keep their positions in the transaction, as
+/// secp256k1 instructions reference their own index.
+pub async fn build_v0_transaction(
+    client: &RpcClient,
+    payer: &Pubkey,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+    luts: &[AddressLookupTableAccount],
+    config: &TransactionConfig,
+) -> Result<VersionedTransaction, ClientError> {
+    let blockhash = client.get_latest_blockhash().await?;
+    let priority_fee = fetch_priority_fee(client, ixs, config).await?;
+    let compute_unit_limit = match config.compute_unit_limit {
+        Some(limit) => limit,
+        None => {
+            let units = simulate_compute_units(client, payer, ixs, luts, blockhash).await?;
+            compute_unit_limit(
+                units,
+                config
+                    .compute_unit_margin
+                    .unwrap_or(DEFAULT_COMPUTE_UNIT_MARGIN),
+            )
+        }
+    };
+
+    let message = compile_v0_message(
+        payer,
+        ixs,
+        luts,
+        blockhash,
+        compute_unit_limit,
+        priority_fee,
+    )?;
+    VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+        .map_err(|e| ClientError::TransactionBuild(e.to_string()))
+}
+
+/// The compute unit limit to request for a transaction that consumed `units`
+/// in simulation.
+pub fn compute_unit_limit(units: u64, margin: f64) -> u32 {
+    let limit = (units as f64 * margin).ceil();
+    limit.min(MAX_COMPUTE_UNIT_LIMIT as f64) as u32
+}
+
+/// The `percentile` of `fees` by the nearest-rank method, or 0 if there are no
+/// fees.
+pub fn priority_fee_percentile(fees: &[u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    let mut fees = fees.to_vec();
+    fees.sort_unstable();
+    let rank = (percentile.min(100) as usize * fees.len() + 99) / 100;
+    fees[rank.saturating_sub(1)]
+}
+
+async fn fetch_priority_fee(
+    client: &RpcClient,
+    ixs: &[Instruction],
+    config: &TransactionConfig,
+) -> Result<u64, ClientError> {
+    let mut writable: Vec<Pubkey> = Vec::new();
+    for meta in ixs.iter().flat_map(|ix| ix.accounts.iter()) {
+        if meta.is_writable && !writable.contains(&meta.pubkey) {
+            writable.push(meta.pubkey);
+        }
+    }
+    writable.truncate(MAX_PRIORITIZATION_FEE_ACCOUNTS);
+
+    let fees: Vec<u64> = client
+        .get_recent_prioritization_fees(&writable)
+        .await?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    let fee = priority_fee_percentile(
+        &fees,
+        config
+            .priority_fee_percentile
+            .unwrap_or(DEFAULT_PRIORITY_FEE_PERCENTILE),
+    );
+    Ok(config.max_priority_fee.map_or(fee, |max| fee.min(max)))
+}
+
+async fn simulate_compute_units(
+    client: &RpcClient,
+    payer: &Pubkey,
+    ixs: &[Instruction],
+    luts: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<u64, ClientError> {
+    // Simulate with the same instruction layout as the final transaction so
+    // the compute budget instructions are accounted for.
+    let message = compile_v0_message(payer, ixs, luts, blockhash, MAX_COMPUTE_UNIT_LIMIT, 0)?;
+    let num_signatures = message.header.num_required_signatures as usize;
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::default(); num_signatures],
+        message: VersionedMessage::V0(message),
+    };
+    let result = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(client.commitment()),
+                ..Default::default()
+            },
+        )
+        .await?
+        .value;
+    if let Some(err) = result.err {
+        return Err(ClientError::SimulationFailed {
+            error: err.to_string(),
+            transaction_error: Some(err),
+            logs: result.logs.unwrap_or_default(),
+        });
+    }
+    result
+        .units_consumed
+        .ok_or_else(|| ClientError::SimulationFailed {
+            error: "Simulation did not report the consumed compute units".to_string(),
+            transaction_error: None,
+            logs: result.logs.unwrap_or_default(),
+        })
+}
+
+fn compile_v0_message(
+    payer: &Pubkey,
+    ixs: &[Instruction],
+    luts: &[AddressLookupTableAccount],
+    blockhash: Hash,
+    compute_unit_limit: u32,
+    priority_fee: u64,
+) -> Result<v0::Message, ClientError> {
+    let mut final_ixs = ixs.to_vec();
+    final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        compute_unit_limit,
+    ));
+    final_ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+        priority_fee,
+    ));
+    v0::Message::try_compile(payer, &final_ixs, luts, blockhash)
+        .map_err(|e| ClientError::TransactionBuild(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle_job::OracleJob;
+    use crate::testing::{MockOracle, MockServer};
+    use solana_sdk::instruction::AccountMeta;
+    use solana_sdk::secp256k1_instruction::new_secp256k1_instruction;
+    use solana_sdk::secp256k1_program;
+    use solana_sdk::signature::Signer;
+    use solana_sdk::transaction::TransactionError;
+
+    #[test]
+    fn test_priority_fee_percentile() {
+        assert_eq!(priority_fee_percentile(&[], 75), 0);
+        let fees = [50, 0, 10, 40, 20, 30, 0, 0];
+        assert_eq!(priority_fee_percentile(&fees, 0), 0);
+        assert_eq!(priority_fee_percentile(&fees, 50), 10);
+        assert_eq!(priority_fee_percentile(&fees, 75), 30);
+        assert_eq!(priority_fee_percentile(&fees, 100), 50);
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        assert_eq!(compute_unit_limit(100_000, 1.2), 120_000);
+        assert_eq!(compute_unit_limit(1_300_000, 1.2), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    fn compute_budget(tx: &VersionedTransaction) -> Vec<Vec<u8>> {
+        let keys = tx.message.static_account_keys();
+        tx.message
+            .instructions()
+            .iter()
+            .filter(|ix| keys[ix.program_id_index as usize] == solana_sdk::compute_budget::ID)
+            .map(|ix| ix.data.clone())
+            .collect()
+    }
+
+    async fn server_with_feed(feed: Pubkey) -> MockServer {
+        let server = MockServer::start(Pubkey::new_unique(), MockOracle::generate(3)).await;
+        server.add_pull_feed(feed, vec![OracleJob::builder().value(42.0).build()]);
+        server
+    }
+
+    #[tokio::test]
+    async fn test_build_update_transaction() {
+        let feed = Pubkey::new_unique();
+        let server = server_with_feed(feed).await;
+        server.set_prioritization_fees(&[0, 100, 200, 300]);
+        server.set_simulation_result(50_000, None);
+        let payer = Keypair::new();
+        let user_ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![AccountMeta::new_readonly(feed, false)],
+        );
+
+        let (tx, _responses, num_successes) = build_update_transaction(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateParams {
+                feed,
+                payer: payer.pubkey(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+            vec![user_ix.clone()],
+            &[&payer],
+            TransactionConfig {
+                max_priority_fee: Some(150),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(num_successes, 3);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+        assert_eq!(tx.message.static_account_keys()[0], payer.pubkey());
+        assert_eq!(
+            compute_budget(&tx),
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(60_000).data,
+                ComputeBudgetInstruction::set_compute_unit_price(150).data,
+            ]
+        );
+        // The update runs first, then the caller's instruction.
+        let instructions = tx.message.instructions();
+        assert_eq!(instructions[1].data, user_ix.data);
+        assert_eq!(instructions.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_build_v0_transaction_keeps_secp256k1_instruction_index() {
+        let server = MockServer::start(Pubkey::new_unique(), MockOracle::generate(1)).await;
+        let payer = Keypair::new();
+        let secret = libsecp256k1::SecretKey::parse(&[7u8; 32]).unwrap();
+        // The offsets of the instruction reference itself at index 0.
+        let secp_ix = new_secp256k1_instruction(&secret, b"update");
+
+        let tx = build_v0_transaction(
+            &server.rpc_client(),
+            &payer.pubkey(),
+            &[secp_ix],
+            &[&payer],
+            &[],
+            &TransactionConfig {
+                compute_unit_limit: Some(100_000),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let keys = tx.message.static_account_keys();
+        let ix = &tx.message.instructions()[0];
+        assert_eq!(keys[ix.program_id_index as usize], secp256k1_program::ID);
+        // The signature, eth address and message instruction indices.
+        assert_eq!([ix.data[3], ix.data[6], ix.data[11]], [0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_build_update_transaction_simulation_failure() {
+        let feed = Pubkey::new_unique();
+        let server = server_with_feed(feed).await;
+        server.set_simulation_result(0, Some(TransactionError::AccountNotFound));
+        let payer = Keypair::new();
+
+        let err = build_update_transaction(
+            SbContext::new(),
+            &server.rpc_client(),
+            FetchUpdateParams {
+                feed,
+                payer: payer.pubkey(),
+                gateway: server.gateway(),
+                crossbar: Some(server.crossbar()),
+                num_signatures: Some(3),
+                debug: None,
+                ..Default::default()
+            },
+            vec![],
+            &[&payer],
+            TransactionConfig::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ClientError::SimulationFailed { .. }));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::secp256k1::SecpSignature;
+use crate::*;
+use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use libsecp256k1::PublicKey as SecpPublicKey;
+use sha3::{Digest, Keccak256};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// Recovers the secp256k1 key that signed the 32 byte `hash`.
+pub fn recover_secp256k1_signer(
+    hash: &[u8; 32],
+    signature: &[u8; 64],
+    recovery_id: u8,
+) -> Option<SecpPublicKey> {
+    let signature = libsecp256k1::Signature::parse_standard(signature).ok()?;
+    let recovery_id = libsecp256k1::RecoveryId::parse(recovery_id).ok()?;
+    libsecp256k1::recover(
+        &libsecp256k1::Message::parse(hash),
+        &signature,
+        &recovery_id,
+    )
+    .ok()
+}
+
+/// The ethereum address of `signer`, as checked by the secp256k1 program.
+pub fn eth_address(signer: &SecpPublicKey) -> [u8; 20] {
+    let hash = Keccak256::digest(&signer.serialize()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Whether `response` is a successful response signed by `signer` over the
+/// checksum of `feed` at `slothash`, as the on-demand program verifies it.
+pub fn verify_oracle_response(
+    feed: &PullFeedAccountData,
+    signer: &SecpPublicKey,
+    response: &OracleResponse,
+    slothash: &[u8; 32],
+) -> bool {
+    let mut value = match response.value {
+        Some(value) => value,
+        None => return false,
+    };
+    value.rescale(18);
+    let checksum = match response.timestamp {
+        Some(timestamp) => PullFeedAccountData::generate_checksum_with_timestamp(
+            feed.queue,
+            feed.feed_hash,
+            value.mantissa(),
+            *slothash,
+            feed.max_variance,
+            feed.min_responses,
+            timestamp as u64,
+        ),
+        None => feed.generate_checksum(value.mantissa(), *slothash),
+    };
+    recover_secp256k1_signer(&checksum, &response.signature, response.recovery_id).as_ref()
+        == Some(signer)
+}
+
+/// Whether `signature` is a secp256k1 program signature of `signer` over the
+/// consensus `checksum`, as computed by `generate_combined_checksum`.
+pub fn verify_consensus_signature(
+    signer: &SecpPublicKey,
+    checksum: &[u8; 32],
+    signature: &SecpSignature,
+) -> bool {
+    if signature.message != checksum || signature.eth_address != eth_address(signer) {
+        return false;
+    }
+    // The secp256k1 program verifies signatures over the keccak256 hash of
+    // the message.
+    let hash: [u8; 32] = Keccak256::digest(checksum).into();
+    recover_secp256k1_signer(&hash, &signature.signature, signature.recovery_id).as_ref()
+        == Some(signer)
+}
+
+/// Whether `submission` is signed by `signer` over the checksum of its values
+/// for `feeds` at `slothash`, as computed by `generate_multi_checksum`.
+pub fn verify_multi_submission(
+    feeds: &[PullFeedAccountData],
+    signer: &SecpPublicKey,
+    submission: &MultiSubmission,
+    slothash: &[u8; 32],
+) -> bool {
+    if submission.values.len() != feeds.len() {
+        return false;
+    }
+    let checksum = generate_multi_checksum(feeds, slothash, &submission.values);
+    recover_secp256k1_signer(&checksum, &submission.signature, submission.recovery_id).as_ref()
+        == Some(signer)
+}
+
+/// Whether the randomness reveal `response` is signed by `signer` over its
+/// 32 byte value, as the on-demand program verifies it.
+pub fn verify_randomness_reveal(
+    signer: &SecpPublicKey,
+    response: &RandomnessRevealResponse,
+) -> bool {
+    let Ok(value) = <[u8; 32]>::try_from(response.value.as_slice()) else {
+        return false;
+    };
+    let Some(signature) = base64
+        .decode(&response.signature)
+        .ok()
+        .and_then(|signature| <[u8; 64]>::try_from(signature).ok())
+    else {
+        return false;
+    };
+    let Ok(recovery_id) = u8::try_from(response.recovery_id) else {
+        return false;
+    };
+    recover_secp256k1_signer(&value, &signature, recovery_id).as_ref() == Some(signer)
+}
+
+/// Splits `responses` into the responses to submit and the successful
+/// responses whose signature does not verify against the oracle's signer in
+/// `signers`. Failed responses carry no signature and are kept.
+pub fn verify_oracle_responses(
+    feed: &PullFeedAccountData,
+    slothash: &[u8; 32],
+    signers: &HashMap<Pubkey, SecpPublicKey>,
+    responses: Vec<OracleResponse>,
+) -> (Vec<OracleResponse>, Vec<OracleError>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for response in responses {
+        let verified = response.value.is_none()
+            || signers.get(&response.oracle).map_or(false, |signer| {
+                verify_oracle_response(feed, signer, &response, slothash)
+            });
+        if verified {
+            valid.push(response);
+        } else {
+            invalid.push(OracleError {
+                oracle: hex::encode(response.oracle),
+                error: "Signature does not match the oracle's secp256k1 signer".to_string(),
+            });
+        }
+    }
+    (valid, invalid)
+}
+
+/// Loads the secp256k1 signers of `oracles`. Oracles whose account is missing
+/// or has no signer are left out.
+pub async fn load_oracle_signers(
+    client: &RpcClient,
+    oracles: &[Pubkey],
+) -> Result<HashMap<Pubkey, SecpPublicKey>, ClientError> {
+    let mut signers = HashMap::new();
+    if oracles.is_empty() {
+        return Ok(signers);
+    }
+    let accounts = client.get_multiple_accounts(oracles).await?;
+    for (oracle, account) in oracles.iter().zip(accounts) {
+        let signer = account
+            .as_ref()
+            .and_then(|account| account.data.get(8..))
+            .and_then(|data| bytemuck::try_from_bytes::<OracleAccountData>(data).ok())
+            .and_then(|data| data.libsecp256k1_signer());
+        if let Some(signer) = signer {
+            signers.insert(*oracle, signer);
+        }
+    }
+    Ok(signers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn signer() -> (libsecp256k1::SecretKey, SecpPublicKey) {
+        let secret = libsecp256k1::SecretKey::parse(&[7u8; 32]).unwrap();
+        (secret, SecpPublicKey::from_secret_key(&secret))
+    }
+
+    fn feed() -> PullFeedAccountData {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.queue = Pubkey::new_unique();
+        feed.feed_hash = [3; 32];
+        feed.max_variance = 1_000_000_000;
+        feed.min_responses = 1;
+        feed
+    }
+
+    fn signed_response(
+        feed: &PullFeedAccountData,
+        value: i128,
+        slothash: [u8; 32],
+    ) -> OracleResponse {
+        let (secret, _) = signer();
+        let checksum = feed.generate_checksum(value, slothash);
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&checksum), &secret);
+        OracleResponse {
+            value: Some(Decimal::from_i128_with_scale(value, 18)),
+            error: String::new(),
+            oracle: Pubkey::new_unique(),
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_oracle_response() {
+        let (_, public) = signer();
+        let feed = feed();
+        let response = signed_response(&feed, 5 * 10i128.pow(18), [9; 32]);
+        assert!(verify_oracle_response(&feed, &public, &response, &[9; 32]));
+        // A different slothash, value or feed config changes the checksum.
+        assert!(!verify_oracle_response(&feed, &public, &response, &[8; 32]));
+        let mut tampered = response.clone();
+        tampered.value = Some(Decimal::from(6));
+        assert!(!verify_oracle_response(&feed, &public, &tampered, &[9; 32]));
+        let mut other_feed = feed;
+        other_feed.min_responses = 2;
+        assert!(!verify_oracle_response(
+            &other_feed,
+            &public,
+            &response,
+            &[9; 32]
+        ));
+    }
+
+    #[test]
+    fn test_verify_oracle_responses_drops_invalid() {
+        let (_, public) = signer();
+        let feed = feed();
+        let valid = signed_response(&feed, 10i128.pow(18), [9; 32]);
+        let mut forged = signed_response(&feed, 10i128.pow(18), [9; 32]);
+        forged.signature[0] ^= 1;
+        let unknown = signed_response(&feed, 10i128.pow(18), [9; 32]);
+        let failed = OracleResponse {
+            value: None,
+            error: "fetch failed".to_string(),
+            oracle: Pubkey::new_unique(),
+            signature: [0; 64],
+            recovery_id: 0,
+            timestamp: None,
+        };
+        let signers = HashMap::from([(valid.oracle, public), (forged.oracle, public)]);
+
+        let (kept, dropped) = verify_oracle_responses(
+            &feed,
+            &[9; 32],
+            &signers,
+            vec![
+                valid.clone(),
+                forged.clone(),
+                unknown.clone(),
+                failed.clone(),
+            ],
+        );
+        let kept: Vec<Pubkey> = kept.iter().map(|x| x.oracle).collect();
+        assert_eq!(kept, vec![valid.oracle, failed.oracle]);
+        let dropped: Vec<String> = dropped.into_iter().map(|x| x.oracle).collect();
+        assert_eq!(
+            dropped,
+            vec![hex::encode(forged.oracle), hex::encode(unknown.oracle)]
+        );
+    }
+
+    #[test]
+    fn test_verify_multi_submission() {
+        let (secret, public) = signer();
+        let mut other_feed = feed();
+        other_feed.feed_hash = [4; 32];
+        let feeds = [feed(), other_feed];
+        let values = vec![10i128.pow(18), i128::MAX];
+        let checksum = generate_multi_checksum(&feeds, &[9; 32], &values);
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&checksum), &secret);
+        let submission = MultiSubmission {
+            values,
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+        };
+        let verify = |feeds: &[PullFeedAccountData], submission: &MultiSubmission, slothash| {
+            verify_multi_submission(feeds, &public, submission, slothash)
+        };
+        assert!(verify(&feeds, &submission, &[9; 32]));
+        assert!(!verify(&feeds, &submission, &[8; 32]));
+        assert!(!verify(&feeds[..1], &submission, &[9; 32]));
+        let mut tampered = submission.clone();
+        tampered.values[0] += 1;
+        assert!(!verify(&feeds, &tampered, &[9; 32]));
+    }
+
+    #[test]
+    fn test_verify_randomness_reveal() {
+        let (secret, public) = signer();
+        let value = [6u8; 32];
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&value), &secret);
+        let response = RandomnessRevealResponse {
+            signature: base64.encode(signature.serialize()),
+            recovery_id: recovery_id.serialize() as i32,
+            value: value.to_vec(),
+        };
+        assert!(verify_randomness_reveal(&public, &response));
+
+        let other =
+            SecpPublicKey::from_secret_key(&libsecp256k1::SecretKey::parse(&[8; 32]).unwrap());
+        assert!(!verify_randomness_reveal(&other, &response));
+        let mut tampered = response.clone();
+        tampered.value[0] ^= 1;
+        assert!(!verify_randomness_reveal(&public, &tampered));
+        let mut truncated = response.clone();
+        truncated.value.pop();
+        assert!(!verify_randomness_reveal(&public, &truncated));
+        let mut malformed = response.clone();
+        malformed.signature = "not base64".to_string();
+        assert!(!verify_randomness_reveal(&public, &malformed));
+        let mut wrong_recovery_id = response;
+        wrong_recovery_id.recovery_id = 256;
+        assert!(!verify_randomness_reveal(&public, &wrong_recovery_id));
+    }
+
+    #[test]
+    fn test_verify_consensus_signature() {
+        let (secret, public) = signer();
+        let checksum = [5u8; 32];
+        let hash: [u8; 32] = Keccak256::digest(checksum).into();
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), &secret);
+        let signature = SecpSignature {
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+            eth_address: eth_address(&public),
+            message: checksum.to_vec(),
+        };
+        assert!(verify_consensus_signature(&public, &checksum, &signature));
+        assert!(!verify_consensus_signature(&public, &[6u8; 32], &signature));
+        let mut wrong_address = signature.clone();
+        wrong_address.eth_address = [0; 20];
+        assert!(!verify_consensus_signature(
+            &public,
+            &checksum,
+            &wrong_address
+        ));
+    }
+}
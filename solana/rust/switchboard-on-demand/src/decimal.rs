@@ -163,6 +163,257 @@ impl From<SwitchboardDecimal> for bool {
     }
 }
 
+/// A fixed point number sharing the 18 decimal i128 representation of pull
+/// feed values, e.g. `CurrentResult::value`. Arithmetic is integer only and
+/// rounds toward zero, so it is cheap and deterministic on-chain.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SbFixed(i128);
+
+impl SbFixed {
+    /// The number of decimal places held by the raw value.
+    pub const SCALE: u32 = 18;
+    pub const ZERO: SbFixed = SbFixed(0);
+    pub const ONE: SbFixed = SbFixed(1_000_000_000_000_000_000);
+    pub const MIN: SbFixed = SbFixed(i128::MIN);
+    pub const MAX: SbFixed = SbFixed(i128::MAX);
+
+    /// Wraps a raw 18 decimal value such as `CurrentResult::value`.
+    pub const fn from_raw(raw: i128) -> Self {
+        Self(raw)
+    }
+
+    /// The raw 18 decimal value.
+    pub const fn raw(&self) -> i128 {
+        self.0
+    }
+
+    pub fn from_int(value: i64) -> Self {
+        Self(value as i128 * Self::ONE.0)
+    }
+
+    /// Converts an integer amount with `decimals` decimal places, e.g. a
+    /// token amount, truncating any digits beyond 18 decimals.
+    pub fn from_scaled(value: i128, decimals: u32) -> Option<Self> {
+        rescale(value, decimals, Self::SCALE).map(Self)
+    }
+
+    /// Converts to an integer amount with `decimals` decimal places, e.g. a
+    /// token amount, truncating toward zero.
+    pub fn to_scaled(&self, decimals: u32) -> Option<i128> {
+        rescale(self.0, Self::SCALE, decimals)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_abs(&self) -> Option<Self> {
+        self.0.checked_abs().map(Self)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        self.0.checked_neg().map(Self)
+    }
+
+    pub fn checked_add(&self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(&self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn checked_mul(&self, other: Self) -> Option<Self> {
+        checked_mul_div(self.0, other.0, Self::ONE.0).map(Self)
+    }
+
+    pub fn checked_div(&self, other: Self) -> Option<Self> {
+        checked_mul_div(self.0, Self::ONE.0, other.0).map(Self)
+    }
+
+    /// Raises the value to an integer power by repeated squaring.
+    pub fn checked_pow(&self, mut exp: u32) -> Option<Self> {
+        let mut base = *self;
+        let mut result = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// The square root rounded down, or `None` for negative values.
+    pub fn checked_sqrt(&self) -> Option<Self> {
+        if self.0 < 0 {
+            return None;
+        }
+        // sqrt(raw / 1e18) * 1e18 == sqrt(raw * 1e18)
+        let (hi, lo) = widening_mul(self.0 as u128, Self::ONE.0 as u128);
+        i128::try_from(isqrt_wide(hi, lo)).ok().map(Self)
+    }
+
+    /// The natural logarithm, accurate to about 1e-17, or `None` for values
+    /// that are not positive.
+    pub fn checked_ln(&self) -> Option<Self> {
+        const LN_2: i128 = 693_147_180_559_945_309;
+        if self.0 <= 0 {
+            return None;
+        }
+        // x = m * 2^k with m in [1, 2)
+        let (mut m, mut k) = (self.0, 0i128);
+        while m >= 2 * Self::ONE.0 {
+            m /= 2;
+            k += 1;
+        }
+        while m < Self::ONE.0 {
+            m *= 2;
+            k -= 1;
+        }
+        // ln(m) = 2 * atanh(z) = 2 * (z + z^3 / 3 + z^5 / 5 + ...), where
+        // z = (m - 1) / (m + 1) < 1 / 3
+        let z = checked_mul_div(m - Self::ONE.0, Self::ONE.0, m + Self::ONE.0)?;
+        let z2 = checked_mul_div(z, z, Self::ONE.0)?;
+        let (mut term, mut sum, mut n) = (z, 0i128, 1i128);
+        while term != 0 {
+            sum += term / n;
+            term = checked_mul_div(term, z2, Self::ONE.0)?;
+            n += 2;
+        }
+        Some(Self(2 * sum + k * LN_2))
+    }
+
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        Decimal::try_from_i128_with_scale(self.0, Self::SCALE).ok()
+    }
+}
+
+impl From<SbFixed> for SwitchboardDecimal {
+    fn from(val: SbFixed) -> Self {
+        SwitchboardDecimal::new(val.0, SbFixed::SCALE)
+    }
+}
+impl From<SbFixed> for BorshDecimal {
+    fn from(val: SbFixed) -> Self {
+        BorshDecimal {
+            mantissa: val.0,
+            scale: SbFixed::SCALE,
+        }
+    }
+}
+impl TryFrom<&SwitchboardDecimal> for SbFixed {
+    type Error = OnDemandError;
+    fn try_from(val: &SwitchboardDecimal) -> std::result::Result<Self, OnDemandError> {
+        SbFixed::from_scaled(val.mantissa, val.scale).ok_or(OnDemandError::DecimalConversionError)
+    }
+}
+impl TryFrom<&BorshDecimal> for SbFixed {
+    type Error = OnDemandError;
+    fn try_from(val: &BorshDecimal) -> std::result::Result<Self, OnDemandError> {
+        SbFixed::from_scaled(val.mantissa, val.scale).ok_or(OnDemandError::DecimalConversionError)
+    }
+}
+impl TryFrom<Decimal> for SbFixed {
+    type Error = OnDemandError;
+    fn try_from(val: Decimal) -> std::result::Result<Self, OnDemandError> {
+        SbFixed::from_scaled(val.mantissa(), val.scale())
+            .ok_or(OnDemandError::DecimalConversionError)
+    }
+}
+
+// moves `value` from `from` decimal places to `to` decimal places, truncating toward zero
+fn rescale(value: i128, from: u32, to: u32) -> Option<i128> {
+    match from.cmp(&to) {
+        Ordering::Greater => Some(value / 10_i128.checked_pow(from - to)?),
+        Ordering::Less => value.checked_mul(10_i128.checked_pow(to - from)?),
+        Ordering::Equal => Some(value),
+    }
+}
+
+// floor of the square root of a 256 bit value using Newton's method
+fn isqrt_wide(hi: u128, lo: u128) -> u128 {
+    if hi == 0 && lo < 2 {
+        return lo;
+    }
+    let bits = if hi == 0 {
+        128 - lo.leading_zeros()
+    } else {
+        256 - hi.leading_zeros()
+    };
+    // start above the root so the iteration decreases monotonically
+    let mut root = if bits >= 255 {
+        u128::MAX
+    } else {
+        1u128 << ((bits + 1) / 2)
+    };
+    loop {
+        let next = match div_wide(hi, lo, root) {
+            Some(quotient) => (root >> 1) + (quotient >> 1) + (root & quotient & 1),
+            None => return root,
+        };
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// Computes `a * b / denominator` rounded toward zero, keeping the full 256
+/// bit intermediate product so that two 18 decimal fixed point values can be
+/// multiplied or divided without overflowing mid-way. Returns `None` if the
+/// denominator is zero or the result does not fit in an `i128`.
+pub fn checked_mul_div(a: i128, b: i128, denominator: i128) -> Option<i128> {
+    if denominator == 0 {
+        return None;
+    }
+    let negative = (a < 0) ^ (b < 0) ^ (denominator < 0);
+    let (hi, lo) = widening_mul(a.unsigned_abs(), b.unsigned_abs());
+    let quotient = div_wide(hi, lo, denominator.unsigned_abs())?;
+    if negative {
+        0i128.checked_sub_unsigned(quotient)
+    } else {
+        i128::try_from(quotient).ok()
+    }
+}
+
+// full 256 bit product of two u128s as (high, low) halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+// binary long division of a 256 bit value by a u128, None if the quotient overflows
+fn div_wide(hi: u128, lo: u128, denominator: u128) -> Option<u128> {
+    if hi >= denominator {
+        return None;
+    }
+    let mut rem = hi;
+    let mut quotient = 0u128;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || rem >= denominator {
+            rem = rem.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +445,70 @@ mod tests {
         assert!(!b);
     }
 
+    #[test]
+    fn sb_fixed_arithmetic() {
+        let two = SbFixed::from_int(2);
+        let three = SbFixed::from_int(3);
+        assert_eq!(two.checked_mul(three), Some(SbFixed::from_int(6)));
+        assert_eq!(
+            SbFixed::ONE.checked_div(three),
+            Some(SbFixed::from_raw(333_333_333_333_333_333))
+        );
+        assert_eq!(two.checked_pow(10), Some(SbFixed::from_int(1024)));
+        assert_eq!(SbFixed::from_int(9).checked_sqrt(), Some(three));
+        assert_eq!(
+            two.checked_sqrt(),
+            Some(SbFixed::from_raw(1_414_213_562_373_095_048))
+        );
+        assert_eq!(SbFixed::from_int(-1).checked_sqrt(), None);
+        assert_eq!(SbFixed::ONE.checked_div(SbFixed::ZERO), None);
+        assert_eq!(SbFixed::MAX.checked_mul(two), None);
+    }
+
+    #[test]
+    fn sb_fixed_ln() {
+        let ln = |raw: i128| SbFixed::from_raw(raw).checked_ln().map(|x| x.raw());
+        assert_eq!(ln(SbFixed::ONE.raw()), Some(0));
+        assert_eq!(ln(2 * SbFixed::ONE.raw()), Some(693_147_180_559_945_309));
+        assert_eq!(ln(SbFixed::ONE.raw() / 2), Some(-693_147_180_559_945_309));
+        // ln(10) = 2.302585092994045684...
+        assert_eq!(ln(10 * SbFixed::ONE.raw()), Some(2_302_585_092_994_045_675));
+        // ln(1e-18) = -41.446531673892822312...
+        assert_eq!(ln(1), Some(-41_446_531_673_892_822_292));
+        assert_eq!(ln(0), None);
+        assert_eq!(ln(-SbFixed::ONE.raw()), None);
+    }
+
+    #[test]
+    fn sb_fixed_rescales_to_token_decimals() {
+        // 1.23456789 scaled to 6 and 9 decimals
+        let value = SbFixed::from_raw(1_234_567_890_000_000_000);
+        assert_eq!(value.to_scaled(6), Some(1_234_567));
+        assert_eq!(SbFixed::from_scaled(1_234_567_890, 9), Some(value));
+        let swb: SwitchboardDecimal = value.into();
+        assert_eq!(SbFixed::try_from(&swb).unwrap(), value);
+    }
+
+    #[test]
+    fn checked_mul_div_keeps_wide_intermediate() {
+        let one = 10_i128.pow(18);
+        // 1e8 * 3e6 with 18 decimals overflows an i128 before dividing
+        let a = 100_000_000 * one;
+        let b = 3_000_000 * one;
+        assert_eq!(checked_mul_div(a, b, one), Some(300_000_000_000_000 * one));
+        assert_eq!(
+            checked_mul_div(-a, b, one),
+            Some(-300_000_000_000_000 * one)
+        );
+        assert_eq!(
+            checked_mul_div(one, one, 3 * one),
+            Some(333_333_333_333_333_333)
+        );
+        assert_eq!(checked_mul_div(one, one, 0), None);
+        assert_eq!(checked_mul_div(i128::MAX, 2, 1), None);
+        assert_eq!(checked_mul_div(i128::MIN, 1, 1), Some(i128::MIN));
+    }
+
     // #[test]
     // fn switchboard_decimal_to_u64() {
     // // 1234.5678
@@ -8,6 +8,9 @@ use std::sync::Arc;
 
 use solana_program::pubkey;
 
+#[doc(hidden)]
+pub use solana_program;
+
 pub mod decimal;
 pub use decimal::*;
 
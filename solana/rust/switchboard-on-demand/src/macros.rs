@@ -140,3 +140,20 @@ macro_rules! impl_account_deserialize {
         }
     };
 }
+
+/// Implements `AccountParse` for an account type using its `Discriminator`
+/// and `Owner` implementations.
+#[macro_export]
+macro_rules! impl_account_parse {
+    ($struct_name:ident) => {
+        impl $crate::AccountParse for $struct_name {
+            fn account_discriminator() -> [u8; 8] {
+                <$struct_name as $crate::Discriminator>::discriminator()
+            }
+
+            fn account_owner() -> $crate::solana_program::pubkey::Pubkey {
+                <$struct_name as $crate::Owner>::owner()
+            }
+        }
+    };
+}
@@ -0,0 +1,135 @@
+use std::cell::Ref;
+
+use solana_program::account_info::AccountInfo;
+use solana_program::pubkey::Pubkey;
+
+use crate::OnDemandError;
+
+/// Uniform parsing of Switchboard accounts from raw account data.
+///
+/// Every account in `on_demand::accounts` implements this trait through
+/// `impl_account_parse!`, so on-chain programs reading an `AccountInfo` and
+/// off-chain indexers reading raw bytes share the same validation.
+pub trait AccountParse: bytemuck::Pod {
+    /// The 8 byte anchor discriminator prefixing the account data.
+    fn account_discriminator() -> [u8; 8];
+
+    /// The program expected to own the account.
+    fn account_owner() -> Pubkey;
+
+    /// The size of the account data, including the discriminator.
+    fn account_size() -> usize {
+        8 + std::mem::size_of::<Self>()
+    }
+
+    /// Validates the discriminator and length of `data`.
+    fn validate_bytes(data: &[u8]) -> Result<(), OnDemandError> {
+        if data.len() < 8 || data[..8] != Self::account_discriminator() {
+            return Err(OnDemandError::InvalidDiscriminator);
+        }
+        if data.len() < Self::account_size() {
+            return Err(OnDemandError::InvalidSize);
+        }
+        Ok(())
+    }
+
+    /// Returns a zero-copy reference to the account stored in `data`.
+    /// Fails if `data` is not suitably aligned for `Self`; use `read_bytes`
+    /// for buffers of unknown alignment.
+    fn parse_bytes(data: &[u8]) -> Result<&Self, OnDemandError> {
+        Self::validate_bytes(data)?;
+        bytemuck::try_from_bytes(&data[8..Self::account_size()])
+            .map_err(|_| OnDemandError::AccountDeserializeError)
+    }
+
+    /// Returns a zero-copy reference to the account stored in `data` after
+    /// checking that it is owned by the expected program.
+    fn parse_owned_bytes<'a>(owner: &Pubkey, data: &'a [u8]) -> Result<&'a Self, OnDemandError> {
+        if *owner != Self::account_owner() {
+            return Err(OnDemandError::InvalidOwner);
+        }
+        Self::parse_bytes(data)
+    }
+
+    /// Returns a copy of the account stored in `data`, regardless of the
+    /// buffer's alignment.
+    fn read_bytes(data: &[u8]) -> Result<Self, OnDemandError> {
+        Self::validate_bytes(data)?;
+        bytemuck::try_pod_read_unaligned(&data[8..Self::account_size()])
+            .map_err(|_| OnDemandError::AccountDeserializeError)
+    }
+
+    /// Borrows the account data of `account_info` after checking its owner,
+    /// discriminator and length.
+    fn parse_account_info<'a>(
+        account_info: &'a AccountInfo,
+    ) -> Result<Ref<'a, Self>, OnDemandError> {
+        if *account_info.owner != Self::account_owner() {
+            return Err(OnDemandError::InvalidOwner);
+        }
+        let data = account_info
+            .try_borrow_data()
+            .map_err(|_| OnDemandError::AccountBorrowError)?;
+        Self::parse_ref(data)
+    }
+
+    /// Maps an already borrowed account data buffer to the account after
+    /// checking its discriminator and length.
+    fn parse_ref<'a>(data: Ref<'a, &mut [u8]>) -> Result<Ref<'a, Self>, OnDemandError> {
+        Self::validate_bytes(&data)?;
+        Ref::filter_map(data, |data: &&mut [u8]| {
+            bytemuck::try_from_bytes(&data[8..Self::account_size()]).ok()
+        })
+        .map_err(|_| OnDemandError::AccountDeserializeError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PullFeedAccountData, RandomnessAccountData};
+
+    fn account_bytes<T: AccountParse>(account: &T) -> Vec<u8> {
+        let mut data = T::account_discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(account));
+        data
+    }
+
+    #[test]
+    fn read_bytes_round_trips() {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.max_staleness = 250;
+        let parsed = PullFeedAccountData::read_bytes(&account_bytes(&feed)).unwrap();
+        assert_eq!(parsed.max_staleness, 250);
+    }
+
+    #[test]
+    fn rejects_wrong_discriminator() {
+        let randomness: RandomnessAccountData = bytemuck::Zeroable::zeroed();
+        let data = account_bytes(&randomness);
+        assert!(matches!(
+            PullFeedAccountData::read_bytes(&data),
+            Err(OnDemandError::InvalidDiscriminator)
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        let data = account_bytes(&feed);
+        assert!(matches!(
+            PullFeedAccountData::read_bytes(&data[..data.len() - 1]),
+            Err(OnDemandError::InvalidSize)
+        ));
+    }
+
+    #[test]
+    fn rejects_wrong_owner() {
+        let feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        let data = account_bytes(&feed);
+        assert!(matches!(
+            PullFeedAccountData::parse_owned_bytes(&Pubkey::new_unique(), &data),
+            Err(OnDemandError::InvalidOwner)
+        ));
+    }
+}
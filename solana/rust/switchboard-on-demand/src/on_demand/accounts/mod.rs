@@ -1,9 +1,11 @@
+pub mod account_parse;
 pub mod oracle;
 pub mod oracle_stats;
 pub mod pull_feed;
 pub mod queue;
 pub mod randomness;
 pub mod state;
+pub use account_parse::*;
 pub use oracle::*;
 pub use oracle_stats::*;
 pub use pull_feed::*;
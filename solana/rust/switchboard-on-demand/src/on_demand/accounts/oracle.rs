@@ -7,7 +7,9 @@ use solana_program::sysvar::clock::Clock;
 use crate::anchor_traits::*;
 #[allow(unused_imports)]
 use crate::impl_account_deserialize;
-use crate::{cfg_client, get_sb_program_id, OnDemandError, Quote};
+use crate::{
+    cfg_client, get_sb_program_id, impl_account_parse, AccountParse, OnDemandError, Quote,
+};
 cfg_client! {
     use crate::address_lookup_table;
     use crate::find_lut_of;
@@ -94,6 +96,7 @@ cfg_client! {
 impl Discriminator for OracleAccountData {
     const DISCRIMINATOR: [u8; 8] = [128, 30, 16, 241, 170, 73, 55, 54];
 }
+impl_account_parse!(OracleAccountData);
 
 impl Owner for OracleAccountData {
     fn owner() -> Pubkey {
@@ -130,19 +133,7 @@ impl OracleAccountData {
         let data = quote_account_info
             .try_borrow_data()
             .map_err(|_| OnDemandError::AccountBorrowError)?;
-        if data.len() < OracleAccountData::discriminator().len() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        let mut disc_bytes = [0u8; 8];
-        disc_bytes.copy_from_slice(&data[..8]);
-        if disc_bytes != OracleAccountData::discriminator() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        Ok(Ref::map(data, |data| {
-            bytemuck::from_bytes(&data[8..std::mem::size_of::<OracleAccountData>() + 8])
-        }))
+        <OracleAccountData as AccountParse>::parse_ref(data)
     }
 
     /// Returns the deserialized Switchboard Quote account
@@ -159,19 +150,7 @@ impl OracleAccountData {
     /// let quote_account = OracleAccountData::new(quote_account_info.try_borrow_data()?)?;
     /// ```
     pub fn new_from_bytes(data: &[u8]) -> Result<&OracleAccountData, OnDemandError> {
-        if data.len() < OracleAccountData::discriminator().len() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        let mut disc_bytes = [0u8; 8];
-        disc_bytes.copy_from_slice(&data[..8]);
-        if disc_bytes != OracleAccountData::discriminator() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        Ok(bytemuck::from_bytes(
-            &data[8..std::mem::size_of::<OracleAccountData>() + 8],
-        ))
+        <OracleAccountData as AccountParse>::parse_bytes(data)
     }
 
     pub fn signer(&self) -> Pubkey {
@@ -3,7 +3,7 @@ use solana_program::pubkey::Pubkey;
 use switchboard_common::cfg_client;
 
 use crate::anchor_traits::*;
-use crate::get_sb_program_id;
+use crate::{get_sb_program_id, impl_account_parse};
 
 #[derive(Default)]
 #[repr(C)]
@@ -58,6 +58,7 @@ impl Owner for OracleStatsAccountData {
 impl Discriminator for OracleStatsAccountData {
     const DISCRIMINATOR: [u8; 8] = [180, 157, 178, 234, 240, 27, 152, 179];
 }
+impl_account_parse!(OracleStatsAccountData);
 cfg_client! {
     use crate::impl_account_deserialize;
 
@@ -5,6 +5,7 @@ use anchor_lang::{
     account, error, zero_copy, AnchorDeserialize, AnchorSerialize, Discriminator, Owner, ZeroCopy,
 };
 use bytemuck;
+use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use sha2::{Digest, Sha256};
 use solana_program::clock::Clock;
@@ -164,6 +165,66 @@ pub struct CompactResult {
     pub slot: u64,
 }
 
+/// A snapshot of the current result of a pull feed along with the
+/// statistics needed to judge its quality.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceReading {
+    /// The median value of the submissions needed for quorom size
+    pub value: i128,
+    /// The standard deviation of the submissions needed for quorom size
+    pub std_dev: i128,
+    /// The range of the submissions needed for quorom size
+    pub range: i128,
+    /// The number of samples used to calculate this result
+    pub num_samples: u8,
+    /// The slot at which this value was signed.
+    pub slot: u64,
+    /// The unix timestamp of the oldest submission used in the result
+    pub min_timestamp: i64,
+    /// The unix timestamp of the newest submission used in the result
+    pub max_timestamp: i64,
+}
+impl PriceReading {
+    /// The median value of the submissions needed for quorom size
+    pub fn value(&self) -> Decimal {
+        Decimal::from_i128_with_scale(self.value, PRECISION)
+    }
+
+    /// The standard deviation of the submissions needed for quorom size
+    pub fn std_dev(&self) -> Decimal {
+        Decimal::from_i128_with_scale(self.std_dev, PRECISION)
+    }
+
+    /// The range of the submissions needed for quorom size
+    pub fn range(&self) -> Decimal {
+        Decimal::from_i128_with_scale(self.range, PRECISION)
+    }
+}
+
+/// Bounds a caller requires the current result to satisfy before it is
+/// accepted by `PullFeedAccountData::read_price`. Unset bounds are not checked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceReadPolicy {
+    /// Maximum standard deviation relative to the median, in basis points.
+    pub max_std_dev_bps: Option<u64>,
+    /// Maximum range relative to the median, in basis points.
+    pub max_range_bps: Option<u64>,
+    /// Minimum number of samples the result must be computed from.
+    pub min_samples: Option<u8>,
+    /// Maximum age in seconds of the oldest submission used in the result.
+    pub max_age_seconds: Option<u64>,
+}
+
+/// Freshness requirements measured in slots, wall-clock seconds or both.
+/// Unset bounds are not checked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StalenessPolicy {
+    /// Maximum number of slots since the result was signed.
+    pub max_staleness_slots: Option<u64>,
+    /// Maximum number of seconds since the result was signed.
+    pub max_age_seconds: Option<u64>,
+}
+
 /// A representation of the data in a pull feed account.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -212,10 +273,63 @@ impl OracleSubmission {
 }
 
 impl PullFeedAccountData {
-    /// Returns true if the value in the current result is within
-    /// staleness threshold
+    /// Returns true if the feed has a result and it is within staleness
+    /// threshold
     pub fn is_result_vaild(&self, clock: &Clock) -> bool {
-        self.result.slot >= clock.slot - self.max_staleness as u64
+        self.result.slot != 0 && self.result.slot >= self.min_valid_slot(clock)
+    }
+
+    /// Returns true if the feed has a result and it is within staleness
+    /// threshold. Fails with `IntegerOverflowError` if the clock slot is
+    /// smaller than `max_staleness`.
+    pub fn checked_is_result_valid(&self, clock: &Clock) -> Result<bool, OnDemandError> {
+        let min_valid_slot = self.checked_min_valid_slot(clock)?;
+        Ok(self.result.slot != 0 && self.result.slot >= min_valid_slot)
+    }
+
+    /// The oldest slot a submission may be signed at to be considered valid,
+    /// clamped to zero while the clock slot is smaller than `max_staleness`.
+    pub fn min_valid_slot(&self, clock: &Clock) -> u64 {
+        clock.slot.saturating_sub(self.max_staleness as u64)
+    }
+
+    /// The oldest slot a submission may be signed at to be considered valid.
+    /// Fails with `IntegerOverflowError` if the clock slot is smaller than
+    /// `max_staleness`.
+    pub fn checked_min_valid_slot(&self, clock: &Clock) -> Result<u64, OnDemandError> {
+        clock
+            .slot
+            .checked_sub(self.max_staleness as u64)
+            .ok_or(OnDemandError::IntegerOverflowError)
+    }
+
+    /// Returns true if the current result was signed within `max_age_seconds`
+    /// of the clock's unix timestamp.
+    ///
+    /// The age is taken from `result_ts()`, the timestamp of the submission
+    /// the result's value comes from, rather than `last_update_timestamp`.
+    /// The latter is bumped by every submission that lands, so it can be
+    /// recent while the value itself was signed long ago.
+    pub fn is_result_valid_with_max_age(&self, clock: &Clock, max_age_seconds: u64) -> bool {
+        self.result.slot != 0 && seconds_since(clock, self.result_ts()) <= max_age_seconds
+    }
+
+    /// Returns true if the current result satisfies every bound in `policy`
+    pub fn is_result_valid_with_policy(&self, clock: &Clock, policy: &StalenessPolicy) -> bool {
+        if self.result.slot == 0 {
+            return false;
+        }
+        if let Some(max_slots) = policy.max_staleness_slots {
+            if self.result.slot < clock.slot.saturating_sub(max_slots) {
+                return false;
+            }
+        }
+        if let Some(max_age) = policy.max_age_seconds {
+            if !self.is_result_valid_with_max_age(clock, max_age) {
+                return false;
+            }
+        }
+        true
     }
 
     pub fn result_submission(&self) -> &OracleSubmission {
@@ -233,19 +347,7 @@ impl PullFeedAccountData {
     }
 
     pub fn parse<'info>(data: Ref<'info, &mut [u8]>) -> Result<Ref<'info, Self>, OnDemandError> {
-        if data.len() < Self::discriminator().len() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        let mut disc_bytes = [0u8; 8];
-        disc_bytes.copy_from_slice(&data[..8]);
-        if disc_bytes != Self::discriminator() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        Ok(Ref::map(data, |data: &&mut [u8]| {
-            bytemuck::from_bytes(&data[8..std::mem::size_of::<Self>() + 8])
-        }))
+        <Self as AccountParse>::parse_ref(data)
     }
 
     /// Generate a checksum for the given feed hash, result, slothash, max_variance and min_responses
@@ -324,7 +426,7 @@ impl PullFeedAccountData {
             .submissions
             .iter()
             .take_while(|s| !s.is_empty())
-            .filter(|s| s.slot >= clock.slot - max_staleness)
+            .filter(|s| s.slot >= clock.slot.saturating_sub(max_staleness))
             .collect::<Vec<_>>();
         if submissions.len() < min_samples as usize {
             return Err(OnDemandError::NotEnoughSamples);
@@ -341,17 +443,46 @@ impl PullFeedAccountData {
 
     /// List of samples that are valid for the current slot
     pub fn valid_samples(&self, clock: &Clock) -> Vec<&OracleSubmission> {
+        self.samples_since(self.min_valid_slot(clock))
+    }
+
+    /// List of samples that are valid for the current slot.
+    /// Fails with `IntegerOverflowError` if the clock slot is smaller than
+    /// `max_staleness`.
+    pub fn checked_valid_samples(
+        &self,
+        clock: &Clock,
+    ) -> Result<Vec<&OracleSubmission>, OnDemandError> {
+        Ok(self.samples_since(self.checked_min_valid_slot(clock)?))
+    }
+
+    fn samples_since(&self, min_slot: u64) -> Vec<&OracleSubmission> {
         self.submissions
             .iter()
             .take_while(|s| !s.is_empty())
-            .filter(|s| s.slot >= clock.slot - self.max_staleness as u64)
+            .filter(|s| s.slot >= min_slot)
+            .collect()
+    }
+
+    /// List of samples signed within `max_age_seconds` of the clock's unix timestamp
+    pub fn valid_samples_with_max_age(
+        &self,
+        clock: &Clock,
+        max_age_seconds: u64,
+    ) -> Vec<&OracleSubmission> {
+        self.submissions
+            .iter()
+            .zip(self.submission_timestamps.iter())
+            .take_while(|(s, _)| !s.is_empty())
+            .filter(|(_, ts)| seconds_since(clock, **ts) <= max_age_seconds)
+            .map(|(s, _)| s)
             .collect()
     }
 
     /// Gets all the samples used in the current result
     pub fn current_result_samples(&self) -> Vec<(usize, &OracleSubmission)> {
         let last_update_slot = self.last_update_slot();
-        let slot_threshold = last_update_slot - self.max_staleness as u64;
+        let slot_threshold = last_update_slot.saturating_sub(self.max_staleness as u64);
         self.submissions
             .iter()
             .enumerate()
@@ -383,12 +514,242 @@ impl PullFeedAccountData {
     /// The median value of the submissions needed for quorom size
     /// Fails if the result is not valid or stale.
     pub fn value(&self, clock: &Clock) -> Result<Decimal, OnDemandError> {
-        if self.result.result_slot().unwrap_or(0) < clock.slot - self.max_staleness as u64 {
+        if self.result.result_slot().unwrap_or(0) < self.min_valid_slot(clock) {
+            return Err(OnDemandError::StaleResult);
+        }
+        self.result.value().ok_or(OnDemandError::StaleResult)
+    }
+
+    /// The median value of the submissions needed for quorom size
+    /// Fails if the result is not valid or stale, or with
+    /// `IntegerOverflowError` if the clock slot is smaller than `max_staleness`.
+    pub fn checked_value(&self, clock: &Clock) -> Result<Decimal, OnDemandError> {
+        if self.result.result_slot().unwrap_or(0) < self.checked_min_valid_slot(clock)? {
             return Err(OnDemandError::StaleResult);
         }
         self.result.value().ok_or(OnDemandError::StaleResult)
     }
 
+    /// The median value of the submissions needed for quorom size
+    /// Fails if the result was signed more than `max_age_seconds` ago.
+    pub fn value_with_max_age(
+        &self,
+        clock: &Clock,
+        max_age_seconds: u64,
+    ) -> Result<Decimal, OnDemandError> {
+        if !self.is_result_valid_with_max_age(clock, max_age_seconds) {
+            return Err(OnDemandError::StaleResult);
+        }
+        self.result.value().ok_or(OnDemandError::StaleResult)
+    }
+
+    /// The median value of the submissions needed for quorom size
+    /// Fails if the result does not satisfy every bound in `policy`.
+    pub fn value_with_policy(
+        &self,
+        clock: &Clock,
+        policy: &StalenessPolicy,
+    ) -> Result<Decimal, OnDemandError> {
+        if !self.is_result_valid_with_policy(clock, policy) {
+            return Err(OnDemandError::StaleResult);
+        }
+        self.result.value().ok_or(OnDemandError::StaleResult)
+    }
+
+    /// Reads the current result and checks it against `policy`.
+    /// Fails with `StaleResult` if the result is not valid or stale, otherwise
+    /// with a dedicated error for the first bound the result violates.
+    pub fn read_price(
+        &self,
+        clock: &Clock,
+        policy: &PriceReadPolicy,
+    ) -> Result<PriceReading, OnDemandError> {
+        self.value(clock)?;
+        let (min_timestamp, max_timestamp) = self.current_result_ts_range();
+        let reading = PriceReading {
+            value: self.result.value,
+            std_dev: self.result.std_dev,
+            range: self.result.range,
+            num_samples: self.result.num_samples,
+            slot: self.result.slot,
+            min_timestamp,
+            max_timestamp,
+        };
+
+        if let Some(min_samples) = policy.min_samples {
+            if reading.num_samples < min_samples {
+                return Err(OnDemandError::InsufficientSamples);
+            }
+        }
+        if let Some(max_age) = policy.max_age_seconds {
+            if seconds_since(clock, min_timestamp) > max_age {
+                return Err(OnDemandError::MaxAgeExceeded);
+            }
+        }
+        if let Some(max_bps) = policy.max_std_dev_bps {
+            if !within_bps(reading.std_dev, reading.value, max_bps)? {
+                return Err(OnDemandError::ExcessiveStdDev);
+            }
+        }
+        if let Some(max_bps) = policy.max_range_bps {
+            if !within_bps(reading.range, reading.value, max_bps)? {
+                return Err(OnDemandError::ExcessiveRange);
+            }
+        }
+        Ok(reading)
+    }
+
+    /// Iterates the historical results ring buffer from oldest to newest,
+    /// skipping slots that have not been written yet.
+    pub fn historical_results(&self) -> impl Iterator<Item = &CompactResult> + '_ {
+        let len = self.historical_results.len();
+        let start = self.historical_result_idx as usize % len;
+        (0..len)
+            .map(move |i| &self.historical_results[(start + i) % len])
+            .filter(|r| r.slot != 0)
+    }
+
+    /// Historical results signed within the last `window_slots` slots,
+    /// ordered from oldest to newest.
+    pub fn historical_results_in_window(
+        &self,
+        clock: &Clock,
+        window_slots: u64,
+    ) -> Vec<&CompactResult> {
+        let start = clock.slot.saturating_sub(window_slots);
+        self.historical_results()
+            .filter(|r| r.slot >= start && r.slot <= clock.slot)
+            .collect()
+    }
+
+    /// Time-weighted average of the historical means over the last
+    /// `window_slots` slots. Each result is weighted by the number of slots
+    /// it remained the latest result, so the result preceding the window
+    /// counts for the slots it covers inside it.
+    ///
+    /// The means are averaged in 18 decimal fixed point, rounding toward zero.
+    pub fn twap(&self, clock: &Clock, window_slots: u64) -> Result<Decimal, OnDemandError> {
+        let start = clock.slot.saturating_sub(window_slots);
+        let history: Vec<&CompactResult> = self
+            .historical_results()
+            .filter(|r| r.slot <= clock.slot)
+            .collect();
+        let mut weighted_sum = 0i128;
+        let mut total_slots = 0i128;
+        for (i, result) in history.iter().enumerate() {
+            let end = history.get(i + 1).map_or(clock.slot, |next| next.slot);
+            let begin = result.slot.max(start);
+            if end > begin {
+                let slots = (end - begin) as i128;
+                weighted_sum = compact_mean(result)?
+                    .raw()
+                    .checked_mul(slots)
+                    .and_then(|weighted| weighted_sum.checked_add(weighted))
+                    .ok_or(OnDemandError::IntegerOverflowError)?;
+                total_slots += slots;
+            }
+        }
+        if total_slots == 0 {
+            // every result in the window landed in the current slot
+            let latest = history
+                .last()
+                .filter(|r| r.slot >= start)
+                .ok_or(OnDemandError::NotEnoughSamples)?;
+            return fixed_to_decimal(compact_mean(latest)?);
+        }
+        fixed_to_decimal(SbFixed::from_raw(weighted_sum / total_slots))
+    }
+
+    /// Exponential moving average of the historical means over the last
+    /// `window_slots` slots using a smoothing factor of `2 / (period + 1)`,
+    /// in 18 decimal fixed point.
+    pub fn ema(
+        &self,
+        clock: &Clock,
+        window_slots: u64,
+        period: u32,
+    ) -> Result<Decimal, OnDemandError> {
+        let alpha = checked_mul_div(2, SbFixed::ONE.raw(), period as i128 + 1)
+            .map(SbFixed::from_raw)
+            .ok_or(OnDemandError::IntegerOverflowError)?;
+        let mut means = self
+            .historical_results_in_window(clock, window_slots)
+            .into_iter()
+            .map(compact_mean);
+        let mut ema = means.next().ok_or(OnDemandError::NotEnoughSamples)??;
+        for mean in means {
+            // ema + alpha * (mean - ema)
+            ema = mean?
+                .checked_sub(ema)
+                .and_then(|delta| delta.checked_mul(alpha))
+                .and_then(|step| ema.checked_add(step))
+                .ok_or(OnDemandError::IntegerOverflowError)?;
+        }
+        fixed_to_decimal(ema)
+    }
+
+    /// Minimum and maximum of the historical means over the last
+    /// `window_slots` slots.
+    pub fn historical_min_max(
+        &self,
+        clock: &Clock,
+        window_slots: u64,
+    ) -> Result<(Decimal, Decimal), OnDemandError> {
+        let means = self
+            .historical_results_in_window(clock, window_slots)
+            .into_iter()
+            .map(compact_mean)
+            .collect::<Result<Vec<SbFixed>, _>>()?;
+        match (means.iter().min(), means.iter().max()) {
+            (Some(min), Some(max)) => Ok((fixed_to_decimal(*min)?, fixed_to_decimal(*max)?)),
+            _ => Err(OnDemandError::NotEnoughSamples),
+        }
+    }
+
+    /// Realized volatility over the last `window_slots` slots, computed as the
+    /// standard deviation of the log returns between consecutive historical
+    /// means, in 18 decimal fixed point. Requires at least two means in the
+    /// window, all of them positive.
+    pub fn realized_volatility(
+        &self,
+        clock: &Clock,
+        window_slots: u64,
+    ) -> Result<Decimal, OnDemandError> {
+        let means = self
+            .historical_results_in_window(clock, window_slots)
+            .into_iter()
+            .map(compact_mean)
+            .collect::<Result<Vec<SbFixed>, _>>()?;
+        if means.iter().any(|mean| mean.raw() <= 0) {
+            return Err(OnDemandError::IllegalFeedValue);
+        }
+        let returns = means
+            .windows(2)
+            .map(|w| w[1].checked_div(w[0]).and_then(|ratio| ratio.checked_ln()))
+            .collect::<Option<Vec<SbFixed>>>()
+            .ok_or(OnDemandError::IntegerOverflowError)?;
+        if returns.is_empty() {
+            return Err(OnDemandError::NotEnoughSamples);
+        }
+        let n = returns.len() as i128;
+        let sum = returns
+            .iter()
+            .try_fold(SbFixed::ZERO, |sum, r| sum.checked_add(*r))
+            .ok_or(OnDemandError::IntegerOverflowError)?;
+        let mean = SbFixed::from_raw(sum.raw() / n);
+        let squares = returns
+            .iter()
+            .try_fold(SbFixed::ZERO, |sum, r| {
+                let deviation = r.checked_sub(mean)?;
+                sum.checked_add(deviation.checked_mul(deviation)?)
+            })
+            .ok_or(OnDemandError::IntegerOverflowError)?;
+        let volatility = SbFixed::from_raw(squares.raw() / n)
+            .checked_sqrt()
+            .ok_or(OnDemandError::IntegerOverflowError)?;
+        fixed_to_decimal(volatility)
+    }
+
     /// The standard deviation of the submissions needed for quorom size
     pub fn std_dev(&self) -> Option<Decimal> {
         self.result.std_dev()
@@ -424,6 +785,7 @@ impl Owner for PullFeedAccountData {
 impl Discriminator for PullFeedAccountData {
     const DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
 }
+impl_account_parse!(PullFeedAccountData);
 
 pub type SbFeed = PullFeedAccountData;
 
@@ -437,3 +799,418 @@ pub fn lower_bound_median(numbers: &mut Vec<i128>) -> Option<i128> {
     }
     Some(numbers[len / 2])
 }
+
+// the stored f32 mean of `result` in 18 decimal fixed point
+fn compact_mean(result: &CompactResult) -> Result<SbFixed, OnDemandError> {
+    let mean = Decimal::from_f32(result.mean).ok_or(OnDemandError::DecimalConversionError)?;
+    SbFixed::try_from(mean)
+}
+
+fn fixed_to_decimal(value: SbFixed) -> Result<Decimal, OnDemandError> {
+    value
+        .to_decimal()
+        .ok_or(OnDemandError::DecimalConversionError)
+}
+
+// seconds elapsed between `timestamp` and the clock, zero if it lies in the future
+fn seconds_since(clock: &Clock, timestamp: i64) -> u64 {
+    clock.unix_timestamp.saturating_sub(timestamp).max(0) as u64
+}
+
+// checks that |amount| / |base| <= max_bps / 10_000 without dividing
+fn within_bps(amount: i128, base: i128, max_bps: u64) -> Result<bool, OnDemandError> {
+    let lhs = amount
+        .unsigned_abs()
+        .checked_mul(10_000)
+        .ok_or(OnDemandError::IntegerOverflowError)?;
+    let rhs = base
+        .unsigned_abs()
+        .checked_mul(max_bps as u128)
+        .ok_or(OnDemandError::IntegerOverflowError)?;
+    Ok(lhs <= rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_with_history(history: &[(u64, f32)], next_idx: u8) -> PullFeedAccountData {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        for (i, (slot, mean)) in history.iter().enumerate() {
+            feed.historical_results[i] = CompactResult {
+                std_dev: 0.0,
+                mean: *mean,
+                slot: *slot,
+            };
+        }
+        feed.historical_result_idx = next_idx;
+        feed
+    }
+
+    fn clock_at(slot: u64) -> Clock {
+        Clock {
+            slot,
+            ..Clock::default()
+        }
+    }
+
+    fn feed_with_result(result_slot: u64, max_staleness: u32) -> PullFeedAccountData {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.max_staleness = max_staleness;
+        feed.result.value = 42;
+        feed.result.slot = result_slot;
+        feed.submissions[0].slot = result_slot;
+        feed.submissions[0].landed_at = result_slot;
+        feed
+    }
+
+    #[test]
+    fn staleness_saturates_on_low_slot_clock() {
+        let feed = feed_with_result(5, 250);
+        let clock = clock_at(10);
+        assert_eq!(feed.min_valid_slot(&clock), 0);
+        assert!(feed.is_result_vaild(&clock));
+        assert!(feed.value(&clock).is_ok());
+        assert_eq!(feed.valid_samples(&clock).len(), 1);
+        assert_eq!(feed.current_result_samples().len(), 1);
+        assert!(feed.get_value(&clock, 250, 1, false).is_ok());
+    }
+
+    #[test]
+    fn never_updated_feed_is_not_valid_on_low_slot_clock() {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.max_staleness = 250;
+        let clock = clock_at(10);
+        assert_eq!(feed.min_valid_slot(&clock), 0);
+        assert!(!feed.is_result_vaild(&clock));
+        assert!(matches!(
+            feed.value(&clock),
+            Err(OnDemandError::StaleResult)
+        ));
+        assert!(!feed.checked_is_result_valid(&clock_at(250)).unwrap());
+    }
+
+    #[test]
+    fn checked_staleness_reports_underflow() {
+        let feed = feed_with_result(5, 250);
+        let clock = clock_at(10);
+        assert!(matches!(
+            feed.checked_min_valid_slot(&clock),
+            Err(OnDemandError::IntegerOverflowError)
+        ));
+        assert!(matches!(
+            feed.checked_is_result_valid(&clock),
+            Err(OnDemandError::IntegerOverflowError)
+        ));
+        assert!(matches!(
+            feed.checked_value(&clock),
+            Err(OnDemandError::IntegerOverflowError)
+        ));
+        assert!(feed.checked_valid_samples(&clock).is_err());
+    }
+
+    #[test]
+    fn checked_staleness_matches_saturating_on_high_slot_clock() {
+        let feed = feed_with_result(1_000, 250);
+        let clock = clock_at(1_100);
+        assert_eq!(feed.checked_min_valid_slot(&clock).unwrap(), 850);
+        assert!(feed.checked_is_result_valid(&clock).unwrap());
+        assert!(!feed.checked_is_result_valid(&clock_at(1_251)).unwrap());
+        assert!(matches!(
+            feed.checked_value(&clock_at(1_251)),
+            Err(OnDemandError::StaleResult)
+        ));
+    }
+
+    fn feed_with_reading(std_dev: i128, range: i128, num_samples: u8) -> PullFeedAccountData {
+        let mut feed = feed_with_result(1_000, 100);
+        feed.result.value = 10_000;
+        feed.result.std_dev = std_dev;
+        feed.result.range = range;
+        feed.result.num_samples = num_samples;
+        feed.submission_timestamps[0] = 9_990;
+        feed
+    }
+
+    fn clock_at_time(slot: u64, unix_timestamp: i64) -> Clock {
+        Clock {
+            slot,
+            unix_timestamp,
+            ..Clock::default()
+        }
+    }
+
+    #[test]
+    fn read_price_accepts_result_on_policy_bounds() {
+        let feed = feed_with_reading(100, 200, 3);
+        let policy = PriceReadPolicy {
+            max_std_dev_bps: Some(100),
+            max_range_bps: Some(200),
+            min_samples: Some(3),
+            max_age_seconds: Some(10),
+        };
+        let reading = feed
+            .read_price(&clock_at_time(1_010, 10_000), &policy)
+            .unwrap();
+        assert_eq!(
+            reading,
+            PriceReading {
+                value: 10_000,
+                std_dev: 100,
+                range: 200,
+                num_samples: 3,
+                slot: 1_000,
+                min_timestamp: 9_990,
+                max_timestamp: 9_990,
+            }
+        );
+        assert!(feed
+            .read_price(&clock_at_time(1_010, 10_000), &PriceReadPolicy::default())
+            .is_ok());
+    }
+
+    #[test]
+    fn read_price_rejects_each_violated_bound() {
+        let feed = feed_with_reading(100, 200, 3);
+        let clock = clock_at_time(1_010, 10_000);
+        let read = |policy: PriceReadPolicy| feed.read_price(&clock, &policy);
+        assert!(matches!(
+            read(PriceReadPolicy {
+                max_std_dev_bps: Some(99),
+                ..Default::default()
+            }),
+            Err(OnDemandError::ExcessiveStdDev)
+        ));
+        assert!(matches!(
+            read(PriceReadPolicy {
+                max_range_bps: Some(199),
+                ..Default::default()
+            }),
+            Err(OnDemandError::ExcessiveRange)
+        ));
+        assert!(matches!(
+            read(PriceReadPolicy {
+                min_samples: Some(4),
+                ..Default::default()
+            }),
+            Err(OnDemandError::InsufficientSamples)
+        ));
+        assert!(matches!(
+            read(PriceReadPolicy {
+                max_age_seconds: Some(9),
+                ..Default::default()
+            }),
+            Err(OnDemandError::MaxAgeExceeded)
+        ));
+        assert!(matches!(
+            feed.read_price(&clock_at_time(1_101, 10_000), &PriceReadPolicy::default()),
+            Err(OnDemandError::StaleResult)
+        ));
+    }
+
+    #[test]
+    fn read_price_with_zero_value_only_accepts_zero_spread() {
+        let mut feed = feed_with_reading(0, 0, 3);
+        feed.result.value = 0;
+        let clock = clock_at_time(1_010, 10_000);
+        let policy = PriceReadPolicy {
+            max_std_dev_bps: Some(u64::MAX),
+            ..Default::default()
+        };
+        assert!(feed.read_price(&clock, &policy).is_ok());
+        feed.result.std_dev = 1;
+        assert!(matches!(
+            feed.read_price(&clock, &policy),
+            Err(OnDemandError::ExcessiveStdDev)
+        ));
+    }
+
+    #[test]
+    fn within_bps_boundaries() {
+        assert!(within_bps(100, 10_000, 100).unwrap());
+        assert!(!within_bps(101, 10_000, 100).unwrap());
+        assert!(within_bps(-100, -10_000, 100).unwrap());
+        assert!(within_bps(0, 10_000, 0).unwrap());
+        assert!(!within_bps(1, 10_000, 0).unwrap());
+        // A zero base only admits a zero amount.
+        assert!(within_bps(0, 0, 0).unwrap());
+        assert!(!within_bps(1, 0, u64::MAX).unwrap());
+        assert!(matches!(
+            within_bps(i128::MAX, 1, 0),
+            Err(OnDemandError::IntegerOverflowError)
+        ));
+        assert!(matches!(
+            within_bps(0, i128::MAX, u64::MAX),
+            Err(OnDemandError::IntegerOverflowError)
+        ));
+    }
+
+    #[test]
+    fn max_age_uses_result_submission_timestamp() {
+        let mut feed = feed_with_result(1_000, 100);
+        feed.submissions[1] = feed.submissions[0];
+        feed.submission_timestamps[0] = 9_990;
+        feed.submission_timestamps[1] = 9_999;
+        feed.last_update_timestamp = 9_999;
+        let clock = clock_at_time(1_010, 10_000);
+        assert!(feed.is_result_valid_with_max_age(&clock, 10));
+        assert!(!feed.is_result_valid_with_max_age(&clock, 9));
+        assert_eq!(
+            feed.value_with_max_age(&clock, 10).unwrap(),
+            feed.result.value().unwrap()
+        );
+        assert!(matches!(
+            feed.value_with_max_age(&clock, 9),
+            Err(OnDemandError::StaleResult)
+        ));
+        // A timestamp ahead of the clock counts as zero seconds old.
+        assert!(feed.is_result_valid_with_max_age(&clock_at_time(1_010, 9_000), 0));
+
+        let never_updated: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        assert!(!never_updated.is_result_valid_with_max_age(&clock, u64::MAX));
+    }
+
+    #[test]
+    fn valid_samples_with_max_age_stops_at_empty_submission() {
+        let mut feed = feed_with_result(1_000, 100);
+        feed.submissions[1] = feed.submissions[0];
+        feed.submissions[3] = feed.submissions[0];
+        feed.submission_timestamps[0] = 9_980;
+        feed.submission_timestamps[1] = 9_995;
+        feed.submission_timestamps[3] = 10_000;
+        let clock = clock_at_time(1_010, 10_000);
+        assert_eq!(feed.valid_samples_with_max_age(&clock, 5).len(), 1);
+        assert_eq!(feed.valid_samples_with_max_age(&clock, 20).len(), 2);
+        assert!(feed.valid_samples_with_max_age(&clock, 4).is_empty());
+    }
+
+    #[test]
+    fn value_with_policy_checks_every_bound() {
+        let mut feed = feed_with_result(1_000, 100);
+        feed.submission_timestamps[0] = 9_990;
+        let clock = clock_at_time(1_010, 10_000);
+        let policy = |max_staleness_slots, max_age_seconds| StalenessPolicy {
+            max_staleness_slots,
+            max_age_seconds,
+        };
+        assert!(feed.value_with_policy(&clock, &policy(None, None)).is_ok());
+        assert!(feed
+            .value_with_policy(&clock, &policy(Some(10), Some(10)))
+            .is_ok());
+        assert!(matches!(
+            feed.value_with_policy(&clock, &policy(Some(9), Some(10))),
+            Err(OnDemandError::StaleResult)
+        ));
+        assert!(matches!(
+            feed.value_with_policy(&clock, &policy(Some(10), Some(9))),
+            Err(OnDemandError::StaleResult)
+        ));
+        // The slot bound saturates on a clock slot below it.
+        assert!(
+            feed.is_result_valid_with_policy(&clock_at_time(5, 10_000), &policy(Some(10), None))
+        );
+
+        let never_updated: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        assert!(!never_updated.is_result_valid_with_policy(&clock, &policy(None, None)));
+    }
+
+    #[test]
+    fn historical_results_iterate_oldest_first() {
+        let feed = feed_with_history(&[(30, 3.0), (10, 1.0), (20, 2.0)], 1);
+        let slots: Vec<u64> = feed.historical_results().map(|r| r.slot).collect();
+        assert_eq!(slots, vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn twap_weights_by_slots_held() {
+        let feed = feed_with_history(&[(100, 10.0), (120, 20.0), (130, 40.0)], 3);
+        // window [110, 150]: 10 for 10 slots, 20 for 10 slots, 40 for 20 slots
+        let twap = feed.twap(&clock_at(150), 40).unwrap();
+        assert_eq!(twap, Decimal::new(275, 1));
+    }
+
+    #[test]
+    fn twap_without_history_fails() {
+        let feed = feed_with_history(&[], 0);
+        assert!(feed.twap(&clock_at(140), 35).is_err());
+    }
+
+    #[test]
+    fn twap_rounds_toward_zero_at_18_decimals() {
+        // (1.5 * 10 + 2.25 * 23) / 33 = 2.0227272727...
+        let feed = feed_with_history(&[(100, 1.5), (110, 2.25)], 2);
+        let twap = feed.twap(&clock_at(133), 33).unwrap();
+        assert_eq!(
+            twap,
+            Decimal::from_i128_with_scale(2_022_727_272_727_272_727, 18)
+        );
+    }
+
+    #[test]
+    fn ema_smooths_means_in_window() {
+        let feed = feed_with_history(&[(90, 1_000.0), (100, 10.0), (110, 20.0), (120, 40.0)], 4);
+        let clock = clock_at(130);
+        // alpha 1 / 2: 10, 15, 27.5
+        assert_eq!(feed.ema(&clock, 30, 3).unwrap(), Decimal::new(275, 1));
+        // alpha 2 / 3, rounded toward zero at 18 decimals
+        assert_eq!(
+            feed.ema(&clock, 30, 2).unwrap(),
+            Decimal::from_i128_with_scale(32_222_222_222_222_222_204, 18)
+        );
+        // alpha 1 follows the latest mean
+        assert_eq!(feed.ema(&clock, 30, 1).unwrap(), Decimal::from(40));
+        assert!(matches!(
+            feed.ema(&clock_at(200), 30, 3),
+            Err(OnDemandError::NotEnoughSamples)
+        ));
+    }
+
+    #[test]
+    fn realized_volatility_of_log_returns() {
+        let clock = clock_at(130);
+        // log returns ln(2) and -ln(2)
+        let feed = feed_with_history(&[(100, 10.0), (110, 20.0), (120, 10.0)], 3);
+        assert_eq!(
+            feed.realized_volatility(&clock, 30).unwrap(),
+            Decimal::from_i128_with_scale(693_147_180_559_945_308, 18)
+        );
+        // log returns ln(1.1) and ln(0.9)
+        let feed = feed_with_history(&[(100, 100.0), (110, 110.0), (120, 99.0)], 3);
+        assert_eq!(
+            feed.realized_volatility(&clock, 30).unwrap(),
+            Decimal::from_i128_with_scale(100_335_347_731_075_582, 18)
+        );
+        let flat = feed_with_history(&[(100, 5.0), (110, 5.0), (120, 5.0)], 3);
+        assert_eq!(flat.realized_volatility(&clock, 30).unwrap(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn realized_volatility_rejects_non_positive_means() {
+        let clock = clock_at(130);
+        for mean in [0.0, -5.0] {
+            let feed = feed_with_history(&[(100, 10.0), (110, mean), (120, 10.0)], 3);
+            assert!(matches!(
+                feed.realized_volatility(&clock, 30),
+                Err(OnDemandError::IllegalFeedValue)
+            ));
+        }
+        let nan = feed_with_history(&[(100, 10.0), (110, f32::NAN)], 2);
+        assert!(matches!(
+            nan.realized_volatility(&clock, 30),
+            Err(OnDemandError::DecimalConversionError)
+        ));
+        let single = feed_with_history(&[(120, 10.0)], 1);
+        assert!(matches!(
+            single.realized_volatility(&clock, 30),
+            Err(OnDemandError::NotEnoughSamples)
+        ));
+    }
+
+    #[test]
+    fn min_max_only_considers_window() {
+        let feed = feed_with_history(&[(100, 1.0), (110, 5.0), (130, 3.0)], 3);
+        let (min, max) = feed.historical_min_max(&clock_at(140), 35).unwrap();
+        assert_eq!(min, Decimal::from(3));
+        assert_eq!(max, Decimal::from(5));
+    }
+}
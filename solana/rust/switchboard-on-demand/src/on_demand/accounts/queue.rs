@@ -9,7 +9,7 @@ use crate::anchor_traits::*;
 use crate::impl_account_deserialize;
 #[allow(unused_imports)]
 use crate::OracleAccountData;
-use crate::{cfg_client, get_sb_program_id, OnDemandError};
+use crate::{cfg_client, get_sb_program_id, impl_account_parse, AccountParse, OnDemandError};
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -78,6 +78,7 @@ cfg_client! {
 impl Discriminator for QueueAccountData {
     const DISCRIMINATOR: [u8; 8] = [217, 194, 55, 127, 184, 83, 138, 1];
 }
+impl_account_parse!(QueueAccountData);
 
 impl Owner for QueueAccountData {
     fn owner() -> Pubkey {
@@ -114,19 +115,7 @@ impl QueueAccountData {
         let data = attestation_queue_account_info
             .try_borrow_data()
             .map_err(|_| OnDemandError::AccountBorrowError)?;
-        if data.len() < QueueAccountData::discriminator().len() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        let mut disc_bytes = [0u8; 8];
-        disc_bytes.copy_from_slice(&data[..8]);
-        if disc_bytes != QueueAccountData::discriminator() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        Ok(Ref::map(data, |data| {
-            bytemuck::from_bytes(&data[8..std::mem::size_of::<QueueAccountData>() + 8])
-        }))
+        <QueueAccountData as AccountParse>::parse_ref(data)
     }
 
     /// Returns the deserialized Switchboard AttestationQueue account
@@ -143,19 +132,7 @@ impl QueueAccountData {
     /// let attestation_queue = QueueAccountData::new(attestation_queue_account_info.try_borrow_data()?)?;
     /// ```
     pub fn new_from_bytes(data: &[u8]) -> Result<&QueueAccountData, OnDemandError> {
-        if data.len() < QueueAccountData::discriminator().len() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        let mut disc_bytes = [0u8; 8];
-        disc_bytes.copy_from_slice(&data[..8]);
-        if disc_bytes != QueueAccountData::discriminator() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        Ok(bytemuck::from_bytes(
-            &data[8..std::mem::size_of::<QueueAccountData>() + 8],
-        ))
+        <QueueAccountData as AccountParse>::parse_bytes(data)
     }
 
     pub fn has_mr_enclave(&self, mr_enclave: &[u8]) -> bool {
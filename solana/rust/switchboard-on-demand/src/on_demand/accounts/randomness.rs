@@ -35,6 +35,8 @@ impl Owner for RandomnessAccountData {
     }
 }
 
+impl_account_parse!(RandomnessAccountData);
+
 cfg_client! {
     impl_account_deserialize!(RandomnessAccountData);
 }
@@ -57,19 +59,7 @@ impl RandomnessAccountData {
     pub fn parse<'info>(
         data: Ref<'info, &mut [u8]>,
     ) -> std::result::Result<Ref<'info, Self>, OnDemandError> {
-        if data.len() < Self::discriminator().len() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        let mut disc_bytes = [0u8; 8];
-        disc_bytes.copy_from_slice(&data[..8]);
-        if disc_bytes != Self::discriminator() {
-            return Err(OnDemandError::InvalidDiscriminator);
-        }
-
-        Ok(Ref::map(data, |data: &&mut [u8]| {
-            bytemuck::from_bytes(&data[8..std::mem::size_of::<Self>() + 8])
-        }))
+        <Self as AccountParse>::parse_ref(data)
     }
 
     cfg_client! {
@@ -4,7 +4,7 @@ use solana_program::pubkey::Pubkey;
 use crate::anchor_traits::*;
 #[allow(unused_imports)]
 use crate::impl_account_deserialize;
-use crate::{cfg_client, get_sb_program_id};
+use crate::{cfg_client, get_sb_program_id, impl_account_parse};
 
 const STATE_SEED: &[u8] = b"STATE";
 
@@ -49,6 +49,7 @@ cfg_client! {
 impl Discriminator for State {
     const DISCRIMINATOR: [u8; 8] = [216, 146, 107, 94, 104, 75, 182, 177];
 }
+impl_account_parse!(State);
 
 impl Owner for State {
     fn owner() -> Pubkey {
@@ -64,6 +64,15 @@ pub enum OnDemandError {
     AddressLookupTableDeserializeError,
     InvalidSize,
     StaleResult,
+    ExcessiveStdDev,
+    ExcessiveRange,
+    /// The current result has fewer samples than the caller's
+    /// `PriceReadPolicy::min_samples`. Unlike `NotEnoughSamples`, which means
+    /// too few valid submissions remain to compute a value at all, the result
+    /// exists but the caller's policy rejects it.
+    InsufficientSamples,
+    MaxAgeExceeded,
+    InvalidOwner,
 }
 
 impl StdError for OnDemandError {
@@ -0,0 +1,275 @@
+use solana_program::clock::Clock;
+
+use crate::*;
+
+/// A feed participating in a `FeedBasket` along with its weight, expressed
+/// as an 18 decimal fixed point value.
+#[derive(Clone, Copy, Debug)]
+pub struct BasketFeed<'a> {
+    pub feed: &'a PullFeedAccountData,
+    pub weight: i128,
+}
+
+/// The reason a `FeedBasket` could not produce a value.
+#[derive(Clone, Debug)]
+pub enum FeedBasketError {
+    /// The basket holds no feeds, or not the number the operation requires.
+    InvalidFeedCount,
+    /// One or more feeds failed their freshness or variance checks. Holds the
+    /// index of every failing feed in the basket with its error.
+    Feeds(Vec<(usize, OnDemandError)>),
+    /// The aggregation overflowed or divided by zero.
+    Arithmetic,
+}
+
+impl From<FeedBasketError> for OnDemandError {
+    fn from(err: FeedBasketError) -> Self {
+        match err {
+            FeedBasketError::InvalidFeedCount => OnDemandError::NotEnoughSamples,
+            FeedBasketError::Feeds(errors) => errors
+                .into_iter()
+                .next()
+                .map_or(OnDemandError::Generic, |(_, err)| err),
+            FeedBasketError::Arithmetic => OnDemandError::IntegerOverflowError,
+        }
+    }
+}
+
+/// Aggregates several pull feeds into a single basket or index price.
+///
+/// Every feed is read through `PullFeedAccountData::read_price` with the
+/// basket's policy before aggregating. Values and results use the feeds'
+/// 18 decimal `PRECISION` i128 representation.
+///
+/// # Examples
+///
+/// ```ignore
+/// let basket = FeedBasket::new(policy)
+///     .with_feed(&btc_usd, 10_i128.pow(18))
+///     .with_feed(&eth_usd, 10_i128.pow(18));
+/// let btc_eth = basket.ratio(&clock)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FeedBasket<'a> {
+    pub feeds: Vec<BasketFeed<'a>>,
+    pub policy: PriceReadPolicy,
+}
+
+impl<'a> FeedBasket<'a> {
+    pub fn new(policy: PriceReadPolicy) -> Self {
+        Self {
+            feeds: Vec::new(),
+            policy,
+        }
+    }
+
+    /// Adds a feed to the basket with an 18 decimal fixed point weight.
+    pub fn with_feed(mut self, feed: &'a PullFeedAccountData, weight: i128) -> Self {
+        self.feeds.push(BasketFeed { feed, weight });
+        self
+    }
+
+    /// Reads the current value of every feed, collecting the errors of all
+    /// feeds that fail the basket's policy.
+    pub fn values(&self, clock: &Clock) -> Result<Vec<i128>, FeedBasketError> {
+        if self.feeds.is_empty() {
+            return Err(FeedBasketError::InvalidFeedCount);
+        }
+        let mut values = Vec::with_capacity(self.feeds.len());
+        let mut errors = Vec::new();
+        for (idx, basket_feed) in self.feeds.iter().enumerate() {
+            match basket_feed.feed.read_price(clock, &self.policy) {
+                Ok(reading) => values.push(reading.value),
+                Err(err) => errors.push((idx, err)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(FeedBasketError::Feeds(errors));
+        }
+        Ok(values)
+    }
+
+    /// The sum of every feed value multiplied by its weight.
+    pub fn weighted_sum(&self, clock: &Clock) -> Result<i128, FeedBasketError> {
+        let values = self.values(clock)?;
+        values
+            .iter()
+            .zip(self.feeds.iter())
+            .try_fold(0i128, |sum, (value, basket_feed)| {
+                let weighted = checked_mul_div(*value, basket_feed.weight, precision_scale())?;
+                sum.checked_add(weighted)
+            })
+            .ok_or(FeedBasketError::Arithmetic)
+    }
+
+    /// The product of every feed value, ignoring weights.
+    pub fn product(&self, clock: &Clock) -> Result<i128, FeedBasketError> {
+        let values = self.values(clock)?;
+        values
+            .iter()
+            .skip(1)
+            .try_fold(values[0], |product, value| {
+                checked_mul_div(product, *value, precision_scale())
+            })
+            .ok_or(FeedBasketError::Arithmetic)
+    }
+
+    /// The value of the first feed divided by the value of the second, e.g.
+    /// BTC/ETH from BTC/USD and ETH/USD. Requires exactly two feeds.
+    pub fn ratio(&self, clock: &Clock) -> Result<i128, FeedBasketError> {
+        if self.feeds.len() != 2 {
+            return Err(FeedBasketError::InvalidFeedCount);
+        }
+        let values = self.values(clock)?;
+        checked_mul_div(values[0], precision_scale(), values[1]).ok_or(FeedBasketError::Arithmetic)
+    }
+
+    /// The lower bound median of the feed values, ignoring weights.
+    pub fn median(&self, clock: &Clock) -> Result<i128, FeedBasketError> {
+        let mut values = self.values(clock)?;
+        lower_bound_median(&mut values).ok_or(FeedBasketError::InvalidFeedCount)
+    }
+}
+
+fn precision_scale() -> i128 {
+    10_i128.pow(PRECISION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOT: u64 = 1_000;
+
+    fn scaled(value: i128) -> i128 {
+        value * precision_scale()
+    }
+
+    fn feed(value: i128) -> PullFeedAccountData {
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.max_staleness = 100;
+        feed.result.value = value;
+        feed.result.num_samples = 1;
+        feed.result.slot = SLOT;
+        feed.submissions[0].slot = SLOT;
+        feed.submissions[0].landed_at = SLOT;
+        feed
+    }
+
+    fn clock() -> Clock {
+        Clock {
+            slot: SLOT + 10,
+            ..Clock::default()
+        }
+    }
+
+    fn basket<'a>(feeds: &'a [PullFeedAccountData], weights: &[i128]) -> FeedBasket<'a> {
+        feeds
+            .iter()
+            .zip(weights)
+            .fold(FeedBasket::default(), |basket, (feed, weight)| {
+                basket.with_feed(feed, *weight)
+            })
+    }
+
+    #[test]
+    fn combines_feed_values() {
+        let feeds = [feed(scaled(2)), feed(scaled(3))];
+        let weighted = basket(&feeds, &[scaled(1) / 2, scaled(2)]);
+        assert_eq!(weighted.weighted_sum(&clock()).unwrap(), scaled(7));
+        assert_eq!(weighted.product(&clock()).unwrap(), scaled(6));
+        // 2 / 3, rounded towards zero
+        assert_eq!(weighted.ratio(&clock()).unwrap(), 666_666_666_666_666_666);
+
+        let feeds = [feed(scaled(5)), feed(scaled(1)), feed(scaled(3))];
+        let unweighted = basket(&feeds, &[0, 0, 0]);
+        assert_eq!(unweighted.median(&clock()).unwrap(), scaled(3));
+        assert_eq!(unweighted.product(&clock()).unwrap(), scaled(15));
+        assert_eq!(unweighted.weighted_sum(&clock()).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_wrong_feed_counts() {
+        let empty = FeedBasket::default();
+        assert!(matches!(
+            empty.weighted_sum(&clock()),
+            Err(FeedBasketError::InvalidFeedCount)
+        ));
+        assert!(matches!(
+            empty.product(&clock()),
+            Err(FeedBasketError::InvalidFeedCount)
+        ));
+        assert!(matches!(
+            empty.median(&clock()),
+            Err(FeedBasketError::InvalidFeedCount)
+        ));
+        let feeds = [feed(scaled(1)), feed(scaled(2)), feed(scaled(3))];
+        assert!(matches!(
+            basket(&feeds, &[0, 0, 0]).ratio(&clock()),
+            Err(FeedBasketError::InvalidFeedCount)
+        ));
+        assert!(matches!(
+            basket(&feeds[..1], &[0]).ratio(&clock()),
+            Err(FeedBasketError::InvalidFeedCount)
+        ));
+    }
+
+    #[test]
+    fn reports_overflow_and_division_by_zero() {
+        let huge = scaled(100_000_000_000_000_000_000);
+        let feeds = [feed(huge), feed(huge)];
+        let huge_basket = basket(&feeds, &[scaled(1), scaled(1)]);
+        assert!(matches!(
+            huge_basket.weighted_sum(&clock()),
+            Err(FeedBasketError::Arithmetic)
+        ));
+        assert!(matches!(
+            huge_basket.product(&clock()),
+            Err(FeedBasketError::Arithmetic)
+        ));
+
+        let feeds = [feed(scaled(1)), feed(0)];
+        let err = basket(&feeds, &[0, 0]).ratio(&clock()).unwrap_err();
+        assert!(matches!(err, FeedBasketError::Arithmetic));
+        assert!(matches!(
+            OnDemandError::from(err),
+            OnDemandError::IntegerOverflowError
+        ));
+    }
+
+    #[test]
+    fn collects_the_error_of_every_failing_feed() {
+        let mut stale = feed(scaled(2));
+        stale.result.slot = SLOT - 200;
+        let never_updated: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        let feeds = [feed(scaled(1)), stale, never_updated];
+
+        let err = basket(&feeds, &[0, 0, 0]).median(&clock()).unwrap_err();
+        let FeedBasketError::Feeds(errors) = err.clone() else {
+            panic!("unexpected error: {:?}", err);
+        };
+        let indices: Vec<usize> = errors.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert!(errors
+            .iter()
+            .all(|(_, err)| matches!(err, OnDemandError::StaleResult)));
+        assert!(matches!(
+            OnDemandError::from(err),
+            OnDemandError::StaleResult
+        ));
+
+        // The basket's policy applies to every feed.
+        let policy = PriceReadPolicy {
+            min_samples: Some(2),
+            ..Default::default()
+        };
+        let strict = FeedBasket::new(policy).with_feed(&feeds[0], 0);
+        let Err(FeedBasketError::Feeds(errors)) = strict.weighted_sum(&clock()) else {
+            panic!("expected a feed error");
+        };
+        assert!(matches!(
+            errors[..],
+            [(0, OnDemandError::InsufficientSamples)]
+        ));
+    }
+}
@@ -5,9 +5,13 @@ pub mod oracle_reset_lut;
 pub mod oracle_set_configs;
 pub mod oracle_sync_lut;
 pub mod permission_set;
+pub mod pull_feed_close;
+pub mod pull_feed_init;
+pub mod pull_feed_set_configs;
 pub mod queue_garbage_collect;
 pub mod queue_pay_subsidy;
 pub mod randomness_commit;
+pub mod randomness_reveal;
 pub use guardian_quote_verify::*;
 pub use oracle_heartbeat::*;
 pub use oracle_heartbeat_v2::*;
@@ -15,6 +19,10 @@ pub use oracle_reset_lut::*;
 pub use oracle_set_configs::*;
 pub use oracle_sync_lut::*;
 pub use permission_set::*;
+pub use pull_feed_close::*;
+pub use pull_feed_init::*;
+pub use pull_feed_set_configs::*;
 pub use queue_garbage_collect::*;
 pub use queue_pay_subsidy::*;
 pub use randomness_commit::*;
+pub use randomness_reveal::*;
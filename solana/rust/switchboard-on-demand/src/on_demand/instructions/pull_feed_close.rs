@@ -0,0 +1,107 @@
+use borsh::BorshSerialize;
+use solana_program::address_lookup_table::program::ID as address_lookup_table_program;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+
+use crate::anchor_traits::*;
+use crate::prelude::*;
+use crate::{find_lut_of, find_lut_signer, get_sb_program_id};
+
+pub struct PullFeedClose {}
+
+#[derive(Clone, BorshSerialize, Debug)]
+pub struct PullFeedCloseParams {}
+
+impl InstructionData for PullFeedCloseParams {}
+
+impl Discriminator for PullFeedClose {
+    const DISCRIMINATOR: [u8; 8] = [19, 134, 50, 142, 177, 215, 196, 83];
+}
+impl Discriminator for PullFeedCloseParams {
+    const DISCRIMINATOR: [u8; 8] = PullFeedClose::DISCRIMINATOR;
+}
+
+pub struct PullFeedCloseAccounts {
+    pub pull_feed: Pubkey,
+    /// The feed's `lut_slot`, used to derive its address lookup table.
+    pub lut_slot: u64,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+}
+impl ToAccountMetas for PullFeedCloseAccounts {
+    fn to_account_metas(&self, _: Option<bool>) -> Vec<AccountMeta> {
+        let wsol_mint = spl_token::native_mint::ID;
+        let lut_signer = find_lut_signer(&self.pull_feed);
+        vec![
+            AccountMeta::new(self.pull_feed, false),
+            AccountMeta::new(
+                get_associated_token_address(&self.pull_feed, &wsol_mint),
+                false,
+            ),
+            AccountMeta::new(find_lut_of(&lut_signer, self.lut_slot), false),
+            AccountMeta::new_readonly(lut_signer, false),
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(State::get_pda(), false),
+            AccountMeta::new_readonly(self.authority, true),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(address_lookup_table_program, false),
+        ]
+    }
+}
+
+impl PullFeedClose {
+    /// Builds the `pull_feed_close` instruction, returning the feed's rent
+    /// to the payer.
+    pub fn build_ix(accounts: PullFeedCloseAccounts) -> Result<Instruction, OnDemandError> {
+        let pid = if cfg!(feature = "devnet") {
+            get_sb_program_id("devnet")
+        } else {
+            get_sb_program_id("mainnet")
+        };
+        Ok(crate::utils::build_ix(
+            &pid,
+            &accounts,
+            &PullFeedCloseParams {},
+        ))
+    }
+
+    /// Builds the close instruction for a loaded feed, signed by its
+    /// authority.
+    pub fn build_ix_for(
+        pull_feed: Pubkey,
+        data: &PullFeedAccountData,
+        payer: Pubkey,
+    ) -> Result<Instruction, OnDemandError> {
+        Self::build_ix(PullFeedCloseAccounts {
+            pull_feed,
+            lut_slot: data.lut_slot,
+            authority: data.authority,
+            payer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_ix_for_sends_only_the_discriminator() {
+        let mut data: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        data.authority = Pubkey::new_unique();
+        data.lut_slot = 7;
+        let (pull_feed, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = PullFeedClose::build_ix_for(pull_feed, &data, payer).unwrap();
+
+        assert_eq!(ix.data, [19, 134, 50, 142, 177, 215, 196, 83]);
+        assert_eq!(ix.accounts.len(), 11);
+        assert_eq!(ix.accounts[0].pubkey, pull_feed);
+        let lut_signer = find_lut_signer(&pull_feed);
+        assert_eq!(ix.accounts[2].pubkey, find_lut_of(&lut_signer, 7));
+        assert_eq!(ix.accounts[3].pubkey, lut_signer);
+        assert!(ix.accounts[4].is_signer && ix.accounts[4].pubkey == payer);
+        assert!(ix.accounts[6].is_signer && ix.accounts[6].pubkey == data.authority);
+    }
+}
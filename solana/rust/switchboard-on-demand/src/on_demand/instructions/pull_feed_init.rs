@@ -0,0 +1,185 @@
+use borsh::BorshSerialize;
+use solana_program::address_lookup_table::program::ID as address_lookup_table_program;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_program;
+
+use crate::anchor_traits::*;
+use crate::prelude::*;
+use crate::{find_lut_of, find_lut_signer, get_sb_program_id};
+
+pub struct PullFeedInit {}
+
+#[derive(Clone, BorshSerialize, Debug)]
+pub struct PullFeedInitParams {
+    pub feed_hash: [u8; 32],
+    /// The maximum variance allowed between oracle responses, scaled by 1e9.
+    pub max_variance: u64,
+    pub min_responses: u32,
+    pub name: [u8; 32],
+    pub recent_slot: u64,
+    /// Deprecated, always zeroed.
+    pub ipfs_hash: [u8; 32],
+    pub min_sample_size: u8,
+    pub max_staleness: u32,
+    pub permit_write_by_authority: Option<bool>,
+}
+
+impl InstructionData for PullFeedInitParams {}
+
+impl Discriminator for PullFeedInit {
+    const DISCRIMINATOR: [u8; 8] = [198, 130, 53, 198, 235, 61, 143, 40];
+}
+impl Discriminator for PullFeedInitParams {
+    const DISCRIMINATOR: [u8; 8] = PullFeedInit::DISCRIMINATOR;
+}
+
+pub struct PullFeedInitArgs {
+    pub pull_feed: Pubkey,
+    pub queue: Pubkey,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub name: String,
+    pub feed_hash: [u8; 32],
+    pub max_variance: u64,
+    pub min_responses: u32,
+    pub min_sample_size: u8,
+    pub max_staleness: u32,
+    pub permit_write_by_authority: Option<bool>,
+    pub recent_slot: u64,
+}
+pub struct PullFeedInitAccounts {
+    pub pull_feed: Pubkey,
+    pub queue: Pubkey,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub recent_slot: u64,
+}
+impl ToAccountMetas for PullFeedInitAccounts {
+    fn to_account_metas(&self, _: Option<bool>) -> Vec<AccountMeta> {
+        let wsol_mint = spl_token::native_mint::ID;
+        let lut_signer = find_lut_signer(&self.pull_feed);
+        vec![
+            AccountMeta::new(self.pull_feed, true),
+            AccountMeta::new_readonly(self.queue, false),
+            AccountMeta::new_readonly(self.authority, false),
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(State::get_pda(), false),
+            AccountMeta::new(
+                get_associated_token_address(&self.pull_feed, &wsol_mint),
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(wsol_mint, false),
+            AccountMeta::new_readonly(lut_signer, false),
+            AccountMeta::new(find_lut_of(&lut_signer, self.recent_slot), false),
+            AccountMeta::new_readonly(address_lookup_table_program, false),
+        ]
+    }
+}
+
+impl PullFeedInit {
+    /// Builds the `pull_feed_init` instruction. The pull feed account must
+    /// sign the transaction alongside the payer.
+    pub fn build_ix(args: PullFeedInitArgs) -> Result<Instruction, OnDemandError> {
+        let pid = if cfg!(feature = "devnet") {
+            get_sb_program_id("devnet")
+        } else {
+            get_sb_program_id("mainnet")
+        };
+        Ok(crate::utils::build_ix(
+            &pid,
+            &PullFeedInitAccounts {
+                pull_feed: args.pull_feed,
+                queue: args.queue,
+                authority: args.authority,
+                payer: args.payer,
+                recent_slot: args.recent_slot,
+            },
+            &PullFeedInitParams {
+                feed_hash: args.feed_hash,
+                max_variance: args.max_variance,
+                min_responses: args.min_responses,
+                name: feed_name_bytes(&args.name)?,
+                recent_slot: args.recent_slot,
+                ipfs_hash: [0; 32],
+                min_sample_size: args.min_sample_size,
+                max_staleness: args.max_staleness,
+                permit_write_by_authority: args.permit_write_by_authority,
+            },
+        ))
+    }
+}
+
+/// Pads a feed name with null bytes to the 32 bytes stored on the account.
+pub fn feed_name_bytes(name: &str) -> Result<[u8; 32], OnDemandError> {
+    let bytes = name.as_bytes();
+    if bytes.len() > 32 {
+        return Err(OnDemandError::InvalidData);
+    }
+    let mut out = [0u8; 32];
+    out[..bytes.len()].copy_from_slice(bytes);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> PullFeedInitArgs {
+        PullFeedInitArgs {
+            pull_feed: Pubkey::new_unique(),
+            queue: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            name: "BTC/USD".to_string(),
+            feed_hash: [1; 32],
+            max_variance: 2,
+            min_responses: 3,
+            min_sample_size: 4,
+            max_staleness: 5,
+            permit_write_by_authority: Some(true),
+            recent_slot: 6,
+        }
+    }
+
+    #[test]
+    fn build_ix_serializes_params_in_field_order() {
+        let args = args();
+        let (pull_feed, payer) = (args.pull_feed, args.payer);
+        let ix = PullFeedInit::build_ix(args).unwrap();
+
+        let mut data = vec![198, 130, 53, 198, 235, 61, 143, 40];
+        data.extend_from_slice(&[1; 32]);
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&feed_name_bytes("BTC/USD").unwrap());
+        data.extend_from_slice(&6u64.to_le_bytes());
+        data.extend_from_slice(&[0; 32]);
+        data.push(4);
+        data.extend_from_slice(&5u32.to_le_bytes());
+        data.extend_from_slice(&[1, 1]);
+        assert_eq!(ix.data, data);
+
+        assert_eq!(ix.accounts.len(), 13);
+        assert_eq!(ix.accounts[0].pubkey, pull_feed);
+        assert!(ix.accounts[0].is_signer && ix.accounts[3].is_signer);
+        assert_eq!(ix.accounts[3].pubkey, payer);
+        let lut_signer = find_lut_signer(&pull_feed);
+        assert_eq!(ix.accounts[10].pubkey, lut_signer);
+        assert_eq!(ix.accounts[11].pubkey, find_lut_of(&lut_signer, 6));
+    }
+
+    #[test]
+    fn feed_names_are_padded_to_32_bytes() {
+        let name = feed_name_bytes("SOL").unwrap();
+        assert_eq!(name[..3], *b"SOL");
+        assert_eq!(name[3..], [0; 29]);
+        assert!(feed_name_bytes(&"a".repeat(32)).is_ok());
+        assert!(matches!(
+            feed_name_bytes(&"a".repeat(33)),
+            Err(OnDemandError::InvalidData)
+        ));
+    }
+}
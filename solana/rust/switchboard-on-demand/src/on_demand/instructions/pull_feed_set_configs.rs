@@ -0,0 +1,118 @@
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+
+use crate::anchor_traits::*;
+use crate::get_sb_program_id;
+use crate::prelude::*;
+
+pub struct PullFeedSetConfigs {}
+
+/// New configuration for a pull feed. Fields left as `None` are unchanged.
+#[derive(Clone, BorshSerialize, Debug, Default)]
+pub struct PullFeedSetConfigsParams {
+    pub feed_hash: Option<[u8; 32]>,
+    pub authority: Option<Pubkey>,
+    /// The maximum variance allowed between oracle responses, scaled by 1e9.
+    pub max_variance: Option<u64>,
+    pub min_responses: Option<u32>,
+    pub name: Option<[u8; 32]>,
+    /// Deprecated, always `None`.
+    pub ipfs_hash: Option<[u8; 32]>,
+    pub min_sample_size: Option<u8>,
+    pub max_staleness: Option<u32>,
+    pub permit_write_by_authority: Option<bool>,
+}
+
+impl InstructionData for PullFeedSetConfigsParams {}
+
+impl Discriminator for PullFeedSetConfigs {
+    const DISCRIMINATOR: [u8; 8] = [217, 45, 11, 246, 64, 26, 82, 168];
+}
+impl Discriminator for PullFeedSetConfigsParams {
+    const DISCRIMINATOR: [u8; 8] = PullFeedSetConfigs::DISCRIMINATOR;
+}
+
+pub struct PullFeedSetConfigsAccounts {
+    pub pull_feed: Pubkey,
+    pub authority: Pubkey,
+}
+impl ToAccountMetas for PullFeedSetConfigsAccounts {
+    fn to_account_metas(&self, _: Option<bool>) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.pull_feed, false),
+            AccountMeta::new_readonly(self.authority, true),
+        ]
+    }
+}
+
+impl PullFeedSetConfigs {
+    /// Builds the `pull_feed_set_configs` instruction, signed by the feed's
+    /// current authority.
+    pub fn build_ix(
+        accounts: PullFeedSetConfigsAccounts,
+        params: PullFeedSetConfigsParams,
+    ) -> Result<Instruction, OnDemandError> {
+        let pid = if cfg!(feature = "devnet") {
+            get_sb_program_id("devnet")
+        } else {
+            get_sb_program_id("mainnet")
+        };
+        Ok(crate::utils::build_ix(&pid, &accounts, &params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_ix(params: PullFeedSetConfigsParams) -> Instruction {
+        let accounts = PullFeedSetConfigsAccounts {
+            pull_feed: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+        };
+        PullFeedSetConfigs::build_ix(accounts, params).unwrap()
+    }
+
+    #[test]
+    fn unchanged_configs_serialize_as_none() {
+        let ix = build_ix(PullFeedSetConfigsParams::default());
+        let mut data = vec![217, 45, 11, 246, 64, 26, 82, 168];
+        data.extend_from_slice(&[0; 9]);
+        assert_eq!(ix.data, data);
+        assert!(!ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+        assert!(ix.accounts[1].is_signer && !ix.accounts[1].is_writable);
+    }
+
+    #[test]
+    fn set_configs_serialize_in_field_order() {
+        let authority = Pubkey::new_unique();
+        let ix = build_ix(PullFeedSetConfigsParams {
+            feed_hash: Some([1; 32]),
+            authority: Some(authority),
+            max_variance: Some(2),
+            min_responses: None,
+            name: Some([3; 32]),
+            ipfs_hash: None,
+            min_sample_size: Some(4),
+            max_staleness: Some(5),
+            permit_write_by_authority: Some(false),
+        });
+
+        let mut data = vec![217, 45, 11, 246, 64, 26, 82, 168];
+        data.push(1);
+        data.extend_from_slice(&[1; 32]);
+        data.push(1);
+        data.extend_from_slice(authority.as_ref());
+        data.push(1);
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.push(0);
+        data.push(1);
+        data.extend_from_slice(&[3; 32]);
+        data.push(0);
+        data.extend_from_slice(&[1, 4]);
+        data.push(1);
+        data.extend_from_slice(&5u32.to_le_bytes());
+        data.extend_from_slice(&[1, 0]);
+        assert_eq!(ix.data, data);
+    }
+}
@@ -124,3 +124,24 @@ fn ix_discriminator(name: &str) -> [u8; 8] {
     sighash.copy_from_slice(&solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8]);
     sighash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_ix_sends_only_the_discriminator() {
+        assert_eq!(
+            RandomnessCommit::DISCRIMINATOR,
+            ix_discriminator("randomness_commit")
+        );
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let ix = RandomnessCommit::build_ix(keys[0], keys[1], keys[2], keys[3]).unwrap();
+        assert_eq!(ix.data, RandomnessCommit::DISCRIMINATOR);
+        let accounts: Vec<Pubkey> = ix.accounts.iter().map(|x| x.pubkey).collect();
+        assert_eq!(
+            accounts,
+            vec![keys[0], keys[1], keys[2], slot_hashes::ID, keys[3]]
+        );
+    }
+}
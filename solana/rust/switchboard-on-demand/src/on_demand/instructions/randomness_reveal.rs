@@ -0,0 +1,157 @@
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::slot_hashes;
+
+use crate::anchor_traits::*;
+use crate::get_sb_program_id;
+use crate::prelude::*;
+
+pub const ORACLE_RANDOMNESS_STATS_SEED: &[u8] = b"OracleRandomnessStats";
+
+pub struct RandomnessReveal {}
+
+#[derive(Clone, BorshSerialize, Debug)]
+pub struct RandomnessRevealParams {
+    pub signature: [u8; 64],
+    pub recovery_id: u8,
+    pub value: [u8; 32],
+}
+
+impl InstructionData for RandomnessRevealParams {}
+
+impl Discriminator for RandomnessRevealParams {
+    const DISCRIMINATOR: [u8; 8] = RandomnessReveal::DISCRIMINATOR;
+}
+
+impl Discriminator for RandomnessReveal {
+    const DISCRIMINATOR: [u8; 8] = [197, 181, 187, 10, 30, 58, 20, 73];
+}
+
+pub struct RandomnessRevealAccounts {
+    pub randomness: Pubkey,
+    pub oracle: Pubkey,
+    pub queue: Pubkey,
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+}
+impl ToAccountMetas for RandomnessRevealAccounts {
+    fn to_account_metas(&self, _: Option<bool>) -> Vec<AccountMeta> {
+        let wsol_mint = spl_token::native_mint::ID;
+        vec![
+            AccountMeta::new(self.randomness, false),
+            AccountMeta::new_readonly(self.oracle, false),
+            AccountMeta::new_readonly(self.queue, false),
+            AccountMeta::new(RandomnessReveal::stats_key(&self.oracle), false),
+            AccountMeta::new_readonly(self.authority, true),
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(slot_hashes::ID, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+            AccountMeta::new(
+                get_associated_token_address(&self.randomness, &wsol_mint),
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(wsol_mint, false),
+            AccountMeta::new_readonly(State::get_pda(), false),
+        ]
+    }
+}
+
+impl RandomnessReveal {
+    /// Builds the `randomness_reveal` instruction from the signed value
+    /// returned by the assigned oracle's gateway.
+    pub fn build_ix(
+        accounts: RandomnessRevealAccounts,
+        params: RandomnessRevealParams,
+    ) -> Result<Instruction, OnDemandError> {
+        let pid = if cfg!(feature = "devnet") {
+            get_sb_program_id("devnet")
+        } else {
+            get_sb_program_id("mainnet")
+        };
+        Ok(crate::utils::build_ix(&pid, &accounts, &params))
+    }
+
+    /// The oracle's randomness stats account, credited on every reveal.
+    pub fn stats_key(oracle: &Pubkey) -> Pubkey {
+        let pid = if cfg!(feature = "devnet") {
+            get_sb_program_id("devnet")
+        } else {
+            get_sb_program_id("mainnet")
+        };
+        Pubkey::find_program_address(&[ORACLE_RANDOMNESS_STATS_SEED, oracle.as_ref()], &pid).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discriminator_is_the_anchor_sighash() {
+        let hash = solana_program::hash::hash(b"global:randomness_reveal");
+        assert_eq!(RandomnessReveal::DISCRIMINATOR, hash.to_bytes()[..8]);
+        assert_eq!(
+            RandomnessRevealParams::DISCRIMINATOR,
+            RandomnessReveal::DISCRIMINATOR
+        );
+    }
+
+    #[test]
+    fn build_ix_serializes_params_and_accounts_in_order() {
+        let accounts = RandomnessRevealAccounts {
+            randomness: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            queue: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+        };
+        let (randomness, oracle, queue, authority, payer) = (
+            accounts.randomness,
+            accounts.oracle,
+            accounts.queue,
+            accounts.authority,
+            accounts.payer,
+        );
+        let params = RandomnessRevealParams {
+            signature: [1; 64],
+            recovery_id: 2,
+            value: [3; 32],
+        };
+        let ix = RandomnessReveal::build_ix(accounts, params).unwrap();
+
+        let mut data = RandomnessReveal::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[1; 64]);
+        data.push(2);
+        data.extend_from_slice(&[3; 32]);
+        assert_eq!(ix.data, data);
+
+        let wsol_mint = spl_token::native_mint::ID;
+        let expected = [
+            (randomness, false, true),
+            (oracle, false, false),
+            (queue, false, false),
+            (RandomnessReveal::stats_key(&oracle), false, true),
+            (authority, true, false),
+            (payer, true, true),
+            (slot_hashes::ID, false, false),
+            (solana_program::system_program::ID, false, false),
+            (
+                get_associated_token_address(&randomness, &wsol_mint),
+                false,
+                true,
+            ),
+            (spl_token::ID, false, false),
+            (spl_associated_token_account::ID, false, false),
+            (wsol_mint, false, false),
+            (State::get_pda(), false, false),
+        ];
+        let metas: Vec<(Pubkey, bool, bool)> = ix
+            .accounts
+            .iter()
+            .map(|x| (x.pubkey, x.is_signer, x.is_writable))
+            .collect();
+        assert_eq!(metas, expected);
+    }
+}
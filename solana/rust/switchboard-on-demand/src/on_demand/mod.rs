@@ -7,6 +7,12 @@ pub use accounts::*;
 pub mod instructions;
 pub use instructions::*;
 
+pub mod feed_basket;
+pub use feed_basket::*;
+
+pub mod randomness_utils;
+pub use randomness_utils::*;
+
 pub mod types;
 use solana_program::pubkey;
 pub use types::*;
@@ -0,0 +1,171 @@
+use solana_program::clock::Clock;
+use solana_program::hash::hashv;
+
+use crate::{OnDemandError, RandomnessAccountData};
+
+const STREAM_DOMAIN: &[u8] = b"switchboard:randomness:stream";
+
+/// A deterministic stream of random numbers derived from a revealed
+/// randomness value.
+///
+/// Blocks are produced as `sha256(seed || counter)`, where the seed is the
+/// value hashed together with a caller chosen domain. Streams with different
+/// domains are independent of each other, so one revealed value can drive
+/// several unrelated draws. Nothing here allocates, and hashing goes through
+/// the sha256 syscall on-chain.
+///
+/// # Examples
+///
+/// ```ignore
+/// let value = randomness.get_value(&clock)?;
+/// let mut dice = RandomStream::with_domain(&value, b"dice");
+/// let roll = dice.next_u64_in_range(1, 7).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomStream {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    offset: usize,
+}
+
+impl RandomStream {
+    /// The default stream of `value`.
+    pub fn new(value: &[u8; 32]) -> Self {
+        Self::with_domain(value, &[])
+    }
+
+    /// An independent stream of `value` separated by `domain`.
+    pub fn with_domain(value: &[u8; 32], domain: &[u8]) -> Self {
+        let seed = hashv(&[STREAM_DOMAIN, domain, value]).to_bytes();
+        Self {
+            seed,
+            counter: 0,
+            block: [0; 32],
+            offset: 32,
+        }
+    }
+
+    /// The `index`th independent stream of `value`.
+    pub fn sub_stream(value: &[u8; 32], index: u64) -> Self {
+        Self::with_domain(value, &index.to_le_bytes())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        if self.offset + 8 > self.block.len() {
+            self.block = hashv(&[&self.seed, &self.counter.to_le_bytes()]).to_bytes();
+            self.counter += 1;
+            self.offset = 0;
+        }
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.block[self.offset..self.offset + 8]);
+        self.offset += 8;
+        u64::from_le_bytes(bytes)
+    }
+
+    /// A uniform value in `[0, n)` without modulo bias, using rejection
+    /// sampling. Returns `None` if `n` is zero.
+    pub fn next_u64_below(&mut self, n: u64) -> Option<u64> {
+        if n == 0 {
+            return None;
+        }
+        // values below `threshold` would over-represent the low residues
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return Some(value % n);
+            }
+        }
+    }
+
+    /// A uniform value in `[low, high)`. Returns `None` if the range is empty.
+    pub fn next_u64_in_range(&mut self, low: u64, high: u64) -> Option<u64> {
+        let span = high.checked_sub(low).filter(|span| *span > 0)?;
+        Some(low + self.next_u64_below(span)?)
+    }
+
+    /// Picks an index with probability proportional to its weight. Returns
+    /// `None` if the weights are empty, all zero or overflow a u64.
+    pub fn weighted_choice(&mut self, weights: &[u64]) -> Option<usize> {
+        let total = weights
+            .iter()
+            .try_fold(0u64, |sum, weight| sum.checked_add(*weight))?;
+        let mut target = self.next_u64_below(total)?;
+        for (idx, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                return Some(idx);
+            }
+            target -= weight;
+        }
+        None
+    }
+
+    /// Shuffles `items` in place with an unbiased Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            // i + 1 is never zero
+            let j = self.next_u64_below(i as u64 + 1).unwrap_or(0) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+impl RandomnessAccountData {
+    /// A `RandomStream` over the revealed value, failing like `get_value` if
+    /// the value was not revealed in the current slot.
+    pub fn stream(&self, clock: &Clock, domain: &[u8]) -> Result<RandomStream, OnDemandError> {
+        let value = self.get_value(clock)?;
+        Ok(RandomStream::with_domain(&value, domain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUE: [u8; 32] = [7; 32];
+
+    #[test]
+    fn streams_are_deterministic_and_domain_separated() {
+        let mut a = RandomStream::new(&VALUE);
+        let mut b = RandomStream::new(&VALUE);
+        let mut c = RandomStream::sub_stream(&VALUE, 1);
+        let a_words: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let b_words: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        let c_words: Vec<u64> = (0..8).map(|_| c.next_u64()).collect();
+        assert_eq!(a_words, b_words);
+        assert_ne!(a_words, c_words);
+    }
+
+    #[test]
+    fn ranges_stay_in_bounds() {
+        let mut stream = RandomStream::new(&VALUE);
+        for _ in 0..1_000 {
+            let roll = stream.next_u64_in_range(1, 7).unwrap();
+            assert!((1..7).contains(&roll));
+        }
+        assert_eq!(stream.next_u64_below(0), None);
+        assert_eq!(stream.next_u64_in_range(5, 5), None);
+    }
+
+    #[test]
+    fn weighted_choice_skips_zero_weights() {
+        let mut stream = RandomStream::new(&VALUE);
+        for _ in 0..100 {
+            assert_eq!(stream.weighted_choice(&[0, 3, 0]), Some(1));
+        }
+        assert_eq!(stream.weighted_choice(&[0, 0]), None);
+        assert_eq!(stream.weighted_choice(&[u64::MAX, 1]), None);
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation() {
+        let mut stream = RandomStream::new(&VALUE);
+        let mut items = [0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        stream.shuffle(&mut items);
+        let mut sorted = items;
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+}